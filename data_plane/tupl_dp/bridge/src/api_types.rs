@@ -9,6 +9,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
 use crate::types::EnforcementDecision;
 
@@ -44,6 +45,9 @@ pub struct Risk {
 }
 
 /// Rate limit tracking context (v1.3).
+///
+/// Client-reported, so enforcement never trusts `call_count`; rate limit rules count
+/// calls in the data plane.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RateLimitContext {
     pub agent_id: String,
@@ -78,6 +82,35 @@ impl IntentEvent {
     pub fn layer_str(&self) -> Option<&str> {
         self.layer.as_deref()
     }
+
+    /// Agent identity used to select rules: the actor id, falling back to the
+    /// rate-limit context's agent. None when neither is set.
+    pub fn agent_id(&self) -> Option<&str> {
+        let rate_limited = self
+            .rate_limit_context
+            .as_ref()
+            .map(|context| context.agent_id.as_str());
+        [Some(self.actor.id.as_str()), rate_limited]
+            .into_iter()
+            .flatten()
+            .find(|agent_id| !agent_id.is_empty())
+    }
+
+    /// Labels the request carries in `context.labels`, matched against rule selector
+    /// tags. Non-string values are ignored.
+    pub fn context_labels(&self) -> HashMap<String, String> {
+        self.context
+            .as_ref()
+            .and_then(|context| context.get("labels"))
+            .and_then(Value::as_object)
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 /// Boundary-level evidence emitted by comparisons.
@@ -134,6 +167,53 @@ mod tests {
         assert_eq!(intent, reparsed);
     }
 
+    #[test]
+    fn intent_agent_id_prefers_actor_over_rate_limit_context() {
+        let mut intent: IntentEvent = serde_json::from_value(json!({
+            "id": "evt-123",
+            "schemaVersion": "v1.3",
+            "tenantId": "tenant-1",
+            "timestamp": 1699564800.0,
+            "actor": {"id": "agent-1", "type": "agent"},
+            "action": "read",
+            "resource": {"type": "database", "name": "users_db", "location": "cloud"},
+            "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+            "risk": {"authn": "required"},
+            "rate_limit_context": {"agent_id": "agent-2", "window_start": 1699564800.0, "call_count": 3}
+        }))
+        .unwrap();
+        assert_eq!(intent.agent_id(), Some("agent-1"));
+
+        intent.actor.id.clear();
+        assert_eq!(intent.agent_id(), Some("agent-2"));
+
+        intent.rate_limit_context = None;
+        assert_eq!(intent.agent_id(), None);
+    }
+
+    #[test]
+    fn intent_context_labels_keep_string_values() {
+        let mut intent: IntentEvent = serde_json::from_value(json!({
+            "id": "evt-123",
+            "schemaVersion": "v1.3",
+            "tenantId": "tenant-1",
+            "timestamp": 1699564800.0,
+            "actor": {"id": "agent-1", "type": "agent"},
+            "action": "read",
+            "resource": {"type": "database", "name": "users_db", "location": "cloud"},
+            "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+            "risk": {"authn": "required"},
+            "context": {"labels": {"env": "prod", "replicas": 3}}
+        }))
+        .unwrap();
+        let labels = intent.context_labels();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels["env"], "prod");
+
+        intent.context = None;
+        assert!(intent.context_labels().is_empty());
+    }
+
     #[test]
     fn comparison_result_roundtrip_matches_schema() {
         let value = json!({
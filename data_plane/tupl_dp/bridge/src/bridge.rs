@@ -1,10 +1,18 @@
-use crate::families::DesignBoundaryRule;
-use crate::rule_vector::RuleVector;
-use crate::types::{now_ms, RuleInstance, RuleMetadata};
+use crate::bundle::{BundleFailure, BundledRule, ImportMode, RuleBundle};
+use crate::families::rate_limit::RateLimiter;
+use crate::families::registry::{build_rule, validate_rule};
+use crate::rule_index::{IndexedRules, RuleIndex, RuleInstances, RuleMap};
+use crate::rule_vector::{AnchorPrecision, RuleVector};
+use crate::signing::SequenceClaim;
+use crate::storage::{
+    HotCache, HotCacheStats, MemoryRuleStore, RuleRow, RuleStore, SqliteRuleStore, StoreRead,
+    StoreTransaction, WarmStore,
+};
+use crate::types::{normalize_tenant, now_ms, PolicyType, RuleInstance, RuleMetadata};
 use parking_lot::{Mutex, RwLock};
-use rusqlite::{params, Connection};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 // ================================================================================================
@@ -14,6 +22,8 @@ use std::sync::Arc;
 /// Configuration for Bridge storage.
 #[derive(Clone, Debug)]
 pub struct StorageConfig {
+    /// Path to the memory-mapped anchor file (rebuilt from cold storage when invalid)
+    pub warm_storage_path: PathBuf,
     /// Path to cold storage database (SQLite)
     pub cold_storage_path: PathBuf,
 }
@@ -21,46 +31,86 @@ pub struct StorageConfig {
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
+            warm_storage_path: PathBuf::from("./var/data/warm_storage.bin"),
             cold_storage_path: PathBuf::from("./var/data/cold_storage.db"),
         }
     }
 }
 
 // ================================================================================================
-// SQLITE SCHEMA
+// BRIDGE STRUCTURE
 // ================================================================================================
 
-const SCHEMA: &str = "
-CREATE TABLE IF NOT EXISTS rules (
-    id          TEXT PRIMARY KEY,
-    tenant_id   TEXT NOT NULL,
-    layer       TEXT,
-    priority    INTEGER NOT NULL DEFAULT 0,
-    rule_json   TEXT NOT NULL,
-    anchors_bin BLOB NOT NULL,
-    status      TEXT NOT NULL DEFAULT 'active',
-    updated_at  REAL NOT NULL
-);
-";
+pub use crate::rule_index::RuleEntry;
+pub use crate::storage::{QuarantinedRule, RuleSetVersion, RuleStatus};
 
-// ================================================================================================
-// BRIDGE STRUCTURE
-// ================================================================================================
+/// Candidate rule set uploaded for inspection before it replaces the active set.
+#[derive(Debug)]
+struct StagedRuleSet {
+    rules: RuleMap,
+    /// Active version at the time staging started
+    base_version: u64,
+    staged_at: u64,
+}
+
+/// Registered agent labels by (tenant, agent).
+type AgentLabelMap = HashMap<(String, String), HashMap<String, String>>;
+
+/// Stored rows that could not be decoded, each with the reason.
+type Undecodable = Vec<(RuleRow, String)>;
+
+/// Tracks how current the in-memory rules are relative to cold storage.
+#[derive(Debug, Default)]
+struct SyncState {
+    /// `PRAGMA data_version` observed at the last change check
+    data_version: Option<i64>,
+    /// When the in-memory rules were last confirmed up to date with storage
+    synced_at: u64,
+}
 
 /// The Bridge is the root data structure for storing all rules in the data plane.
 ///
-/// Rules are stored in a single in-memory HashMap (the fast read path) backed by
-/// SQLite as the single source of truth for persistence. The HashMap is rebuilt
-/// from SQLite on startup and on InstallRules calls.
+/// Rules are stored in a single in-memory HashMap (the fast read path) backed by a
+/// `RuleStore` (SQLite by default) as the single source of truth for persistence. The
+/// HashMap is rebuilt from the store on startup and on InstallRules calls.
+///
+/// Anchors are too large to keep for every rule, so they live in a bounded LRU
+/// `HotCache`. A miss is served from the memory-mapped `WarmStore` snapshot when one is
+/// configured and the rule has not changed since, and from the store otherwise.
+///
+/// Every mutation commits a new rule-set version. Cold storage keeps an append-only
+/// history of what changed in each version (`rule_versions` + `rule_history`), so any
+/// past version can be reconstructed, diffed, or rolled back to.
+///
+/// Several processes may share one database. Each write first applies any history
+/// committed by other processes, and `sync_if_changed` lets a replica notice foreign
+/// writes cheaply, so the version number is the same across replicas once synced.
 #[derive(Debug)]
 pub struct Bridge {
     active_version: Arc<RwLock<u64>>,
-    staged_version: Arc<RwLock<Option<u64>>>,
     created_at: u64,
-    /// In-memory store: rule_id → (rule instance, rule vector)
-    rules: Arc<RwLock<HashMap<String, (Arc<dyn RuleInstance>, RuleVector)>>>,
-    /// SQLite connection for persistence
-    db: Arc<Mutex<Connection>>,
+    /// In-memory store: rule_id → rule instance, indexed by (tenant, agent, layer)
+    rules: Arc<RwLock<IndexedRules>>,
+    /// Hot tier for active rule anchors
+    anchors: Arc<HotCache>,
+    /// Warm tier file location, if configured
+    warm_path: Option<PathBuf>,
+    /// Memory-mapped anchor snapshot (None until built or after a full rebuild)
+    warm: Arc<RwLock<Option<WarmStore>>>,
+    /// Staging area for a complete candidate rule set (not persisted)
+    staged: Arc<RwLock<Option<StagedRuleSet>>>,
+    /// Persistence backend
+    store: Arc<Mutex<Box<dyn RuleStore>>>,
+    /// Change-detection state for databases shared with other processes
+    sync: Arc<RwLock<SyncState>>,
+    /// Labels registered per agent, matched against rule selector tags
+    agent_labels: Arc<RwLock<AgentLabelMap>>,
+    /// Call counters of rate limit rules (in memory only)
+    rate_limits: Arc<RateLimiter>,
+    /// Rules moved to quarantine while loading at construction
+    quarantined_on_load: Vec<String>,
+    /// Precision anchors are persisted with
+    anchor_precision: AnchorPrecision,
 }
 
 impl Bridge {
@@ -71,36 +121,99 @@ impl Bridge {
 
     /// Creates a new Bridge with the specified storage configuration.
     pub fn new(storage_config: StorageConfig) -> Result<Self, String> {
-        // Ensure parent directory exists
-        if let Some(parent) = storage_config.cold_storage_path.parent() {
-            if !parent.as_os_str().is_empty() {
-                std::fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create storage directory: {}", e))?;
-            }
-        }
+        let store = SqliteRuleStore::open(&storage_config.cold_storage_path)?;
+        Self::with_store(Box::new(store))?.with_warm_storage(&storage_config.warm_storage_path)
+    }
 
-        let conn = Connection::open(&storage_config.cold_storage_path)
-            .map_err(|e| format!("Failed to open SQLite database: {}", e))?;
+    /// Creates a Bridge backed by an in-memory store (no filesystem access).
+    pub fn in_memory() -> Result<Self, String> {
+        Self::with_store(Box::new(MemoryRuleStore::new()))
+    }
 
-        conn.execute_batch(SCHEMA)
-            .map_err(|e| format!("Failed to create schema: {}", e))?;
+    /// Creates a Bridge on top of any rule store and loads its active rules.
+    ///
+    /// The anchor cache is sized by `BRIDGE_HOT_CACHE_CAPACITY`, and anchors are persisted
+    /// with the precision in `BRIDGE_ANCHOR_PRECISION`.
+    pub fn with_store(store: Box<dyn RuleStore>) -> Result<Self, String> {
+        Self::with_store_and_cache(store, HotCache::from_env())
+    }
 
-        let db = Arc::new(Mutex::new(conn));
-        let rules = Arc::new(RwLock::new(HashMap::new()));
+    /// Creates a Bridge on top of any rule store with an explicit anchor cache.
+    pub fn with_store_and_cache(
+        store: Box<dyn RuleStore>,
+        anchors: HotCache,
+    ) -> Result<Self, String> {
+        let version = store.latest_version()?;
 
-        let bridge = Bridge {
-            active_version: Arc::new(RwLock::new(0)),
-            staged_version: Arc::new(RwLock::new(None)),
+        let mut bridge = Bridge {
+            active_version: Arc::new(RwLock::new(version)),
             created_at: now_ms(),
-            rules,
-            db,
+            rules: Arc::new(RwLock::new(IndexedRules::default())),
+            anchors: Arc::new(anchors),
+            warm_path: None,
+            warm: Arc::new(RwLock::new(None)),
+            staged: Arc::new(RwLock::new(None)),
+            store: Arc::new(Mutex::new(store)),
+            sync: Arc::new(RwLock::new(SyncState::default())),
+            agent_labels: Arc::new(RwLock::new(HashMap::new())),
+            rate_limits: Arc::new(RateLimiter::new()),
+            quarantined_on_load: Vec::new(),
+            anchor_precision: AnchorPrecision::from_env(),
         };
 
-        bridge.rebuild_from_db()?;
+        let (_, quarantined) = bridge.rebuild_and_quarantine()?;
+        bridge.quarantined_on_load = quarantined;
 
         Ok(bridge)
     }
 
+    /// Adds a warm anchor tier backed by the file at `path`.
+    ///
+    /// An existing file is reused if it is intact and not ahead of the store; rules
+    /// changed since it was written are skipped until the next rebuild. Otherwise the
+    /// file is rebuilt from the store.
+    pub fn with_warm_storage(mut self, path: &Path) -> Result<Self, String> {
+        self.warm_path = Some(path.to_path_buf());
+
+        let store = self.store.lock();
+        let version = self.version();
+        let reused = match WarmStore::open(path) {
+            Ok(mut warm) if warm.version() <= version => {
+                for (rule_id, _) in store.history_between(warm.version(), version)? {
+                    warm.invalidate(&rule_id);
+                }
+                Some(warm)
+            }
+            Ok(warm) => {
+                log::warn!(
+                    "Warm storage version {} is ahead of cold storage ({}); rebuilding",
+                    warm.version(),
+                    version
+                );
+                None
+            }
+            Err(e) if path.exists() => {
+                log::warn!("{}; rebuilding", e);
+                None
+            }
+            Err(_) => None,
+        };
+        let warm = match reused {
+            Some(warm) => warm,
+            None => build_warm(&**store, path)?,
+        };
+        *self.warm.write() = Some(warm);
+        drop(store);
+
+        Ok(self)
+    }
+
+    /// Persists anchors written from now on with `precision`. Existing rows keep theirs.
+    pub fn with_anchor_precision(mut self, precision: AnchorPrecision) -> Self {
+        self.anchor_precision = precision;
+        self
+    }
+
     /// Creates a Bridge with default storage paths.
     pub fn with_defaults() -> Result<Self, String> {
         Self::new(StorageConfig::default())
@@ -110,86 +223,279 @@ impl Bridge {
     // PRIVATE: REBUILD FROM DATABASE
     // ============================================================================================
 
-    /// Rebuilds the in-memory HashMap from all rows in SQLite.
-    /// Called at init and can be called on reconnect.
-    fn rebuild_from_db(&self) -> Result<(), String> {
-        // Collect all rows while holding the DB lock, then release it before
-        // acquiring the rules write lock to avoid lock ordering issues.
-        // The named binding for `rows` forces collection before the block closes,
-        // ensuring conn and stmt are dropped before we take the write lock on rules.
-        let rows: Vec<(String, Option<String>, i64, String, Vec<u8>)> = {
-            let conn = self.db.lock();
-
-            let mut stmt = conn
-                .prepare(
-                    "SELECT id, layer, priority, rule_json, anchors_bin FROM rules WHERE status = 'active'",
-                )
-                .map_err(|e| format!("Prepare failed during rebuild: {}", e))?;
-
-            let collected: Result<Vec<_>, _> = stmt
-                .query_map([], |row| {
-                    let id: String = row.get(0)?;
-                    let layer: Option<String> = row.get(1)?;
-                    let priority: i64 = row.get(2)?;
-                    let rule_json: String = row.get(3)?;
-                    let anchors_bin: Vec<u8> = row.get(4)?;
-                    Ok((id, layer, priority, rule_json, anchors_bin))
-                })
-                .map_err(|e| format!("Query failed during rebuild: {}", e))?
-                .collect();
+    /// Rebuilds the in-memory HashMap (and the warm anchor file, if configured) from all
+    /// active rows in the store. Called at init and can be called on reconnect.
+    ///
+    /// The new map is built off to the side and swapped in under one short write lock,
+    /// so enforcement never sees an empty or partially loaded rule set. The store lock is
+    /// held throughout (writers take it before the rules lock) so no local write can
+    /// land between reading the rows and publishing them.
+    fn rebuild_from_db(&self) -> Result<RefreshDelta, String> {
+        self.rebuild_and_quarantine().map(|(delta, _)| delta)
+    }
 
-            // stmt and conn are still alive here but collected is owned; we can drop them
-            drop(stmt);
-            drop(conn);
+    /// `rebuild_from_db`, also returning the IDs of active rows moved to quarantine
+    /// because they could not be decoded.
+    fn rebuild_and_quarantine(&self) -> Result<(RefreshDelta, Vec<String>), String> {
+        let mut store = self.store.lock();
+
+        let mut rows = store.list_rules(Some(RuleStatus::Active))?;
+        let mut next = HashMap::with_capacity(rows.len());
+        let mut undecodable = Vec::new();
+        rows.retain(|stored| {
+            let row = &stored.row;
+            match decode_rule(&row.rule_json, &row.anchors_bin) {
+                Ok(rule) => {
+                    next.insert(row.rule_id.clone(), rule);
+                    true
+                }
+                Err(e) => {
+                    undecodable.push((row.clone(), e));
+                    false
+                }
+            }
+        });
+        // Only anchors already in the hot cache are decoded; the rest load on first use.
+        let mut cached = HashMap::new();
+        for stored in &rows {
+            if self.anchors.contains(&stored.row.rule_id) {
+                let vector = RuleVector::decode(&stored.row.anchors_bin)?;
+                cached.insert(stored.row.rule_id.clone(), vector);
+            }
+        }
+        let quarantined = quarantine_rows(&mut **store, &undecodable)?;
+        let latest = store.latest_version()?;
+        let labels = load_agent_labels(&**store)?;
+
+        // The store may have been replaced wholesale, so the warm snapshot is rewritten
+        // rather than patched.
+        let warm = self.warm_path.as_ref().and_then(|path| {
+            let rows = rows.iter().map(|stored| {
+                (
+                    stored.row.rule_id.as_str(),
+                    stored.row.anchors_bin.as_slice(),
+                )
+            });
+            WarmStore::build(path, latest, rows)
+                .map_err(|e| discard_warm_file(path, &e))
+                .ok()
+        });
+
+        let delta = self.rebuild_delta(&next, &cached)?;
+        self.rules.write().replace(next);
+        *self.agent_labels.write() = labels;
+        *self.warm.write() = warm;
+        self.anchors.clear();
+        self.warm_anchors(cached);
+        self.set_version(latest);
+        self.sync.write().synced_at = now_ms();
+
+        Ok((delta, quarantined))
+    }
 
-            collected.map_err(|e| format!("Row collection failed during rebuild: {}", e))?
+    /// Counts how a full rebuild changes the in-memory rules.
+    ///
+    /// Anchors are only held for cached rules, so a rule whose metadata is unchanged
+    /// counts as updated only when its cached anchors differ from `cached`.
+    fn rebuild_delta(
+        &self,
+        next: &RuleInstances,
+        cached: &HashMap<String, RuleVector>,
+    ) -> Result<RefreshDelta, String> {
+        let current = self.rules.read();
+        let mut delta = RefreshDelta {
+            added: next.keys().filter(|id| !current.contains_key(*id)).count(),
+            removed: current.keys().filter(|id| !next.contains_key(*id)).count(),
+            updated: 0,
         };
 
-        let mut map = self.rules.write();
-        map.clear();
-
-        for (id, _layer, priority, rule_json, anchors_bin) in rows {
-            // Deserialize metadata from the stored JSON
-            let metadata: RuleMetadata = match serde_json::from_str(&rule_json) {
-                Ok(m) => m,
-                Err(e) => {
-                    eprintln!("Skipping rule {} with invalid JSON: {}", id, e);
-                    continue;
-                }
+        for (rule_id, rule) in next {
+            let Some(old) = current.get(rule_id) else {
+                continue;
             };
+            let metadata_changed = metadata_json(old.as_ref())? != metadata_json(rule.as_ref())?;
+            // Compared at the persisted precision, so rounding alone is not a change.
+            let anchors_changed = cached.get(rule_id).is_some_and(|vector| {
+                self.anchors.peek(rule_id).is_some_and(|old| {
+                    old.encode(self.anchor_precision) != vector.encode(self.anchor_precision)
+                })
+            });
+            if metadata_changed || anchors_changed {
+                delta.updated += 1;
+            }
+        }
+        Ok(delta)
+    }
 
-            // Deserialize the RuleVector from raw little-endian f32 bytes
-            let rule_vector = match deserialize_rule_vector(&anchors_bin) {
-                Ok(v) => v,
-                Err(e) => {
-                    eprintln!("Skipping rule {} with invalid anchors: {}", id, e);
-                    continue;
+    /// Public wrapper for rebuild_from_db — called by RefreshService.
+    pub fn rebuild_from_db_public(&self) -> Result<RefreshDelta, String> {
+        self.rebuild_from_db()
+    }
+
+    /// Applies only the changes committed to cold storage since the in-memory version.
+    ///
+    /// Changes are read from the version history, so rows written by another process
+    /// sharing the database are picked up without reloading every rule. Falls back to a
+    /// full rebuild if the history is behind the in-memory version (e.g. the database
+    /// file was replaced).
+    pub fn refresh_delta(&self) -> Result<RefreshDelta, String> {
+        let mut store = self.store.lock();
+        match self.catch_up(&**store)? {
+            Some((delta, undecodable)) => {
+                if !quarantine_rows(&mut **store, &undecodable)?.is_empty() {
+                    self.set_version(store.latest_version()?);
                 }
-            };
+                Ok(delta)
+            }
+            None => {
+                drop(store);
+                self.rebuild_from_db()
+            }
+        }
+    }
 
-            let rule: Arc<dyn RuleInstance> = Arc::new(DesignBoundaryRule::new(
-                metadata.rule_id.clone(),
-                metadata.priority,
-                metadata.scope,
-                metadata.layer,
-                metadata.created_at_ms,
-                metadata.enabled,
-                metadata.description,
-                metadata.params,
-            ));
+    /// Checks whether another process has committed to cold storage and applies its changes.
+    ///
+    /// Uses the store's change token (`PRAGMA data_version` for SQLite), so an idle check
+    /// is cheap. Returns None when nothing changed or the store cannot be shared.
+    pub fn sync_if_changed(&self) -> Result<Option<RefreshDelta>, String> {
+        let data_version = self.store.lock().change_token()?;
+
+        if data_version.is_none() || self.sync.read().data_version == data_version {
+            self.sync.write().synced_at = now_ms();
+            return Ok(None);
+        }
 
-            // Suppress unused variable warning for priority — it came from the DB column
-            let _ = priority;
+        let delta = self.refresh_delta()?;
+        // Label changes are not versioned, so they are reloaded on any foreign commit.
+        let labels = load_agent_labels(&**self.store.lock())?;
+        *self.agent_labels.write() = labels;
+        self.sync.write().data_version = data_version;
+        Ok(Some(delta))
+    }
+
+    /// Applies history committed since the in-memory version. Caller holds the store lock.
+    ///
+    /// A rule whose new row cannot be decoded leaves the in-memory set, so its previous
+    /// version is not enforced; the row is returned for the caller to quarantine.
+    /// Returns None (and changes nothing) if the history is behind the in-memory version.
+    fn catch_up<R: StoreRead + ?Sized>(
+        &self,
+        store: &R,
+    ) -> Result<Option<(RefreshDelta, Undecodable)>, String> {
+        let since = self.version();
+        let latest = store.latest_version()?;
 
-            map.insert(id, (rule, rule_vector));
+        if latest < since {
+            return Ok(None);
         }
 
-        Ok(())
+        let mut delta = RefreshDelta::default();
+        let mut undecodable = Vec::new();
+        if latest > since {
+            let mut changes = Vec::new();
+            for (id, persisted) in store.history_between(since, latest)? {
+                let entry = match persisted {
+                    Some((rule_json, anchors_bin)) => {
+                        match decode_rule_entry(&rule_json, &anchors_bin) {
+                            Ok(entry) => Some(entry),
+                            Err(e) => {
+                                // Only the current row can be quarantined; an older entry
+                                // was already replaced.
+                                if let Some(stored) = store.get_rule(&id)? {
+                                    if stored.status == RuleStatus::Active
+                                        && stored.row.rule_json == rule_json
+                                        && stored.row.anchors_bin == anchors_bin
+                                    {
+                                        undecodable.push((stored.row, e));
+                                    }
+                                }
+                                None
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                changes.push((id, entry));
+            }
+
+            let mut map = self.rules.write();
+            for (id, entry) in changes {
+                match entry {
+                    Some((rule, anchors)) => {
+                        match map.insert(id.clone(), rule) {
+                            Some(_) => delta.updated += 1,
+                            None => delta.added += 1,
+                        }
+                        self.cache_anchors(id, anchors);
+                    }
+                    None => {
+                        if map.remove(&id).is_some() {
+                            delta.removed += 1;
+                        }
+                        self.drop_anchors(&id);
+                    }
+                }
+            }
+            drop(map);
+
+            self.set_version(latest);
+        }
+
+        self.sync.write().synced_at = now_ms();
+        Ok(Some((delta, undecodable)))
     }
 
-    /// Public wrapper for rebuild_from_db — called by RefreshService.
-    pub fn rebuild_from_db_public(&self) -> Result<(), String> {
-        self.rebuild_from_db()
+    /// Starts a write transaction and brings the in-memory rules up to date first.
+    ///
+    /// The store locks out other writers for the transaction, so no other process can
+    /// commit between the catch-up and the write. Undecodable rows met while catching up
+    /// are quarantined as a version of their own in the same transaction, and the sequence
+    /// of a signed payload being applied (see `signed_write`) is claimed in it. Returns
+    /// the version the write will be recorded as. Commit with `commit_write`.
+    fn begin_write<'s>(
+        &self,
+        store: &'s mut dyn RuleStore,
+    ) -> Result<(Box<dyn StoreTransaction + 's>, u64), String> {
+        let mut tx = store.begin()?;
+        let quarantined = match self.catch_up(&*tx)? {
+            Some((_, undecodable)) => !quarantine_in(tx.as_mut(), &undecodable)?.is_empty(),
+            None => false,
+        };
+        claim_pending(tx.as_mut())?;
+        let version = if quarantined {
+            tx.latest_version()? + 1
+        } else {
+            self.version() + 1
+        };
+        Ok((tx, version))
+    }
+
+    /// Runs `write` on behalf of a signed payload, claiming the payload's sequence.
+    ///
+    /// The claim is recorded by the first transaction `write` commits, so it is persisted
+    /// with the change it authorizes. If `write` commits nothing (it failed, or only
+    /// touched memory) the claim is recorded on its own, so the payload is still spent.
+    /// Fails without running `write` when the sequence was already used.
+    pub fn signed_write<T>(
+        &self,
+        claim: Option<&SequenceClaim>,
+        write: impl FnOnce() -> T,
+    ) -> Result<T, String> {
+        let Some(claim) = claim else {
+            return Ok(write());
+        };
+        claim.check_after(self.store.lock().signing_sequence(&claim.key_id)?)?;
+
+        let pending = PendingClaim::set(claim);
+        let result = write();
+        if pending.unspent() {
+            let mut store = self.store.lock();
+            let mut tx = store.begin()?;
+            claim_in(tx.as_mut(), claim)?;
+            tx.commit()?;
+        }
+        Ok(result)
     }
 
     // ============================================================================================
@@ -201,9 +507,12 @@ impl Bridge {
         *self.active_version.read()
     }
 
-    /// Returns the staged version (if any)
+    /// Returns the active version the staged rule set was built against (if any)
     pub fn staged_version(&self) -> Option<u64> {
-        *self.staged_version.read()
+        self.staged
+            .read()
+            .as_ref()
+            .map(|staged| staged.base_version)
     }
 
     /// Returns the creation timestamp
@@ -211,6 +520,13 @@ impl Bridge {
         self.created_at
     }
 
+    /// Returns when the in-memory rules were last confirmed up to date with cold storage.
+    ///
+    /// `version()` is the last synced version: the storage version the rules reflect.
+    pub fn last_synced_at(&self) -> u64 {
+        self.sync.read().synced_at
+    }
+
     /// Returns the number of installed rules
     pub fn rule_count(&self) -> usize {
         self.rules.read().len()
@@ -218,109 +534,447 @@ impl Bridge {
 
     /// Returns a clone of all installed rule instances.
     pub fn all_rules(&self) -> Vec<Arc<dyn RuleInstance>> {
-        self.rules
-            .read()
-            .values()
-            .map(|(rule, _)| Arc::clone(rule))
-            .collect()
+        self.rules.read().values().cloned().collect()
+    }
+
+    /// Returns enabled rules applicable to a request, highest priority first.
+    ///
+    /// Served from the (tenant, agent, layer) index, so the cost depends on the number of
+    /// matching rules rather than the total. `tenant`/`agent` of None match any tenant or
+    /// agent; a specific agent also matches the global rules of the requested tenant, and
+    /// an empty agent matches only global rules. Rules without a layer apply to every
+    /// layer, and an empty `layer` matches only those.
+    pub fn rules_for(
+        &self,
+        tenant: Option<&str>,
+        agent: Option<&str>,
+        layer: &str,
+    ) -> Vec<Arc<dyn RuleInstance>> {
+        self.rules.read().index().lookup(tenant, agent, layer)
     }
 
     /// Returns a specific rule by ID if present.
     pub fn get_rule(&self, rule_id: &str) -> Option<Arc<dyn RuleInstance>> {
+        self.rules.read().get(rule_id).cloned()
+    }
+
+    /// Returns a specific rule by ID if present and owned by `tenant`.
+    pub fn get_tenant_rule(&self, tenant: &str, rule_id: &str) -> Option<Arc<dyn RuleInstance>> {
+        self.get_rule(rule_id)
+            .filter(|rule| rule.scope().belongs_to(tenant))
+    }
+
+    /// Returns a clone of every installed rule owned by `tenant`.
+    pub fn tenant_rules(&self, tenant: &str) -> Vec<Arc<dyn RuleInstance>> {
         self.rules
             .read()
-            .get(rule_id)
-            .map(|(rule, _)| Arc::clone(rule))
+            .values()
+            .filter(|rule| rule.scope().belongs_to(tenant))
+            .cloned()
+            .collect()
     }
 
     // ============================================================================================
     // RULE OPERATIONS
     // ============================================================================================
 
-    /// Adds a rule and stores its pre-encoded anchors. Upserts into SQLite AND inserts into HashMap.
+    /// Adds a rule and stores its pre-encoded anchors. Upserts into the store AND inserts into
+    /// HashMap.
+    ///
+    /// Fails if the rule_id is already stored under another tenant.
     pub fn add_rule_with_anchors(
         &self,
         rule: Arc<dyn RuleInstance>,
         anchors: RuleVector,
     ) -> Result<(), String> {
-        let rule_id = rule.rule_id().to_string();
-        let metadata = RuleMetadata::from_rule(rule.as_ref());
-
-        let rule_json = serde_json::to_string(&metadata)
-            .map_err(|e| format!("Failed to serialize rule metadata: {}", e))?;
+        let row = rule_row(rule.as_ref(), &anchors, self.anchor_precision)?;
+
+        let mut store = self.store.lock();
+        let (mut tx, version) = self.begin_write(&mut **store)?;
+        check_owner(&*tx, &row)?;
+        tx.upsert(&row)?;
+        record_row(tx.as_mut(), version, &row)?;
+        tx.record_version(
+            version,
+            "install",
+            &format!("Installed rule {}", row.rule_id),
+        )?;
+        commit_write(tx)?;
+
+        self.rules.write().insert(row.rule_id.clone(), rule);
+        self.cache_anchors(row.rule_id, anchors);
+
+        self.set_version(version);
+        Ok(())
+    }
 
-        let anchors_bin = serialize_rule_vector(&anchors);
+    /// Adds a batch of rules all-or-nothing.
+    ///
+    /// Every row is upserted inside a single store transaction and the in-memory map is
+    /// updated under one write lock, so enforcement never observes a partially installed
+    /// batch. The version is bumped once for the whole batch. Like `add_rule_with_anchors`,
+    /// a rule_id stored under another tenant fails the whole batch.
+    pub fn add_rules_batch(
+        &self,
+        batch: Vec<(Arc<dyn RuleInstance>, RuleVector)>,
+    ) -> Result<(), String> {
+        if batch.is_empty() {
+            return Ok(());
+        }
 
-        let tenant_id = metadata
-            .scope
-            .agent_ids
-            .first()
-            .cloned()
-            .unwrap_or_default();
-
-        let layer: Option<&str> = metadata.layer.as_deref();
-        let updated_at = (now_ms() as f64) / 1000.0;
-
-        {
-            let conn = self.db.lock();
-            conn.execute(
-                "INSERT OR REPLACE INTO rules (id, tenant_id, layer, priority, rule_json, anchors_bin, status, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'active', ?7)",
-                params![
-                    rule_id,
-                    tenant_id,
-                    layer,
-                    metadata.priority as i64,
-                    rule_json,
-                    anchors_bin,
-                    updated_at,
-                ],
-            )
-            .map_err(|e| format!("SQLite upsert failed: {}", e))?;
+        let rows = batch
+            .iter()
+            .map(|(rule, anchors)| rule_row(rule.as_ref(), anchors, self.anchor_precision))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut store = self.store.lock();
+        let (mut tx, version) = self.begin_write(&mut **store)?;
+        for row in &rows {
+            check_owner(&*tx, row)?;
+            tx.upsert(row)?;
+            record_row(tx.as_mut(), version, row)?;
         }
+        tx.record_version(
+            version,
+            "install",
+            &format!("Installed batch of {} rules", rows.len()),
+        )?;
+        commit_write(tx)?;
 
-        self.rules
-            .write()
-            .insert(rule_id, (Arc::clone(&rule), anchors));
+        let mut map = self.rules.write();
+        for (row, (rule, anchors)) in rows.into_iter().zip(batch) {
+            map.insert(row.rule_id.clone(), rule);
+            self.cache_anchors(row.rule_id, anchors);
+        }
+        drop(map);
 
-        self.increment_version();
+        self.set_version(version);
         Ok(())
     }
 
-    /// Removes a rule by ID. Returns true if the rule was present.
+    /// Removes a rule by ID, whatever its status. Returns true if the rule was present.
     pub fn remove_rule(&self, rule_id: &str) -> Result<bool, String> {
-        let removed = self.rules.write().remove(rule_id).is_some();
-        if !removed {
+        let mut store = self.store.lock();
+        let (mut tx, version) = self.begin_write(&mut **store)?;
+        let was_active = self.rules.read().contains_key(rule_id);
+
+        if !tx.delete(rule_id)? {
+            // Keep whatever the catch-up quarantined; memory already reflects it.
+            commit_write(tx)?;
             return Ok(false);
         }
-
-        {
-            let conn = self.db.lock();
-            conn.execute("DELETE FROM rules WHERE id = ?1", params![rule_id])
-                .map_err(|e| format!("SQLite delete failed: {}", e))?;
+        if was_active {
+            tx.record_removal(version, rule_id)?;
         }
+        tx.record_version(version, "remove", &format!("Removed rule {}", rule_id))?;
+        commit_write(tx)?;
+
+        self.rules.write().remove(rule_id);
+        self.drop_anchors(rule_id);
 
-        self.increment_version();
+        self.set_version(version);
         Ok(true)
     }
 
     /// Clears all rules and storage state.
-    pub fn clear_all(&self) {
+    ///
+    /// The in-memory rules and caches are only cleared once the store has committed.
+    pub fn clear_all(&self) -> Result<(), String> {
+        let mut store = self.store.lock();
+        let (mut tx, version) = self.begin_write(&mut **store)?;
+        clear_rows(tx.as_mut(), version)?;
+        commit_write(tx)?;
+
         self.rules.write().clear();
+        *self.warm.write() = None;
+        self.anchors.clear();
+        self.set_version(version);
+        Ok(())
+    }
 
-        let conn = self.db.lock();
-        let _ = conn.execute("DELETE FROM rules", []);
+    // ============================================================================================
+    // RULE STATUS
+    // ============================================================================================
 
-        drop(conn);
-        self.increment_version();
+    /// Moves rules to `status`. Returns the IDs whose status actually changed.
+    ///
+    /// Disabled and archived rules keep their row (and anchors) in the store but leave the
+    /// in-memory set, so enforcement skips them; re-enabling reloads them from the row.
+    /// All changes are committed as one version. Unknown IDs are ignored.
+    pub fn set_rule_status(
+        &self,
+        rule_ids: &[String],
+        status: RuleStatus,
+    ) -> Result<Vec<String>, String> {
+        self.update_status(
+            rule_ids,
+            status,
+            status_verb(status),
+            |changed| match changed {
+                [rule_id] => format!("Set rule {} to {}", rule_id, status.as_str()),
+                _ => format!("Set {} rules to {}", changed.len(), status.as_str()),
+            },
+        )
     }
 
-    /// Get rule anchors. Reads directly from the in-memory HashMap (no SQLite hit).
-    pub fn get_rule_anchors(&self, rule_id: &str) -> Option<RuleVector> {
-        self.rules
+    /// Archives active rules whose validity window ended at or before `now_ms`.
+    ///
+    /// Recorded as a single "expire" version. Returns the archived rule IDs.
+    pub fn archive_expired(&self, now_ms: u64) -> Result<Vec<String>, String> {
+        let mut expired: Vec<String> = self
+            .rules
             .read()
-            .get(rule_id)
-            .map(|(_, vector)| vector.clone())
+            .values()
+            .filter(|rule| rule.expires_at().is_some_and(|end| end <= now_ms))
+            .map(|rule| rule.rule_id().to_string())
+            .collect();
+        if expired.is_empty() {
+            return Ok(expired);
+        }
+        expired.sort();
+
+        self.update_status(
+            &expired,
+            RuleStatus::Archived,
+            "expire",
+            |changed| match changed {
+                [rule_id] => format!("Archived expired rule {}", rule_id),
+                _ => format!("Archived {} expired rules", changed.len()),
+            },
+        )
+    }
+
+    /// Applies a status change and records it as one version of `kind`.
+    fn update_status(
+        &self,
+        rule_ids: &[String],
+        status: RuleStatus,
+        kind: &str,
+        describe: impl FnOnce(&[String]) -> String,
+    ) -> Result<Vec<String>, String> {
+        let mut store = self.store.lock();
+        let (mut tx, version) = self.begin_write(&mut **store)?;
+
+        let mut changed = Vec::new();
+        let mut enabled = Vec::new();
+        for rule_id in rule_ids {
+            let Some(stored) = tx.get_rule(rule_id)? else {
+                continue;
+            };
+            if stored.status == status {
+                continue;
+            }
+
+            tx.set_status(rule_id, status)?;
+
+            if status == RuleStatus::Active {
+                let entry = decode_rule_entry(&stored.row.rule_json, &stored.row.anchors_bin)
+                    .map_err(|e| format!("Cannot re-enable rule {}: {}", rule_id, e))?;
+                record_row(tx.as_mut(), version, &stored.row)?;
+                enabled.push((rule_id.clone(), entry));
+            } else if stored.status == RuleStatus::Active {
+                tx.record_removal(version, rule_id)?;
+            }
+            changed.push(rule_id.clone());
+        }
+
+        if changed.is_empty() {
+            // Keep whatever the catch-up quarantined; memory already reflects it.
+            commit_write(tx)?;
+            return Ok(changed);
+        }
+
+        let description = describe(&changed);
+        tx.record_version(version, kind, &description)?;
+        commit_write(tx)?;
+
+        let mut map = self.rules.write();
+        if status != RuleStatus::Active {
+            for rule_id in &changed {
+                map.remove(rule_id);
+                self.drop_anchors(rule_id);
+            }
+        }
+        for (rule_id, (rule, anchors)) in enabled {
+            map.insert(rule_id.clone(), rule);
+            self.cache_anchors(rule_id, anchors);
+        }
+        drop(map);
+
+        self.set_version(version);
+        Ok(changed)
+    }
+
+    /// Moves the rules among `rule_ids` that `tenant` owns to `status`; others are ignored.
+    pub fn set_tenant_rule_status(
+        &self,
+        tenant: &str,
+        rule_ids: &[String],
+        status: RuleStatus,
+    ) -> Result<Vec<String>, String> {
+        let owned: Vec<String> = self
+            .list_rules(Some(tenant), None)?
+            .into_iter()
+            .map(|listing| listing.rule_id)
+            .filter(|rule_id| rule_ids.contains(rule_id))
+            .collect();
+        self.set_rule_status(&owned, status)
+    }
+
+    /// Moves every rule of `tenant` scoped to `agent_id` (in any status) to `status`.
+    pub fn set_agent_status(
+        &self,
+        tenant: &str,
+        agent_id: &str,
+        status: RuleStatus,
+    ) -> Result<Vec<String>, String> {
+        let rule_ids: Vec<String> = self
+            .list_rules(Some(tenant), Some(agent_id))?
+            .into_iter()
+            .map(|listing| listing.rule_id)
+            .collect();
+        self.set_rule_status(&rule_ids, status)
+    }
+
+    /// Lists every stored rule with its status, optionally only those of one tenant and/or
+    /// scoped to one agent.
+    pub fn list_rules(
+        &self,
+        tenant: Option<&str>,
+        agent_id: Option<&str>,
+    ) -> Result<Vec<RuleListing>, String> {
+        let rows = self.store.lock().list_rules(None)?;
+
+        let mut listings = Vec::with_capacity(rows.len());
+        for stored in rows {
+            let rule_id = stored.row.rule_id;
+            let metadata: RuleMetadata = match serde_json::from_str(&stored.row.rule_json) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    log::warn!("Skipping rule {} in listing: invalid JSON: {}", rule_id, e);
+                    continue;
+                }
+            };
+            if tenant.is_some_and(|tenant| !metadata.scope.belongs_to(tenant)) {
+                continue;
+            }
+            if agent_id.is_some_and(|agent_id| !metadata.scope.is_scoped_to(agent_id)) {
+                continue;
+            }
+            listings.push(RuleListing {
+                tenant_id: metadata.scope.tenant().to_string(),
+                rule_id,
+                layer: metadata.layer,
+                priority: metadata.priority,
+                policy_type: metadata.policy_type,
+                status: stored.status,
+                updated_at_ms: stored.updated_at_ms,
+                signed_by: metadata.signed_by,
+                tags: metadata.scope.tags,
+            });
+        }
+        Ok(listings)
+    }
+
+    /// Get anchors for an active rule from the hot cache, falling back to warm storage and
+    /// then the store on a miss.
+    pub fn get_rule_anchors(&self, rule_id: &str) -> Option<RuleVector> {
+        if !self.rules.read().contains_key(rule_id) {
+            return None;
+        }
+        if let Some(anchors) = self.anchors.get_and_mark(rule_id) {
+            return Some(anchors);
+        }
+        if let Some(anchors) = self.read_warm(rule_id) {
+            return Some(anchors);
+        }
+        match self.load_anchors(rule_id) {
+            Ok(anchors) => anchors,
+            Err(e) => {
+                log::error!("Failed to load anchors for rule {}: {}", rule_id, e);
+                None
+            }
+        }
+    }
+
+    /// Reads an active rule's anchors from the store and caches them.
+    fn load_anchors(&self, rule_id: &str) -> Result<Option<RuleVector>, String> {
+        let store = self.store.lock();
+        let Some(stored) = store.get_rule(rule_id)? else {
+            return Ok(None);
+        };
+        if stored.status != RuleStatus::Active {
+            return Ok(None);
+        }
+        let anchors = RuleVector::decode(&stored.row.anchors_bin)?;
+        // A zero-capacity cache just means every lookup goes to the store.
+        let _ = self.anchors.insert(rule_id.to_string(), anchors.clone());
+        Ok(Some(anchors))
+    }
+
+    /// Reads anchors from the warm snapshot and caches them.
+    ///
+    /// The warm lock is held until the cache insert, so a concurrent write (which
+    /// invalidates the snapshot entry before touching the cache) always lands last.
+    fn read_warm(&self, rule_id: &str) -> Option<RuleVector> {
+        let warm = self.warm.read();
+        let anchors = warm.as_ref()?.get(rule_id)?;
+        let _ = self.anchors.insert(rule_id.to_string(), anchors.clone());
+        Some(anchors)
+    }
+
+    /// Stops serving `rule_id` from the warm snapshot. Must run before the hot cache changes.
+    fn invalidate_warm(&self, rule_id: &str) {
+        if let Some(warm) = self.warm.write().as_mut() {
+            warm.invalidate(rule_id);
+        }
+    }
+
+    /// Forgets a rule's anchors in both the warm and hot tiers.
+    fn drop_anchors(&self, rule_id: &str) {
+        self.invalidate_warm(rule_id);
+        self.anchors.remove(rule_id);
+    }
+
+    /// Caches freshly written anchors when they replace a cached entry or there is room,
+    /// so writes do not push out anchors that are in use.
+    fn cache_anchors(&self, rule_id: String, anchors: RuleVector) {
+        self.invalidate_warm(&rule_id);
+        if self.anchors.contains(&rule_id) || self.anchors.len() < self.anchors.capacity() {
+            let _ = self.anchors.insert(rule_id, anchors);
+        }
+    }
+
+    /// Fills the cache with anchors while there is room.
+    fn warm_anchors(&self, anchors: impl IntoIterator<Item = (String, RuleVector)>) {
+        for (rule_id, vector) in anchors {
+            if self.anchors.len() >= self.anchors.capacity() {
+                break;
+            }
+            let _ = self.anchors.insert(rule_id, vector);
+        }
+    }
+
+    /// Rewrites the warm anchor file from the active rows in the store.
+    ///
+    /// Returns the number of rules written, or None when no warm tier is configured.
+    pub fn rebuild_warm_storage(&self) -> Result<Option<usize>, String> {
+        let Some(path) = &self.warm_path else {
+            return Ok(None);
+        };
+        let store = self.store.lock();
+        match build_warm(&**store, path) {
+            Ok(warm) => {
+                let count = warm.len();
+                *self.warm.write() = Some(warm);
+                Ok(Some(count))
+            }
+            Err(e) => {
+                *self.warm.write() = None;
+                discard_warm_file(path, &e);
+                Err(e)
+            }
+        }
     }
 
     // ============================================================================================
@@ -329,12 +983,34 @@ impl Bridge {
 
     /// Returns statistics about the bridge
     pub fn stats(&self) -> BridgeStats {
+        let quarantined_rules = self
+            .list_quarantined()
+            .map(|quarantined| quarantined.len())
+            .unwrap_or_else(|e| {
+                log::error!("Failed to count quarantined rules: {}", e);
+                0
+            });
+
         let rules = self.rules.read();
         let total_rules = rules.len();
-        let global_rules = rules
-            .values()
-            .filter(|(rule, _)| rule.scope().is_global)
-            .count();
+        let global_rules = rules.values().filter(|rule| rule.scope().is_global).count();
+
+        let mut tables: BTreeMap<(String, String), FamilyLayerStats> = BTreeMap::new();
+        for rule in rules.values() {
+            let family_id = rule.family_id().to_string();
+            let layer = rule.layer().unwrap_or_default().to_string();
+            let table = tables
+                .entry((family_id.clone(), layer.clone()))
+                .or_insert_with(|| FamilyLayerStats {
+                    family_id,
+                    layer,
+                    ..Default::default()
+                });
+            table.rule_count += 1;
+            if rule.scope().is_global {
+                table.global_count += 1;
+            }
+        }
 
         BridgeStats {
             version: self.version(),
@@ -342,139 +1018,1070 @@ impl Bridge {
             global_rules,
             scoped_rules: total_rules.saturating_sub(global_rules),
             created_at: self.created_at,
+            last_synced_at: self.last_synced_at(),
+            hot_cache: self.anchors.stats(),
+            quarantined_rules,
+            tables: tables.into_values().collect(),
+        }
+    }
+
+    // ============================================================================================
+    // QUARANTINE
+    // ============================================================================================
+
+    /// Lists rule rows moved to quarantine because they could not be decoded.
+    pub fn list_quarantined(&self) -> Result<Vec<QuarantinedRule>, String> {
+        self.store.lock().list_quarantined()
+    }
+
+    /// Lists one tenant's quarantined rule rows.
+    pub fn list_tenant_quarantined(&self, tenant: &str) -> Result<Vec<QuarantinedRule>, String> {
+        let mut quarantined = self.list_quarantined()?;
+        quarantined.retain(|entry| entry.row.tenant_id == tenant);
+        Ok(quarantined)
+    }
+
+    /// Returns the IDs of active rules that failed to load when this bridge was created.
+    ///
+    /// They are in quarantine and not enforced.
+    pub fn quarantined_on_load(&self) -> &[String] {
+        &self.quarantined_on_load
+    }
+
+    // ============================================================================================
+    // AGENT LABELS
+    // ============================================================================================
+
+    /// Replaces the labels registered for an agent of a tenant; empty `labels` removes them.
+    ///
+    /// Registered labels are matched against rule selector tags and take precedence over
+    /// labels a request carries. They are not part of the rule-set history.
+    pub fn set_agent_labels(
+        &self,
+        tenant: &str,
+        agent_id: &str,
+        labels: HashMap<String, String>,
+    ) -> Result<(), String> {
+        if agent_id.is_empty() {
+            return Err("agent_id is required".to_string());
+        }
+        if labels.keys().any(|key| key.is_empty()) {
+            return Err(format!(
+                "Labels for agent {} contain an empty key",
+                agent_id
+            ));
+        }
+        let tenant = normalize_tenant(tenant);
+
+        let mut store = self.store.lock();
+        let mut tx = store.begin()?;
+        claim_pending(tx.as_mut())?;
+        tx.set_agent_labels(tenant, agent_id, &labels)?;
+        commit_write(tx)?;
+
+        let key = (tenant.to_string(), agent_id.to_string());
+        let mut registered = self.agent_labels.write();
+        if labels.is_empty() {
+            registered.remove(&key);
+        } else {
+            registered.insert(key, labels);
         }
+        Ok(())
+    }
+
+    /// Returns the labels registered for an agent of a tenant (empty when none).
+    pub fn agent_labels(&self, tenant: &str, agent_id: &str) -> HashMap<String, String> {
+        self.agent_labels
+            .read()
+            .get(&(normalize_tenant(tenant).to_string(), agent_id.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Call counters of rate limit rules, shared by every enforcement on this Bridge.
+    pub fn rate_limits(&self) -> &RateLimiter {
+        &self.rate_limits
     }
 
     // ============================================================================================
     // VERSIONING
     // ============================================================================================
 
-    /// Increments the bridge version
-    fn increment_version(&self) {
-        *self.active_version.write() += 1;
+    /// Publishes a committed version. Callers hold the store lock so versions stay in commit order.
+    fn set_version(&self, version: u64) {
+        *self.active_version.write() = version;
     }
 
-    /// Sets the staged version for hot-reload
-    pub fn set_staged_version(&self, version: u64) {
-        *self.staged_version.write() = Some(version);
+    /// Returns true when `version` is a recorded rule-set version (0 is the empty set).
+    pub fn has_version(&self, version: u64) -> Result<bool, String> {
+        version_exists(&**self.store.lock(), version)
     }
 
-    /// Clears the staged version
-    pub fn clear_staged_version(&self) {
-        *self.staged_version.write() = None;
+    /// Lists recorded rule-set versions, newest first.
+    pub fn list_versions(&self, limit: usize) -> Result<Vec<RuleSetVersion>, String> {
+        self.store.lock().list_versions(limit)
     }
 
-    /// Promotes staged version to active (atomic hot-reload)
-    pub fn promote_staged(&self) -> Result<(), String> {
-        let staged = *self.staged_version.read();
+    /// Lists the versions that changed one tenant's rules, newest first. Rule counts
+    /// only cover that tenant.
+    pub fn list_tenant_versions(
+        &self,
+        tenant: &str,
+        limit: usize,
+    ) -> Result<Vec<RuleSetVersion>, String> {
+        self.store.lock().list_tenant_versions(tenant, limit)
+    }
+
+    /// Compares the rule sets of two recorded versions.
+    pub fn diff_versions(&self, from: u64, to: u64) -> Result<RuleSetDiff, String> {
+        let store = self.store.lock();
+        let old = load_version(&**store, from)?;
+        let new = load_version(&**store, to)?;
+        RuleSetDiff::between(&old, &new, self.anchor_precision)
+    }
 
-        match staged {
-            Some(v) => {
-                *self.active_version.write() = v;
-                self.clear_staged_version();
-                Ok(())
+    /// Compares one tenant's rules in two recorded versions.
+    pub fn diff_tenant_versions(
+        &self,
+        tenant: &str,
+        from: u64,
+        to: u64,
+    ) -> Result<RuleSetDiff, String> {
+        let store = self.store.lock();
+        let old = tenant_entries(load_version(&**store, from)?, tenant);
+        let new = tenant_entries(load_version(&**store, to)?, tenant);
+        RuleSetDiff::between(&old, &new, self.anchor_precision)
+    }
+
+    /// Rolls one tenant's rules back to a recorded version.
+    ///
+    /// With `agent_id`, only rules scoped to that agent are restored (global rules and
+    /// rules of other agents are left as they are). Other tenants are never touched. The
+    /// rollback itself is committed as a new version, so it can be undone the same way.
+    pub fn rollback_to(
+        &self,
+        version: u64,
+        tenant: &str,
+        agent_id: Option<&str>,
+    ) -> Result<RuleSetDiff, String> {
+        let mut store = self.store.lock();
+        let (mut tx, mut next_version) = self.begin_write(&mut **store)?;
+        let target = tenant_entries(load_version(&*tx, version)?, tenant);
+
+        let mut quarantined = Vec::new();
+        let (next, subject) = match agent_id {
+            None => (target, format!("tenant {}", tenant)),
+            Some(agent_id) => {
+                // Rows that cannot be decoded are quarantined rather than dropped by the
+                // replacement below.
+                let (active, undecodable) = load_active(&*tx)?;
+                quarantined = quarantine_in(tx.as_mut(), &undecodable)?;
+                if !quarantined.is_empty() {
+                    next_version = tx.latest_version()? + 1;
+                }
+                let mut next: RuleMap = tenant_entries(active, tenant)
+                    .into_iter()
+                    .filter(|(_, (rule, _))| !rule.scope().is_scoped_to(agent_id))
+                    .collect();
+                next.extend(
+                    target
+                        .into_iter()
+                        .filter(|(_, (rule, _))| rule.scope().is_scoped_to(agent_id)),
+                );
+                (next, format!("agent {} of tenant {}", agent_id, tenant))
+            }
+        };
+
+        let description = format!("Rolled back {} to version {}", subject, version);
+        let diff = self.replace_in(
+            tx.as_mut(),
+            next_version,
+            tenant,
+            &next,
+            "rollback",
+            &description,
+        )?;
+        commit_write(tx)?;
+
+        if !quarantined.is_empty() {
+            let mut rules = self.rules.write();
+            for rule_id in &quarantined {
+                rules.remove(rule_id);
+                self.drop_anchors(rule_id);
             }
-            None => Err("No staged version to promote".to_string()),
         }
+        self.apply_replacement(next, &diff, next_version);
+        Ok(diff)
+    }
+
+    // ============================================================================================
+    // STAGING
+    // ============================================================================================
+
+    /// Uploads rules into the staging area and returns the staged rule count.
+    ///
+    /// With `replace` (or when nothing is staged yet) a fresh candidate set is started;
+    /// otherwise the rules are merged into the existing one. Staged rules are invisible
+    /// to enforcement until `promote_staged` is called.
+    pub fn stage_rules(&self, batch: Vec<RuleEntry>, replace: bool) -> usize {
+        let mut staged = self.staged.write();
+        if replace || staged.is_none() {
+            *staged = Some(StagedRuleSet {
+                rules: HashMap::new(),
+                base_version: self.version(),
+                staged_at: now_ms(),
+            });
+        }
+
+        let set = staged.as_mut().expect("staged set initialized above");
+        for (rule, anchors) in batch {
+            set.rules
+                .insert(rule.rule_id().to_string(), (rule, anchors));
+        }
+        set.rules.len()
+    }
+
+    /// Returns the number of staged rules, or None when nothing is staged.
+    pub fn staged_rule_count(&self) -> Option<usize> {
+        self.staged.read().as_ref().map(|staged| staged.rules.len())
+    }
+
+    /// Returns the timestamp when the current staging area was started.
+    pub fn staged_at(&self) -> Option<u64> {
+        self.staged.read().as_ref().map(|staged| staged.staged_at)
+    }
+
+    /// Returns a clone of all staged rule instances.
+    pub fn staged_rules(&self) -> Vec<Arc<dyn RuleInstance>> {
+        self.staged
+            .read()
+            .as_ref()
+            .map(|staged| {
+                staged
+                    .rules
+                    .values()
+                    .map(|(rule, _)| Arc::clone(rule))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns enabled staged rules applicable to a request, highest priority first.
+    ///
+    /// Same matching as `rules_for`; the staged set is indexed on demand.
+    pub fn staged_rules_for(
+        &self,
+        tenant: Option<&str>,
+        agent: Option<&str>,
+        layer: &str,
+    ) -> Vec<Arc<dyn RuleInstance>> {
+        self.staged
+            .read()
+            .as_ref()
+            .map(|staged| {
+                RuleIndex::build(staged.rules.values().map(|(rule, _)| rule))
+                    .lookup(tenant, agent, layer)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get anchors for a staged rule.
+    pub fn get_staged_rule_anchors(&self, rule_id: &str) -> Option<RuleVector> {
+        self.staged
+            .read()
+            .as_ref()
+            .and_then(|staged| staged.rules.get(rule_id))
+            .map(|(_, vector)| vector.clone())
+    }
+
+    /// Drops the staged rule set. Returns the number of rules discarded, if any were staged.
+    pub fn discard_staged(&self) -> Option<usize> {
+        self.staged.write().take().map(|staged| staged.rules.len())
+    }
+
+    /// Compares a tenant's staged rules against its active ones.
+    pub fn diff_staged(&self, tenant: &str) -> Result<Option<RuleSetDiff>, String> {
+        let staged = self.staged.read();
+        let Some(staged) = staged.as_ref() else {
+            return Ok(None);
+        };
+        let mut staged_rows = rows_of(&staged.rules, self.anchor_precision)?;
+        staged_rows.retain(|_, row| row.tenant_id == tenant);
+        let active = tenant_rows(&**self.store.lock(), tenant)?;
+        Ok(Some(RuleSetDiff::between_rows(&active, &staged_rows)))
+    }
+
+    /// Promotes the staged rule set to a tenant's active set (atomic hot-reload).
+    ///
+    /// The staged set replaces every active rule of `tenant`: the store is rewritten in a
+    /// single transaction and the in-memory map is updated under one write lock. Staged
+    /// rules of another tenant fail the promotion. On failure the staged set is kept so
+    /// the promotion can be retried.
+    pub fn promote_staged(&self, tenant: &str) -> Result<RuleSetDiff, String> {
+        let mut staged_guard = self.staged.write();
+        let staged = staged_guard
+            .take()
+            .ok_or_else(|| "No staged rule set to promote".to_string())?;
+
+        let description = format!(
+            "Promoted staged rule set (base version {})",
+            staged.base_version
+        );
+        match self.replace_active(tenant, staged.rules, "promote", &description) {
+            Ok((diff, _)) => Ok(diff),
+            Err((rules, e)) => {
+                *staged_guard = Some(StagedRuleSet { rules, ..staged });
+                Err(e)
+            }
+        }
+    }
+
+    /// Replaces a tenant's active rules, persisting them first. Other tenants keep
+    /// theirs. Returns the diff and the version recorded; hands the rules back on failure.
+    fn replace_active(
+        &self,
+        tenant: &str,
+        next: RuleMap,
+        kind: &str,
+        description: &str,
+    ) -> Result<(RuleSetDiff, u64), (RuleMap, String)> {
+        let mut store = self.store.lock();
+        let persisted = self
+            .begin_write(&mut **store)
+            .and_then(|(mut tx, version)| {
+                let diff =
+                    self.replace_in(tx.as_mut(), version, tenant, &next, kind, description)?;
+                commit_write(tx)?;
+                Ok((diff, version))
+            });
+        match persisted {
+            Ok((diff, version)) => {
+                self.apply_replacement(next, &diff, version);
+                Ok((diff, version))
+            }
+            Err(e) => Err((next, e)),
+        }
+    }
+
+    /// Rewrites `tx` so `tenant` holds exactly `next` and records it as `version`.
+    fn replace_in(
+        &self,
+        tx: &mut dyn StoreTransaction,
+        version: u64,
+        tenant: &str,
+        next: &RuleMap,
+        kind: &str,
+        description: &str,
+    ) -> Result<RuleSetDiff, String> {
+        let rows = rows_of(next, self.anchor_precision)?;
+        if let Some(row) = rows.values().find(|row| row.tenant_id != tenant) {
+            return Err(format!(
+                "Rule {} belongs to tenant {}, not {}",
+                row.rule_id, row.tenant_id, tenant
+            ));
+        }
+
+        for row in rows.values() {
+            check_owner(&*tx, row)?;
+        }
+        let diff = RuleSetDiff::between_rows(&tenant_rows(&*tx, tenant)?, &rows);
+        replace_tenant_rows(tx, version, &rows, &diff)?;
+        tx.record_version(version, kind, description)?;
+        Ok(diff)
+    }
+
+    /// Brings the in-memory rules in line with a committed `replace_in`.
+    fn apply_replacement(&self, next: RuleMap, diff: &RuleSetDiff, version: u64) {
+        for rule_id in diff.removed.iter().chain(&diff.changed) {
+            self.drop_anchors(rule_id);
+        }
+        for rule_id in &diff.added {
+            self.invalidate_warm(rule_id);
+        }
+        let mut anchors = Vec::with_capacity(next.len());
+        let mut rules = self.rules.write();
+        for rule_id in &diff.removed {
+            rules.remove(rule_id);
+        }
+        for (rule_id, (rule, vector)) in next {
+            rules.insert(rule_id.clone(), rule);
+            anchors.push((rule_id, vector));
+        }
+        drop(rules);
+        self.warm_anchors(anchors);
+        self.set_version(version);
+    }
+
+    // ============================================================================================
+    // BUNDLES
+    // ============================================================================================
+
+    /// Exports every active rule of a tenant, with its anchors, as a portable bundle.
+    pub fn export_bundle(&self, tenant: &str) -> Result<RuleBundle, String> {
+        let store = self.store.lock();
+        let version = store.latest_version()?;
+
+        let mut rules = Vec::new();
+        for stored in store.list_rules(Some(RuleStatus::Active))? {
+            let row = stored.row;
+            if row.tenant_id != tenant {
+                continue;
+            }
+            let metadata: RuleMetadata = serde_json::from_str(&row.rule_json)
+                .map_err(|e| format!("Cannot export rule {}: invalid JSON: {}", row.rule_id, e))?;
+            rules.push(BundledRule {
+                metadata,
+                anchors: row.anchors_bin,
+            });
+        }
+        Ok(RuleBundle::new(version, rules))
+    }
+
+    /// Imports a bundle into a tenant, validating every rule before anything is written.
+    ///
+    /// If any rule fails validation (including rules of another tenant) nothing is
+    /// imported and the failures are returned. A dry run stops after validation and
+    /// reports the diff the import would apply. Otherwise the import is committed as a
+    /// single "import" version; bundled rules that were disabled or archived become
+    /// active again. Replace mode only replaces the tenant's own rules.
+    pub fn import_bundle(
+        &self,
+        tenant: &str,
+        bundle: RuleBundle,
+        mode: ImportMode,
+        dry_run: bool,
+    ) -> Result<BundleImport, String> {
+        let source_version = bundle.source_version;
+        let (rules, failures) = decode_bundle(bundle, tenant);
+        if !failures.is_empty() {
+            return Ok(BundleImport {
+                failures,
+                diff: RuleSetDiff::default(),
+                version: None,
+            });
+        }
+
+        let description = format!(
+            "Imported bundle of {} rules ({}, source version {})",
+            rules.len(),
+            mode.as_str(),
+            source_version
+        );
+        let (diff, version) = match (mode, dry_run) {
+            (_, true) => {
+                let rows = rows_of(&rules, self.anchor_precision)?;
+                let active = tenant_rows(&**self.store.lock(), tenant)?;
+                let diff = match mode {
+                    ImportMode::Merge => RuleSetDiff::merging(&active, &rows),
+                    ImportMode::Replace => RuleSetDiff::between_rows(&active, &rows),
+                };
+                (diff, None)
+            }
+            (ImportMode::Merge, false) => {
+                let (diff, version) = self.merge_active(rules, "import", &description)?;
+                (diff, Some(version))
+            }
+            (ImportMode::Replace, false) => {
+                let (diff, version) = self
+                    .replace_active(tenant, rules, "import", &description)
+                    .map_err(|(_, e)| e)?;
+                (diff, Some(version))
+            }
+        };
+
+        Ok(BundleImport {
+            failures: Vec::new(),
+            diff,
+            version,
+        })
+    }
+
+    /// Adds or updates `rules` and records them as one version. Other active rules stay.
+    fn merge_active(
+        &self,
+        mut rules: RuleMap,
+        kind: &str,
+        description: &str,
+    ) -> Result<(RuleSetDiff, u64), String> {
+        let rows = rows_of(&rules, self.anchor_precision)?;
+
+        let mut store = self.store.lock();
+        let (mut tx, version) = self.begin_write(&mut **store)?;
+        for row in rows.values() {
+            check_owner(&*tx, row)?;
+        }
+        let diff = RuleSetDiff::merging(&active_rows(&*tx)?, &rows);
+        let touched: Vec<&String> = diff.added.iter().chain(&diff.changed).collect();
+        for rule_id in &touched {
+            let row = &rows[*rule_id];
+            tx.upsert(row)?;
+            record_row(tx.as_mut(), version, row)?;
+        }
+        tx.record_version(version, kind, description)?;
+        commit_write(tx)?;
+
+        let mut map = self.rules.write();
+        for rule_id in touched {
+            if let Some((rule, anchors)) = rules.remove(rule_id) {
+                map.insert(rule_id.clone(), rule);
+                self.cache_anchors(rule_id.clone(), anchors);
+            }
+        }
+        drop(map);
+
+        self.set_version(version);
+        Ok((diff, version))
     }
 }
 
 // ================================================================================================
-// SERIALIZATION HELPERS
+// PERSISTED ROWS
 // ================================================================================================
 
-/// Serialize a RuleVector to raw little-endian f32 bytes.
+/// Serializes a rule into its persisted row.
+fn rule_row(
+    rule: &dyn RuleInstance,
+    anchors: &RuleVector,
+    precision: AnchorPrecision,
+) -> Result<RuleRow, String> {
+    let metadata = rule.metadata();
+
+    let rule_json = serde_json::to_string(metadata)
+        .map_err(|e| format!("Failed to serialize rule metadata: {}", e))?;
+
+    Ok(RuleRow {
+        rule_id: metadata.rule_id.clone(),
+        tenant_id: metadata.scope.tenant().to_string(),
+        layer: metadata.layer.clone(),
+        priority: metadata.priority as i64,
+        rule_json,
+        anchors_bin: anchors.encode(precision),
+    })
+}
+
+/// Serializes every rule of a rule set, keyed by rule_id.
+fn rows_of(
+    rules: &RuleMap,
+    precision: AnchorPrecision,
+) -> Result<HashMap<String, RuleRow>, String> {
+    rules
+        .iter()
+        .map(|(rule_id, (rule, anchors))| {
+            Ok((
+                rule_id.clone(),
+                rule_row(rule.as_ref(), anchors, precision)?,
+            ))
+        })
+        .collect()
+}
+
+/// Returns the persisted rows of every active rule, keyed by rule_id.
+fn active_rows<R: StoreRead + ?Sized>(store: &R) -> Result<HashMap<String, RuleRow>, String> {
+    Ok(store
+        .list_rules(Some(RuleStatus::Active))?
+        .into_iter()
+        .map(|stored| (stored.row.rule_id.clone(), stored.row))
+        .collect())
+}
+
+/// Returns the persisted rows of a tenant's active rules, keyed by rule_id.
+fn tenant_rows<R: StoreRead + ?Sized>(
+    store: &R,
+    tenant: &str,
+) -> Result<HashMap<String, RuleRow>, String> {
+    let mut rows = active_rows(store)?;
+    rows.retain(|_, row| row.tenant_id == tenant);
+    Ok(rows)
+}
+
+/// Keeps only the rules of `tenant`.
+fn tenant_entries(mut rules: RuleMap, tenant: &str) -> RuleMap {
+    rules.retain(|_, (rule, _)| rule.scope().belongs_to(tenant));
+    rules
+}
+
+/// Writes a warm snapshot of the store's active rows at its latest version.
+fn build_warm<R: StoreRead + ?Sized>(store: &R, path: &Path) -> Result<WarmStore, String> {
+    let rows = store.list_rules(Some(RuleStatus::Active))?;
+    let version = store.latest_version()?;
+    WarmStore::build(
+        path,
+        version,
+        rows.iter().map(|stored| {
+            (
+                stored.row.rule_id.as_str(),
+                stored.row.anchors_bin.as_slice(),
+            )
+        }),
+    )
+}
+
+/// Removes a warm file that could not be rebuilt, so no later start maps a stale snapshot.
+/// Anchors are served from the hot cache and cold storage until the next rebuild.
+fn discard_warm_file(path: &Path, error: &str) {
+    log::error!(
+        "Failed to rebuild warm storage {}: {}",
+        path.display(),
+        error
+    );
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            log::error!(
+                "Failed to remove stale warm storage {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+thread_local! {
+    /// The signed-payload sequence the current thread's `Bridge::signed_write` still has
+    /// to record.
+    static PENDING_CLAIM: RefCell<Option<SequenceClaim>> = const { RefCell::new(None) };
+}
+
+/// Holds a claim in `PENDING_CLAIM` until dropped.
+struct PendingClaim;
+
+impl PendingClaim {
+    fn set(claim: &SequenceClaim) -> Self {
+        PENDING_CLAIM.with(|pending| *pending.borrow_mut() = Some(claim.clone()));
+        PendingClaim
+    }
+
+    fn unspent(&self) -> bool {
+        PENDING_CLAIM.with(|pending| pending.borrow().is_some())
+    }
+}
+
+impl Drop for PendingClaim {
+    fn drop(&mut self) {
+        PENDING_CLAIM.with(|pending| pending.borrow_mut().take());
+    }
+}
+
+/// Claims the pending signed-payload sequence, if any, in `tx`.
+fn claim_pending(tx: &mut dyn StoreTransaction) -> Result<(), String> {
+    match PENDING_CLAIM.with(|pending| pending.borrow().clone()) {
+        Some(claim) => claim_in(tx, &claim),
+        None => Ok(()),
+    }
+}
+
+/// Records `claim` in `tx`. Fails if its key already used the sequence or a later one.
+fn claim_in(tx: &mut dyn StoreTransaction, claim: &SequenceClaim) -> Result<(), String> {
+    claim.check_after(tx.signing_sequence(&claim.key_id)?)?;
+    tx.set_signing_sequence(&claim.key_id, claim.sequence)
+}
+
+/// Commits a write transaction; a signed-payload sequence claimed in it is now spent.
+fn commit_write(tx: Box<dyn StoreTransaction + '_>) -> Result<(), String> {
+    tx.commit()?;
+    PENDING_CLAIM.with(|pending| pending.borrow_mut().take());
+    Ok(())
+}
+
+/// Moves undecodable active rows to quarantine, recorded as one "quarantine" version.
 ///
-/// Layout: action_anchors (16×32 f32s) + action_count (u64 LE) +
-///         resource_anchors (16×32 f32s) + resource_count (u64 LE) +
-///         data_anchors (16×32 f32s) + data_count (u64 LE) +
-///         risk_anchors (16×32 f32s) + risk_count (u64 LE)
-fn serialize_rule_vector(v: &RuleVector) -> Vec<u8> {
-    use crate::rule_vector::{MAX_ANCHORS_PER_SLOT, SLOT_WIDTH};
+/// A row rewritten since it was read is left alone; the next rebuild judges it again.
+/// Returns the quarantined rule IDs.
+fn quarantine_rows(
+    store: &mut dyn RuleStore,
+    undecodable: &Undecodable,
+) -> Result<Vec<String>, String> {
+    if undecodable.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    let floats_per_block = MAX_ANCHORS_PER_SLOT * SLOT_WIDTH;
-    let bytes_per_block = floats_per_block * 4;
-    let total = 4 * (bytes_per_block + 8); // 4 slots × (floats + u64 count)
+    let mut tx = store.begin()?;
+    let quarantined = quarantine_in(tx.as_mut(), undecodable)?;
+    if !quarantined.is_empty() {
+        tx.commit()?;
+    }
+    Ok(quarantined)
+}
 
-    let mut out = Vec::with_capacity(total);
+/// `quarantine_rows` inside the caller's transaction.
+fn quarantine_in(
+    tx: &mut dyn StoreTransaction,
+    undecodable: &Undecodable,
+) -> Result<Vec<String>, String> {
+    if undecodable.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    let write_block = |out: &mut Vec<u8>, block: &[[f32; SLOT_WIDTH]; MAX_ANCHORS_PER_SLOT], count: usize| {
-        for row in block.iter() {
-            for &f in row.iter() {
-                out.extend_from_slice(&f.to_le_bytes());
-            }
+    let version = tx.latest_version()? + 1;
+    let mut quarantined = Vec::new();
+    for (row, reason) in undecodable {
+        let unchanged = tx
+            .get_rule(&row.rule_id)?
+            .is_some_and(|stored| stored.status == RuleStatus::Active && stored.row == *row);
+        if !unchanged {
+            continue;
         }
-        out.extend_from_slice(&(count as u64).to_le_bytes());
+        log::warn!("Quarantining rule {}: {}", row.rule_id, reason);
+        tx.quarantine(&row.rule_id, reason)?;
+        tx.record_removal(version, &row.rule_id)?;
+        quarantined.push(row.rule_id.clone());
+    }
+    if quarantined.is_empty() {
+        return Ok(quarantined);
+    }
+
+    let description = match quarantined.as_slice() {
+        [rule_id] => format!("Quarantined undecodable rule {}", rule_id),
+        _ => format!("Quarantined {} undecodable rules", quarantined.len()),
     };
+    tx.record_version(version, "quarantine", &description)?;
+    Ok(quarantined)
+}
+
+/// Decodes every active rule with its anchors, returning undecodable rows separately
+/// so the caller can quarantine them.
+fn load_active<R: StoreRead + ?Sized>(store: &R) -> Result<(RuleMap, Undecodable), String> {
+    let mut rules = HashMap::new();
+    let mut undecodable = Vec::new();
+    for (rule_id, row) in active_rows(store)? {
+        match decode_rule_entry(&row.rule_json, &row.anchors_bin) {
+            Ok(entry) => {
+                rules.insert(rule_id, entry);
+            }
+            Err(e) => undecodable.push((row, e)),
+        }
+    }
+    Ok((rules, undecodable))
+}
+
+/// Serializes a rule's persisted metadata.
+fn metadata_json(rule: &dyn RuleInstance) -> Result<String, String> {
+    serde_json::to_string(rule.metadata())
+        .map_err(|e| format!("Failed to serialize rule metadata: {}", e))
+}
+
+/// Refuses a row whose rule_id is already stored under another tenant.
+fn check_owner<R: StoreRead + ?Sized>(store: &R, row: &RuleRow) -> Result<(), String> {
+    match store.get_rule(&row.rule_id)? {
+        Some(stored) if stored.row.tenant_id != row.tenant_id => Err(format!(
+            "Rule {} belongs to tenant {}",
+            row.rule_id, stored.row.tenant_id
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Appends a row to the history of `version`.
+fn record_row(tx: &mut dyn StoreTransaction, version: u64, row: &RuleRow) -> Result<(), String> {
+    tx.record_history(version, row)
+}
+
+/// Replaces a tenant's active rules in the store, as computed in `diff` against them.
+/// Runs inside the caller's transaction.
+///
+/// Disabled and archived rows are kept unless the new set reactivates them.
+///
+/// Only the rules in `diff` are appended to the history of `version`.
+fn replace_tenant_rows(
+    tx: &mut dyn StoreTransaction,
+    version: u64,
+    rows: &HashMap<String, RuleRow>,
+    diff: &RuleSetDiff,
+) -> Result<(), String> {
+    for rule_id in &diff.removed {
+        tx.delete(rule_id)?;
+    }
+    for row in rows.values() {
+        tx.upsert(row)?;
+        if diff.added.contains(&row.rule_id) || diff.changed.contains(&row.rule_id) {
+            record_row(tx, version, row)?;
+        }
+    }
+    for rule_id in &diff.removed {
+        tx.record_removal(version, rule_id)?;
+    }
+    Ok(())
+}
+
+/// Deletes every stored rule and records the removals as `version`.
+/// Runs inside the caller's transaction.
+fn clear_rows(tx: &mut dyn StoreTransaction, version: u64) -> Result<(), String> {
+    let ids = tx.delete_all(None)?;
+    for rule_id in &ids {
+        tx.record_removal(version, rule_id)?;
+    }
+    tx.record_version(version, "clear", &format!("Cleared {} rules", ids.len()))
+}
+
+// ================================================================================================
+// RULE STATUS
+// ================================================================================================
+
+/// Version kind recorded when rules move to `status`.
+fn status_verb(status: RuleStatus) -> &'static str {
+    match status {
+        RuleStatus::Active => "enable",
+        RuleStatus::Disabled => "disable",
+        RuleStatus::Archived => "archive",
+    }
+}
+
+/// A stored rule as shown in listings, whatever its status.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleListing {
+    pub rule_id: String,
+    pub tenant_id: String,
+    pub layer: Option<String>,
+    pub priority: u32,
+    pub policy_type: PolicyType,
+    pub status: RuleStatus,
+    pub updated_at_ms: u64,
+    /// Trusted key id that signed the rule's install (None = unsigned)
+    pub signed_by: Option<String>,
+    /// Selector tags the rule is limited to (empty = none)
+    pub tags: HashMap<String, String>,
+}
+
+/// Reads every registered agent label set from the store.
+fn load_agent_labels<R: StoreRead + ?Sized>(store: &R) -> Result<AgentLabelMap, String> {
+    Ok(store
+        .list_agent_labels()?
+        .into_iter()
+        .map(|entry| ((entry.tenant_id, entry.agent_id), entry.labels))
+        .collect())
+}
+
+// ================================================================================================
+// VERSION HISTORY
+// ================================================================================================
+
+/// Returns true when `version` has been recorded. Version 0 (the empty set) always exists.
+fn version_exists<R: StoreRead + ?Sized>(store: &R, version: u64) -> Result<bool, String> {
+    Ok(version == 0 || store.has_version(version)?)
+}
+
+/// Reconstructs the rule set as of `version` by taking each rule's latest history entry.
+fn load_version<R: StoreRead + ?Sized>(store: &R, version: u64) -> Result<RuleMap, String> {
+    if !version_exists(store, version)? {
+        return Err(format!("Unknown rule-set version {}", version));
+    }
+
+    store
+        .history_between(0, version)?
+        .into_iter()
+        .filter_map(|(id, persisted)| persisted.map(|row| (id, row)))
+        .map(|(id, (rule_json, anchors_bin))| {
+            let entry = decode_rule_entry(&rule_json, &anchors_bin)
+                .map_err(|e| format!("Rule {} in version {}: {}", id, version, e))?;
+            Ok((id, entry))
+        })
+        .collect()
+}
+
+// ================================================================================================
+// RULE RECONSTRUCTION
+// ================================================================================================
+
+/// Decodes a persisted rule's metadata, checking that its anchors would decode too.
+fn decode_rule(rule_json: &str, anchors_bin: &[u8]) -> Result<Arc<dyn RuleInstance>, String> {
+    let metadata: RuleMetadata =
+        serde_json::from_str(rule_json).map_err(|e| format!("invalid JSON: {}", e))?;
+    RuleVector::check_encoding(anchors_bin).map_err(|e| format!("invalid anchors: {}", e))?;
+    build_persisted(metadata)
+}
+
+/// Decodes a persisted rule (metadata JSON + anchor bytes) back into a rule entry.
+fn decode_rule_entry(rule_json: &str, anchors_bin: &[u8]) -> Result<RuleEntry, String> {
+    let metadata: RuleMetadata =
+        serde_json::from_str(rule_json).map_err(|e| format!("invalid JSON: {}", e))?;
+    let rule_vector =
+        RuleVector::decode(anchors_bin).map_err(|e| format!("invalid anchors: {}", e))?;
+    Ok((build_persisted(metadata)?, rule_vector))
+}
+
+/// Validates and builds a persisted rule, so a row its family no longer accepts is
+/// quarantined instead of loaded.
+fn build_persisted(metadata: RuleMetadata) -> Result<Arc<dyn RuleInstance>, String> {
+    validate_rule(&metadata)?;
+    build_rule(metadata)
+}
+
+/// Decodes and validates every bundled rule for import into `tenant`, collecting all
+/// failures.
+fn decode_bundle(bundle: RuleBundle, tenant: &str) -> (RuleMap, Vec<BundleFailure>) {
+    let mut rules = HashMap::with_capacity(bundle.rules.len());
+    let mut failures = Vec::new();
+    for BundledRule { metadata, anchors } in bundle.rules {
+        let rule_id = metadata.rule_id.clone();
+        let failure = |stage, message: String| BundleFailure {
+            rule_id: rule_id.clone(),
+            stage,
+            message,
+        };
+
+        if rule_id.is_empty() {
+            failures.push(failure("metadata", "rule_id is empty".to_string()));
+            continue;
+        }
+        if rules.contains_key(&rule_id) {
+            failures.push(failure(
+                "metadata",
+                format!("duplicate rule_id {}", rule_id),
+            ));
+            continue;
+        }
+        if !metadata.scope.belongs_to(tenant) {
+            failures.push(failure(
+                "metadata",
+                format!(
+                    "rule belongs to tenant {}, not {}",
+                    metadata.scope.tenant(),
+                    tenant
+                ),
+            ));
+            continue;
+        }
+        if let (Some(start), Some(end)) = (metadata.not_before, metadata.expires_at) {
+            if end <= start {
+                failures.push(failure(
+                    "metadata",
+                    format!("expires_at {} is not after not_before {}", end, start),
+                ));
+                continue;
+            }
+        }
+        let vector = match RuleVector::decode(&anchors) {
+            Ok(vector) => vector,
+            Err(e) => {
+                failures.push(failure("anchors", e));
+                continue;
+            }
+        };
 
-    write_block(&mut out, &v.action_anchors, v.action_count);
-    write_block(&mut out, &v.resource_anchors, v.resource_count);
-    write_block(&mut out, &v.data_anchors, v.data_count);
-    write_block(&mut out, &v.risk_anchors, v.risk_count);
+        if let Err(e) = validate_rule(&metadata) {
+            failures.push(failure("metadata", e));
+            continue;
+        }
+        let rule = match build_rule(metadata) {
+            Ok(rule) => rule,
+            Err(e) => {
+                failures.push(failure("metadata", e));
+                continue;
+            }
+        };
 
-    out
+        rules.insert(rule_id, (rule, vector));
+    }
+    (rules, failures)
 }
 
-/// Deserialize a RuleVector from raw little-endian f32 bytes.
-fn deserialize_rule_vector(bytes: &[u8]) -> Result<RuleVector, String> {
-    use crate::rule_vector::{MAX_ANCHORS_PER_SLOT, SLOT_WIDTH};
+// ================================================================================================
+// RULE SET DIFF
+// ================================================================================================
 
-    let floats_per_block = MAX_ANCHORS_PER_SLOT * SLOT_WIDTH;
-    let bytes_per_block = floats_per_block * 4;
-    let block_with_count = bytes_per_block + 8;
-    let expected = 4 * block_with_count;
+/// Difference between two rule sets, keyed by rule_id.
+///
+/// A rule counts as changed when either its persisted metadata or its anchors differ.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleSetDiff {
+    /// Rules present only in the new set
+    pub added: Vec<String>,
+    /// Rules present only in the old set
+    pub removed: Vec<String>,
+    /// Rules present in both sets with different content
+    pub changed: Vec<String>,
+    /// Number of rules identical in both sets
+    pub unchanged: usize,
+}
 
-    if bytes.len() != expected {
-        return Err(format!(
-            "Expected {} bytes for RuleVector, got {}",
-            expected,
-            bytes.len()
-        ));
+impl RuleSetDiff {
+    fn between(old: &RuleMap, new: &RuleMap, precision: AnchorPrecision) -> Result<Self, String> {
+        Ok(Self::between_rows(
+            &rows_of(old, precision)?,
+            &rows_of(new, precision)?,
+        ))
     }
 
-    let read_block = |data: &[u8]| -> Result<([[f32; SLOT_WIDTH]; MAX_ANCHORS_PER_SLOT], usize), String> {
-        let mut block = [[0f32; SLOT_WIDTH]; MAX_ANCHORS_PER_SLOT];
-        let mut offset = 0;
-        for row in block.iter_mut() {
-            for f in row.iter_mut() {
-                let chunk: [u8; 4] = data[offset..offset + 4]
-                    .try_into()
-                    .map_err(|_| "Slice conversion failed".to_string())?;
-                *f = f32::from_le_bytes(chunk);
-                offset += 4;
+    fn between_rows(old: &HashMap<String, RuleRow>, new: &HashMap<String, RuleRow>) -> Self {
+        let mut diff = RuleSetDiff::default();
+
+        for (rule_id, after) in new {
+            match old.get(rule_id) {
+                None => diff.added.push(rule_id.clone()),
+                Some(before) => {
+                    if before.rule_json == after.rule_json
+                        && before.anchors_bin == after.anchors_bin
+                    {
+                        diff.unchanged += 1;
+                    } else {
+                        diff.changed.push(rule_id.clone());
+                    }
+                }
             }
         }
-        let count_chunk: [u8; 8] = data[offset..offset + 8]
-            .try_into()
-            .map_err(|_| "Count slice conversion failed".to_string())?;
-        let count = u64::from_le_bytes(count_chunk) as usize;
-        Ok((block, count))
-    };
+        diff.removed = old
+            .keys()
+            .filter(|rule_id| !new.contains_key(*rule_id))
+            .cloned()
+            .collect();
 
-    let (action_anchors, action_count) = read_block(&bytes[0..block_with_count])?;
-    let (resource_anchors, resource_count) =
-        read_block(&bytes[block_with_count..2 * block_with_count])?;
-    let (data_anchors, data_count) =
-        read_block(&bytes[2 * block_with_count..3 * block_with_count])?;
-    let (risk_anchors, risk_count) =
-        read_block(&bytes[3 * block_with_count..4 * block_with_count])?;
-
-    Ok(RuleVector {
-        action_anchors,
-        action_count,
-        resource_anchors,
-        resource_count,
-        data_anchors,
-        data_count,
-        risk_anchors,
-        risk_count,
-    })
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Diff of merging `incoming` into `old`: nothing is removed, and rules absent from
+    /// `incoming` count as unchanged.
+    fn merging(old: &HashMap<String, RuleRow>, incoming: &HashMap<String, RuleRow>) -> Self {
+        let mut diff = RuleSetDiff::default();
+        for (rule_id, after) in incoming {
+            match old.get(rule_id) {
+                None => diff.added.push(rule_id.clone()),
+                Some(before) => {
+                    if before.rule_json != after.rule_json
+                        || before.anchors_bin != after.anchors_bin
+                    {
+                        diff.changed.push(rule_id.clone());
+                    }
+                }
+            }
+        }
+        diff.unchanged = old.len() - diff.changed.len();
+
+        diff.added.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Returns true when both sets are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
 }
 
 // ================================================================================================
 // STATISTICS STRUCTURES
 // ================================================================================================
 
+/// Outcome of a bundle import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BundleImport {
+    /// Rules that failed validation; nothing is imported when any are present
+    pub failures: Vec<BundleFailure>,
+    /// What the import changed (or, for a dry run, would change) in the active set
+    pub diff: RuleSetDiff,
+    /// Version the import was committed as (None for dry runs and failed validation)
+    pub version: Option<u64>,
+}
+
+/// Rules changed in memory by a refresh.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshDelta {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
 /// Bridge-level statistics
 #[derive(Debug, Clone)]
 pub struct BridgeStats {
@@ -488,4 +2095,21 @@ pub struct BridgeStats {
     pub scoped_rules: usize,
     /// Bridge creation timestamp
     pub created_at: u64,
+    /// When the in-memory rules were last confirmed up to date with cold storage
+    pub last_synced_at: u64,
+    /// Anchor cache occupancy, hits and evictions
+    pub hot_cache: HotCacheStats,
+    /// Rule rows in quarantine (never enforced)
+    pub quarantined_rules: usize,
+    /// Active rules per (family, layer), ordered by family then layer
+    pub tables: Vec<FamilyLayerStats>,
+}
+
+/// Active rule counts for one family and layer ("" = layer-less rules).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FamilyLayerStats {
+    pub family_id: String,
+    pub layer: String,
+    pub rule_count: usize,
+    pub global_count: usize,
 }
@@ -0,0 +1,236 @@
+//! # Rule Bundles
+//!
+//! Portable, versioned snapshot of an active rule set.
+//!
+//! A bundle carries every rule's `RuleMetadata` (including its policy fields) together
+//! with its encoded `RuleVector`, so a rule set can be moved between environments
+//! (including air-gapped ones) without re-encoding anchors in the Management Plane.
+//!
+//! On disk and on the wire a bundle is gzip-compressed JSON. Anchors are the persisted
+//! `RuleVector` encoding, hex encoded.
+
+use crate::types::{now_ms, RuleMetadata};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Bundle format written by this binary.
+///
+/// Version 1 always carried the fixed-size anchor layout; version 2 may carry the
+/// compact encoding. Both are read.
+pub const BUNDLE_FORMAT_VERSION: u32 = 2;
+
+/// A complete exported rule set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleBundle {
+    pub format_version: u32,
+    pub exported_at_ms: u64,
+    /// Rule-set version the bundle was exported from
+    pub source_version: u64,
+    pub rules: Vec<BundledRule>,
+}
+
+/// A single rule in a bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledRule {
+    pub metadata: RuleMetadata,
+    /// `RuleVector::encode` output (or the fixed-size layout in format 1)
+    #[serde(with = "hex_bytes")]
+    pub anchors: Vec<u8>,
+}
+
+/// How an imported bundle is combined with the active rule set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Add or update the bundled rules and keep every other active rule
+    Merge,
+    /// Make the bundled rules the whole active rule set
+    Replace,
+}
+
+impl ImportMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportMode::Merge => "merge",
+            ImportMode::Replace => "replace",
+        }
+    }
+}
+
+/// A bundled rule that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleFailure {
+    pub rule_id: String,
+    /// "metadata"|"anchors"
+    pub stage: &'static str,
+    pub message: String,
+}
+
+impl RuleBundle {
+    /// Creates a bundle in the current format.
+    pub fn new(source_version: u64, rules: Vec<BundledRule>) -> Self {
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            exported_at_ms: now_ms(),
+            source_version,
+            rules,
+        }
+    }
+
+    /// Attributes every rule to the key that signed the bundle (None = unsigned).
+    ///
+    /// Replaces whatever signer the bundled metadata claims, which is not itself trusted.
+    pub fn set_signer(&mut self, key_id: Option<&str>) {
+        for rule in &mut self.rules {
+            rule.metadata.signed_by = key_id.map(|key_id| key_id.to_string());
+        }
+    }
+
+    /// Encodes the bundle as gzip-compressed JSON.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        serde_json::to_writer(&mut encoder, self)
+            .map_err(|e| format!("Failed to encode bundle: {}", e))?;
+        encoder
+            .finish()
+            .map_err(|e| format!("Failed to compress bundle: {}", e))
+    }
+
+    /// Decodes a bundle, refusing formats this binary does not know.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut json = Vec::new();
+        GzDecoder::new(bytes)
+            .read_to_end(&mut json)
+            .map_err(|e| format!("Failed to decompress bundle: {}", e))?;
+
+        let format_version = serde_json::from_slice::<FormatProbe>(&json)
+            .map_err(|e| format!("Invalid bundle: {}", e))?
+            .format_version;
+        if !(1..=BUNDLE_FORMAT_VERSION).contains(&format_version) {
+            return Err(format!(
+                "Bundle format version {} is not supported (expected 1 to {})",
+                format_version, BUNDLE_FORMAT_VERSION
+            ));
+        }
+
+        serde_json::from_slice(&json).map_err(|e| format!("Invalid bundle: {}", e))
+    }
+
+    /// Writes the bundle to `path`.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), String> {
+        let bytes = self.to_bytes()?;
+        let mut file = std::fs::File::create(path)
+            .map_err(|e| format!("Failed to create bundle {}: {}", path.display(), e))?;
+        file.write_all(&bytes)
+            .map_err(|e| format!("Failed to write bundle {}: {}", path.display(), e))
+    }
+
+    /// Reads a bundle from `path`.
+    pub fn read_from_file(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| format!("Failed to read bundle {}: {}", path.display(), e))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Reads only the format version, so newer bundles are reported as such rather than
+/// as whatever field they fail to parse.
+#[derive(Deserialize)]
+struct FormatProbe {
+    format_version: u32,
+}
+
+mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut out = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            out.push(DIGITS[(byte >> 4) as usize] as char);
+            out.push(DIGITS[(byte & 0x0f) as usize] as char);
+        }
+        serializer.serialize_str(&out)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        if text.len() % 2 != 0 {
+            return Err(serde::de::Error::custom("hex string has odd length"));
+        }
+        text.as_bytes()
+            .chunks_exact(2)
+            .map(|pair| {
+                let pair = std::str::from_utf8(pair).map_err(serde::de::Error::custom)?;
+                u8::from_str_radix(pair, 16).map_err(serde::de::Error::custom)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule_vector::{AnchorPrecision, RuleVector};
+    use crate::types::{PolicyType, RuleScope};
+    use serde_json::json;
+
+    fn bundled(rule_id: &str) -> BundledRule {
+        BundledRule {
+            metadata: RuleMetadata {
+                rule_id: rule_id.to_string(),
+                priority: 5,
+                scope: RuleScope::global(),
+                layer: Some("L4".to_string()),
+                created_at_ms: 1,
+                enabled: true,
+                description: None,
+                params: json!({"rule_type": "design_boundary"}),
+                policy_type: PolicyType::default(),
+                drift_threshold: 0.0,
+                modification_spec: None,
+                slice_weights: [0.25; 4],
+                not_before: None,
+                expires_at: Some(10),
+                signed_by: None,
+            },
+            anchors: RuleVector::default().encode(AnchorPrecision::F32),
+        }
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let bundle = RuleBundle::new(7, vec![bundled("a"), bundled("b")]);
+        let decoded = RuleBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(decoded.source_version, 7);
+        assert_eq!(decoded.rules.len(), 2);
+        assert_eq!(decoded.rules[1].metadata.rule_id, "b");
+        assert_eq!(decoded.rules[1].metadata.expires_at, Some(10));
+        assert_eq!(decoded.rules[0].anchors, bundle.rules[0].anchors);
+    }
+
+    #[test]
+    fn test_unknown_format_is_refused() {
+        let mut bundle = RuleBundle::new(1, vec![bundled("a")]);
+        bundle.format_version = BUNDLE_FORMAT_VERSION + 1;
+
+        let err = RuleBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap_err();
+        assert!(err.contains("not supported"), "{}", err);
+        assert!(RuleBundle::from_bytes(b"not a bundle").is_err());
+    }
+
+    #[test]
+    fn test_format_1_bundles_are_read() {
+        let mut bundle = RuleBundle::new(1, vec![bundled("a")]);
+        bundle.format_version = 1;
+        bundle.rules[0].anchors = RuleVector::default().to_le_bytes();
+
+        let decoded = RuleBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap();
+        assert!(RuleVector::decode(&decoded.rules[0].anchors).is_ok());
+    }
+}
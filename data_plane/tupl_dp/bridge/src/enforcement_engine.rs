@@ -9,6 +9,7 @@
 //! 6. Returns enforcement decision with evidence
 //! 7. Records complete telemetry to /var/hitlogs for audit trail
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -18,10 +19,14 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::bridge::Bridge;
+use crate::families::net_egress::{resolve_hosts, DnsAnswers};
+use crate::families::{family, EvaluationContext};
 use crate::rule_vector::RuleVector;
 use crate::telemetry::session::SliceComparisonDetail;
 use crate::telemetry::{EnforcementSession, RuleEvaluationEvent, SessionEvent, TelemetryRecorder};
-use crate::types::{Decision, EnforcementDecision, PolicyType, RuleInstance};
+use crate::types::{
+    normalize_tenant, now_ms, Decision, EnforcementDecision, PolicyType, RuleInstance,
+};
 use crate::vector_comparison::{compare_intent_vs_rule, ComparisonResult, DecisionMode};
 
 const CONNECT_TIMEOUT_MS: u64 = 500;
@@ -103,6 +108,15 @@ struct IntentEncodingResponse {
     vector: Vec<f32>,
 }
 
+/// Rule set an evaluation reads from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSource {
+    /// Rules currently enforced
+    Active,
+    /// Candidate rules in the Bridge staging area (dry run, no telemetry)
+    Staged,
+}
+
 // ============================================================================
 // EnforcementEngine Implementation
 // ============================================================================
@@ -154,6 +168,47 @@ impl EnforcementEngine {
         vector_override: Option<[f32; 128]>,
         request_id: &str,
         drift_score: f32,
+    ) -> Result<EnforcementResult, String> {
+        self.enforce_with_source(
+            intent_json,
+            vector_override,
+            request_id,
+            drift_score,
+            RuleSource::Active,
+        )
+        .await
+    }
+
+    /// Evaluate an IntentEvent against the staged rule set without recording telemetry.
+    ///
+    /// Used to test a candidate rule set before it is promoted.
+    pub async fn enforce_staged(
+        &self,
+        intent_json: &str,
+        vector_override: Option<[f32; 128]>,
+        request_id: &str,
+        drift_score: f32,
+    ) -> Result<EnforcementResult, String> {
+        if self.bridge.staged_rule_count().is_none() {
+            return Err("No staged rule set to evaluate".to_string());
+        }
+        self.enforce_with_source(
+            intent_json,
+            vector_override,
+            request_id,
+            drift_score,
+            RuleSource::Staged,
+        )
+        .await
+    }
+
+    async fn enforce_with_source(
+        &self,
+        intent_json: &str,
+        vector_override: Option<[f32; 128]>,
+        request_id: &str,
+        drift_score: f32,
+        source: RuleSource,
     ) -> Result<EnforcementResult, String> {
         let session_start = Instant::now();
 
@@ -164,24 +219,37 @@ impl EnforcementEngine {
         // Layer is optional — default to "" which get_rules_for_layer treats as "match all".
         let layer = intent.layer_str().unwrap_or("");
 
-        println!("Enforcing intent for layer: {}", layer);
+        // Only the requesting tenant's rules are ever applied, and of those only the
+        // calling agent's rules plus global ones.
+        let tenant = normalize_tenant(&intent.tenant_id);
+        let agent = intent.agent_id();
+
+        // Labels matched against rule selector tags. The agent's registered labels win over
+        // those the request carries, so a request cannot relabel its agent.
+        let mut labels = intent.context_labels();
+        if let Some(agent) = agent {
+            labels.extend(self.bridge.agent_labels(tenant, agent));
+        }
+
+        println!("Enforcing intent for tenant {} layer: {}", tenant, layer);
 
-        // Start telemetry session (uses request_id as session_id if non-empty)
-        let session_id = self
-            .telemetry
-            .as_ref()
-            .and_then(|t| t.start_session(layer.to_string(), intent_json.to_string(), request_id));
+        // Start telemetry session (uses request_id as session_id if non-empty).
+        // Staged dry runs are not part of the audit trail.
+        let session_id = match source {
+            RuleSource::Active => self.telemetry.as_ref().and_then(|t| {
+                t.start_session(layer.to_string(), intent_json.to_string(), request_id)
+            }),
+            RuleSource::Staged => None,
+        };
 
         // Populate agent_id and tenant_id from IntentEvent
         if let (Some(ref telemetry), Some(ref sid)) = (&self.telemetry, &session_id) {
             telemetry.with_session(sid, |session| {
                 // Set tenant_id from intent
-                session.tenant_id = Some(intent.tenant_id.clone());
+                session.tenant_id = Some(tenant.to_string());
 
-                // Set agent_id from rate_limit_context if available
-                if let Some(ref rate_limit) = intent.rate_limit_context {
-                    session.agent_id = Some(rate_limit.agent_id.clone());
-                }
+                // Set agent_id to the identity rules are selected for
+                session.agent_id = agent.map(str::to_string);
             });
         }
 
@@ -196,10 +264,76 @@ impl EnforcementEngine {
             });
         }
 
-        // 1. Encode intent to 128d vector (or reuse override)
-        let encoding_start = Instant::now();
+        // 1. Query rules for this layer from Bridge
+        let query_start = Instant::now();
+        let rules = self.get_rules_for_layer(tenant, agent, &labels, layer, source)?;
+        let query_duration = query_start.elapsed().as_micros() as u64;
+
+        if rules.is_empty() {
+            // No rules = fail-closed (BLOCK)
+            println!(
+                "No rules configured for layer {}, blocking by default",
+                layer
+            );
+
+            // Record no rules found
+            if let (Some(ref telemetry), Some(ref sid)) = (&self.telemetry, &session_id) {
+                telemetry.with_session(sid, |session| {
+                    session.add_event(SessionEvent::NoRulesFound {
+                        timestamp_us: EnforcementSession::timestamp_us(),
+                        layer: layer.to_string(),
+                        agent_id: agent.map(str::to_string),
+                    });
+                });
+
+                let total_duration = session_start.elapsed().as_micros() as u64;
+                telemetry.complete_session(sid, 0, total_duration).ok();
+            }
+
+            return Ok(EnforcementResult {
+                decision: 0,
+                slice_similarities: [0.0; 4],
+                rules_evaluated: 0,
+                evidence: vec![],
+                session_id: session_id.clone().unwrap_or_else(|| request_id.to_string()),
+                enforcement_decision: Some(EnforcementDecision {
+                    decision: Decision::Deny,
+                    modified_params: None,
+                    drift_triggered: false,
+                }),
+            });
+        }
+
+        let rules_count = rules.len();
+        println!("Found {} rules for layer {}", rules_count, layer);
 
+        // Record rules queried
         if let (Some(ref telemetry), Some(ref sid)) = (&self.telemetry, &session_id) {
+            telemetry.with_session(sid, |session| {
+                session.add_event(SessionEvent::RulesQueried {
+                    timestamp_us: EnforcementSession::timestamp_us(),
+                    layer: layer.to_string(),
+                    agent_id: agent.map(str::to_string),
+                    rule_count: rules_count,
+                    query_duration_us: query_duration,
+                });
+                session.performance.rule_query_duration_us = query_duration;
+                session.performance.rules_queried = rules_count;
+            });
+        }
+
+        // Only anchor-based rules compare against the intent vector; deterministic ones
+        // are decided without a round trip to the Management Plane.
+        let needs_vector = rules
+            .iter()
+            .any(|rule| family(rule.family_id()).map_or(true, |family| family.requires_anchors));
+
+        // 2. Encode intent to 128d vector (or reuse override)
+        let encoding_start = Instant::now();
+
+        if let (true, Some(ref telemetry), Some(ref sid)) =
+            (needs_vector, &self.telemetry, &session_id)
+        {
             telemetry.with_session(sid, |session| {
                 session.add_event(SessionEvent::EncodingStarted {
                     timestamp_us: EnforcementSession::timestamp_us(),
@@ -211,6 +345,8 @@ impl EnforcementEngine {
         {
             let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
             (vector, 0u64, norm)
+        } else if !needs_vector {
+            ([0.0; 128], 0u64, 0.0)
         } else {
             match self.encode_intent(intent_json).await {
                 Ok(vector) => {
@@ -253,7 +389,9 @@ impl EnforcementEngine {
             }
         };
 
-        if let (Some(ref telemetry), Some(ref sid)) = (&self.telemetry, &session_id) {
+        if let (true, Some(ref telemetry), Some(ref sid)) =
+            (needs_vector, &self.telemetry, &session_id)
+        {
             telemetry.with_session(sid, |session| {
                 session.add_event(SessionEvent::EncodingCompleted {
                     timestamp_us: EnforcementSession::timestamp_us(),
@@ -265,62 +403,6 @@ impl EnforcementEngine {
             });
         }
 
-        // 2. Query rules for this layer from Bridge
-        let query_start = Instant::now();
-        let rules = self.get_rules_for_layer(layer)?;
-        let query_duration = query_start.elapsed().as_micros() as u64;
-
-        if rules.is_empty() {
-            // No rules = fail-closed (BLOCK)
-            println!(
-                "No rules configured for layer {}, blocking by default",
-                layer
-            );
-
-            // Record no rules found
-            if let (Some(ref telemetry), Some(ref sid)) = (&self.telemetry, &session_id) {
-                telemetry.with_session(sid, |session| {
-                    session.add_event(SessionEvent::NoRulesFound {
-                        timestamp_us: EnforcementSession::timestamp_us(),
-                        layer: layer.to_string(),
-                    });
-                });
-
-                let total_duration = session_start.elapsed().as_micros() as u64;
-                telemetry.complete_session(sid, 0, total_duration).ok();
-            }
-
-            return Ok(EnforcementResult {
-                decision: 0,
-                slice_similarities: [0.0; 4],
-                rules_evaluated: 0,
-                evidence: vec![],
-                session_id: session_id.clone().unwrap_or_else(|| request_id.to_string()),
-                enforcement_decision: Some(EnforcementDecision {
-                    decision: Decision::Deny,
-                    modified_params: None,
-                    drift_triggered: false,
-                }),
-            });
-        }
-
-        let rules_count = rules.len();
-        println!("Found {} rules for layer {}", rules_count, layer);
-
-        // Record rules queried
-        if let (Some(ref telemetry), Some(ref sid)) = (&self.telemetry, &session_id) {
-            telemetry.with_session(sid, |session| {
-                session.add_event(SessionEvent::RulesQueried {
-                    timestamp_us: EnforcementSession::timestamp_us(),
-                    layer: layer.to_string(),
-                    rule_count: rules_count,
-                    query_duration_us: query_duration,
-                });
-                session.performance.rule_query_duration_us = query_duration;
-                session.performance.rules_queried = rules_count;
-            });
-        }
-
         // 3. Five-pass AARM evaluation.
         //    All passes operate on the same rule set, partitioned by policy_type.
         let mut evidence = Vec::new();
@@ -342,6 +424,26 @@ impl EnforcementEngine {
             }
         }
 
+        // Hostnames deterministic rules match by address are looked up once, up front.
+        let mut hosts: Vec<String> = rules
+            .iter()
+            .flat_map(|rule| rule.dns_lookups(&intent))
+            .collect();
+        hosts.sort();
+        hosts.dedup();
+        let dns = if hosts.is_empty() {
+            DnsAnswers::new()
+        } else {
+            resolve_hosts(hosts).await
+        };
+
+        // Counters deterministic rules consult; dry runs must not consume them.
+        let context = EvaluationContext {
+            rate_limits: self.bridge.rate_limits(),
+            dns: &dns,
+            dry_run: source == RuleSource::Staged,
+        };
+
         // Helper closure: evaluate a single rule vector comparison and record telemetry.
         // Returns (ComparisonResult, rule_vector) or an Err.
         let evaluate_rule = |rule: &Arc<dyn RuleInstance>,
@@ -357,34 +459,68 @@ impl EnforcementEngine {
                 });
             }
 
-            let rule_vector =
-                self.bridge
-                    .get_rule_anchors(rule.rule_id())
-                    .ok_or_else(|| {
-                        format!(
-                            "Rule '{}' missing pre-encoded anchors (install-time encoding incomplete)",
-                            rule.rule_id()
-                        )
-                    })?;
-
-            let weights = self.get_rule_weights(rule);
-            let (ev_thresholds, ev_decision_mode) = self.get_rule_thresholds(rule)?;
-            let cmp = self.compare_with_sandbox(
-                &intent_vector,
-                &rule_vector,
-                ev_thresholds,
-                ev_decision_mode,
-                weights,
-            )?;
-            let rule_eval_duration = 0u64; // timing not re-measured in closure for simplicity
-
             let slice_names = ["action", "resource", "data", "risk"];
-            let triggering_slice = slice_names[cmp.triggering_slice_idx].to_string();
 
-            let scoring_mode = match ev_decision_mode {
-                DecisionMode::WeightedAvgMode => "weighted-avg".to_string(),
-                DecisionMode::MinMode => "min".to_string(),
-            };
+            // Deterministic families decide without anchors; a match counts as full
+            // similarity so the passes below treat both kinds alike.
+            let (cmp, rule_vector, ev_thresholds, triggering_slice, anchor_matched, scoring_mode) =
+                match rule.evaluate(&intent, &context) {
+                    Some(outcome) => {
+                        let similarity = if outcome.matched { 1.0 } else { 0.0 };
+                        let cmp = ComparisonResult {
+                            decision: outcome.matched as u8,
+                            slice_similarities: [similarity; 4],
+                            triggering_slice_idx: 0,
+                        };
+                        (
+                            cmp,
+                            RuleVector::default(),
+                            [0.0; 4],
+                            String::new(),
+                            outcome.detail,
+                            "deterministic".to_string(),
+                        )
+                    }
+                    None => {
+                        let anchors = match source {
+                            RuleSource::Active => self.bridge.get_rule_anchors(rule.rule_id()),
+                            RuleSource::Staged => {
+                                self.bridge.get_staged_rule_anchors(rule.rule_id())
+                            }
+                        };
+                        let rule_vector = anchors.ok_or_else(|| {
+                            format!(
+                                "Rule '{}' missing pre-encoded anchors (install-time encoding incomplete)",
+                                rule.rule_id()
+                            )
+                        })?;
+
+                        let weights = self.get_rule_weights(rule);
+                        let (ev_thresholds, ev_decision_mode) = self.get_rule_thresholds(rule)?;
+                        let cmp = self.compare_with_sandbox(
+                            &intent_vector,
+                            &rule_vector,
+                            ev_thresholds,
+                            ev_decision_mode,
+                            weights,
+                        )?;
+
+                        let triggering_slice = slice_names[cmp.triggering_slice_idx].to_string();
+                        let scoring_mode = match ev_decision_mode {
+                            DecisionMode::WeightedAvgMode => "weighted-avg".to_string(),
+                            DecisionMode::MinMode => "min".to_string(),
+                        };
+                        (
+                            cmp,
+                            rule_vector,
+                            ev_thresholds,
+                            triggering_slice,
+                            String::new(),
+                            scoring_mode,
+                        )
+                    }
+                };
+            let rule_eval_duration = 0u64; // timing not re-measured in closure for simplicity
 
             evidence.push(RuleEvidence {
                 rule_id: rule.rule_id().to_string(),
@@ -392,7 +528,7 @@ impl EnforcementEngine {
                 decision: cmp.decision,
                 similarities: cmp.slice_similarities,
                 triggering_slice,
-                anchor_matched: String::new(),
+                anchor_matched,
                 thresholds: ev_thresholds,
                 scoring_mode,
             });
@@ -400,12 +536,7 @@ impl EnforcementEngine {
             if let (Some(ref telemetry), Some(ref sid)) = (&self.telemetry, &session_id) {
                 let thresholds = ev_thresholds;
                 let slice_details = self.build_slice_details(&cmp, &thresholds);
-                let payload = rule.management_plane_payload();
-                let rule_family = payload
-                    .get("rule_type")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("design_boundary")
-                    .to_string();
+                let rule_family = rule.family_id().to_string();
 
                 telemetry.with_session(sid, |session| {
                     session.add_event(SessionEvent::RuleEvaluationCompleted {
@@ -484,17 +615,20 @@ impl EnforcementEngine {
 
         // -----------------------------------------------------------------------
         // Pass 1 — FORBIDDEN
-        //   Any match → DENY immediately. Drift is irrelevant.
+        //   Any match → DENY immediately (or the rule's own deny decision, e.g.
+        //   STEP_UP for rate limits). Drift is irrelevant.
         // -----------------------------------------------------------------------
         for rule in &forbidden_rules {
             let (cmp, _) = evaluate_rule(rule, &mut evidence)?;
             if cmp.decision == 1 {
+                let decision = rule.deny_decision();
                 println!(
-                    "DENY (FORBIDDEN): rule '{}' matched — blocking immediately",
+                    "{} (FORBIDDEN): rule '{}' matched — blocking immediately",
+                    decision,
                     rule.rule_id()
                 );
                 let ed = EnforcementDecision {
-                    decision: Decision::Deny,
+                    decision,
                     modified_params: None,
                     drift_triggered: false,
                 };
@@ -516,6 +650,7 @@ impl EnforcementEngine {
         // Pass 2 — CONTEXT_DENY
         //   Match + drift exceeded → DENY with drift_triggered = true.
         //   Match + drift disabled (threshold == 0.0) → DENY, drift_triggered = false.
+        //   DENY is the rule's deny decision, as in pass 1.
         // -----------------------------------------------------------------------
         for rule in &context_deny_rules {
             let (cmp, _) = evaluate_rule(rule, &mut evidence)?;
@@ -528,13 +663,15 @@ impl EnforcementEngine {
                     (false, true)
                 };
                 if deny {
+                    let decision = rule.deny_decision();
                     println!(
-                        "DENY (CONTEXT_DENY): rule '{}' matched (drift_triggered={})",
+                        "{} (CONTEXT_DENY): rule '{}' matched (drift_triggered={})",
+                        decision,
                         rule.rule_id(),
                         drift_triggered
                     );
                     let ed = EnforcementDecision {
-                        decision: Decision::Deny,
+                        decision,
                         modified_params: None,
                         drift_triggered,
                     };
@@ -728,25 +865,37 @@ impl EnforcementEngine {
         Ok(vector)
     }
 
-    /// Query rules for a specific layer from Bridge
-    fn get_rules_for_layer(&self, layer: &str) -> Result<Vec<Arc<dyn RuleInstance>>, String> {
-        println!("Querying rules for layer: {}", layer);
-
-        let requested_layer = if layer.is_empty() { None } else { Some(layer) };
-
-        let mut filtered: Vec<_> = self
-            .bridge
-            .all_rules()
-            .into_iter()
-            .filter(|rule| rule.is_enabled())
-            .filter(|rule| match (rule.layer(), requested_layer) {
-                (None, _) => true,
-                (Some(rule_layer), Some(requested)) => rule_layer == requested,
-                (Some(_), None) => false,
-            })
-            .collect();
+    /// Query the rules of a tenant that apply to an agent for a specific layer from Bridge
+    ///
+    /// Without an agent identity only global rules apply. Rules with selector tags only
+    /// apply when every tag matches one of `labels`.
+    fn get_rules_for_layer(
+        &self,
+        tenant: &str,
+        agent: Option<&str>,
+        labels: &HashMap<String, String>,
+        layer: &str,
+        source: RuleSource,
+    ) -> Result<Vec<Arc<dyn RuleInstance>>, String> {
+        println!(
+            "Querying rules for layer: {} (agent: {})",
+            layer,
+            agent.unwrap_or("-")
+        );
+
+        // Enabled rules for the layer (plus layer-less rules) whose scope applies to the
+        // agent, pre-sorted by priority. The empty agent matches only global rules.
+        let agent = agent.unwrap_or("");
+        let mut filtered = match source {
+            RuleSource::Active => self.bridge.rules_for(Some(tenant), Some(agent), layer),
+            RuleSource::Staged => self
+                .bridge
+                .staged_rules_for(Some(tenant), Some(agent), layer),
+        };
 
-        filtered.sort_by(|a, b| b.priority().cmp(&a.priority()));
+        // Rules outside their validity window are ignored until the sweeper archives them.
+        let now = now_ms();
+        filtered.retain(|rule| rule.is_effective_at(now) && rule.scope().selects(labels));
 
         println!("Found {} rules for layer {}", filtered.len(), layer);
         Ok(filtered)
@@ -0,0 +1,288 @@
+//! # Data Sensitivity Rules
+//!
+//! Deterministic rules over the structured `data` and `risk` fields of an intent, which
+//! otherwise only reach enforcement through the semantic encoding.
+//!
+//! ```json
+//! {"rule_type": "data_sensitivity", "sensitivity": ["restricted"], "volume": ["bulk"]}
+//! {"rule_type": "data_sensitivity", "pii": true, "authn_not": ["strong"], "on_match": "step_up"}
+//! ```
+//!
+//! A rule matches a call that meets every condition it sets:
+//! - `sensitivity` / `sensitivity_not`: some / none of the listed labels are in `data.sensitivity`
+//! - `volume` / `volume_not`: `data.volume` is / is not one of the listed values
+//! - `authn` / `authn_not`: `risk.authn` is / is not one of the listed values
+//! - `pii`: `data.pii` equals the given flag (an unreported `pii` never matches)
+//!
+//! Values compare case-insensitively, and a missing `data.volume` is not any listed value.
+//! Matches act under every policy type like semantic matches do; a FORBIDDEN or
+//! CONTEXT_DENY match yields `on_match` (DENY or STEP_UP).
+
+use serde_json::Value;
+
+use super::params::{check_on_match, list_param, on_match_param};
+use super::EvaluationContext;
+use crate::api_types::IntentEvent;
+use crate::types::{Decision, RuleInstance, RuleMatch, RuleMetadata};
+
+/// Family id (`rule_type`) of data sensitivity rules.
+pub const FAMILY_ID: &str = "data_sensitivity";
+
+/// Condition on a single-valued field: is, or is not, one of some values.
+#[derive(Debug, Clone, Default)]
+struct OneOf {
+    any: Vec<String>,
+    none: Vec<String>,
+}
+
+impl OneOf {
+    fn is_set(&self) -> bool {
+        !self.any.is_empty() || !self.none.is_empty()
+    }
+
+    fn matches(&self, value: Option<&str>) -> bool {
+        let listed = |values: &[String]| {
+            value.is_some_and(|value| values.iter().any(|v| v.eq_ignore_ascii_case(value)))
+        };
+        (self.any.is_empty() || listed(&self.any)) && !listed(&self.none)
+    }
+}
+
+/// Parsed conditions of a rule.
+#[derive(Debug, Clone)]
+struct DataSpec {
+    sensitivity: Vec<String>,
+    sensitivity_not: Vec<String>,
+    volume: OneOf,
+    authn: OneOf,
+    pii: Option<bool>,
+    on_match: Decision,
+}
+
+impl DataSpec {
+    fn parse(params: &Value) -> Result<Self, String> {
+        let strings = |key: &str| -> Result<Vec<String>, String> {
+            list_param(params, key)?
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(text) if !text.trim().is_empty() => Ok(text.trim().to_string()),
+                    other => Err(format!(
+                        "{} entries must be non-empty strings, got {}",
+                        key, other
+                    )),
+                })
+                .collect()
+        };
+
+        let pii = match params.get("pii") {
+            None | Some(Value::Null) => None,
+            Some(Value::Bool(pii)) => Some(*pii),
+            Some(Value::String(text)) if text == "true" || text == "false" => Some(text == "true"),
+            Some(other) => return Err(format!("pii must be a bool, got {}", other)),
+        };
+
+        let spec = Self {
+            sensitivity: strings("sensitivity")?,
+            sensitivity_not: strings("sensitivity_not")?,
+            volume: OneOf {
+                any: strings("volume")?,
+                none: strings("volume_not")?,
+            },
+            authn: OneOf {
+                any: strings("authn")?,
+                none: strings("authn_not")?,
+            },
+            pii,
+            on_match: on_match_param(params)?,
+        };
+        let conditioned = !spec.sensitivity.is_empty()
+            || !spec.sensitivity_not.is_empty()
+            || spec.volume.is_set()
+            || spec.authn.is_set()
+            || spec.pii.is_some();
+        if !conditioned {
+            return Err("at least one condition is required".to_string());
+        }
+        Ok(spec)
+    }
+
+    /// Returns the first condition the intent fails, or None when it meets all of them.
+    fn unmet(&self, intent: &IntentEvent) -> Option<String> {
+        let labels = &intent.data.sensitivity;
+        let labelled = |label: &String| labels.iter().any(|l| l.eq_ignore_ascii_case(label));
+
+        if !self.sensitivity.is_empty() && !self.sensitivity.iter().any(labelled) {
+            return Some(format!(
+                "sensitivity {:?} has none of {:?}",
+                labels, self.sensitivity
+            ));
+        }
+        if let Some(label) = self.sensitivity_not.iter().find(|label| labelled(label)) {
+            return Some(format!("sensitivity includes {}", label));
+        }
+        let volume = intent.data.volume.as_deref();
+        if !self.volume.matches(volume) {
+            return Some(format!("volume {} does not match", volume.unwrap_or("-")));
+        }
+        if !self.authn.matches(Some(&intent.risk.authn)) {
+            return Some(format!("authn {} does not match", intent.risk.authn));
+        }
+        if let Some(pii) = self.pii {
+            if intent.data.pii != Some(pii) {
+                return Some(format!("pii is not {}", pii));
+            }
+        }
+        None
+    }
+}
+
+/// Checks a data sensitivity rule's conditions and its on_match decision against its
+/// policy type.
+pub fn validate(metadata: &RuleMetadata) -> Result<(), String> {
+    let spec = DataSpec::parse(&metadata.params)?;
+    check_on_match(&spec.on_match, &metadata.policy_type)
+}
+
+/// Deterministic rule over an intent's data sensitivity, PII, volume and authn fields.
+#[derive(Debug)]
+pub struct DataSensitivityRule {
+    metadata: RuleMetadata,
+    spec: DataSpec,
+}
+
+impl DataSensitivityRule {
+    /// Builds a rule from its metadata. Fails when the params do not parse.
+    pub fn from_metadata(metadata: RuleMetadata) -> Result<Self, String> {
+        let spec = DataSpec::parse(&metadata.params)?;
+        Ok(Self { metadata, spec })
+    }
+}
+
+impl RuleInstance for DataSensitivityRule {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn family_id(&self) -> &str {
+        FAMILY_ID
+    }
+
+    fn evaluate(&self, intent: &IntentEvent, _context: &EvaluationContext) -> Option<RuleMatch> {
+        let (matched, detail) = match self.spec.unmet(intent) {
+            Some(unmet) => (false, unmet),
+            None => (true, "all data conditions met".to_string()),
+        };
+        Some(RuleMatch { matched, detail })
+    }
+
+    fn deny_decision(&self) -> Decision {
+        self.spec.on_match.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::families::test_support::{evaluate, intent, metadata};
+    use crate::types::PolicyType;
+    use serde_json::json;
+
+    fn data_access(data: Value, authn: &str) -> IntentEvent {
+        intent(json!({
+            "action": "read",
+            "resource": {"type": "database", "name": "users_db", "location": "cloud"},
+            "data": data,
+            "risk": {"authn": authn}
+        }))
+    }
+
+    fn matched(rule: &DataSensitivityRule, intent: &IntentEvent) -> bool {
+        evaluate(rule, intent).matched
+    }
+
+    #[test]
+    fn test_sensitivity_and_volume_conditions() {
+        let rule = DataSensitivityRule::from_metadata(metadata(
+            PolicyType::Forbidden,
+            json!({"rule_type": FAMILY_ID, "sensitivity": ["restricted"], "volume": "bulk"}),
+        ))
+        .unwrap();
+        let data = |sensitivity: Value, volume: Value| {
+            data_access(
+                json!({"sensitivity": sensitivity, "pii": false, "volume": volume}),
+                "required",
+            )
+        };
+
+        assert!(matched(
+            &rule,
+            &data(json!(["internal", "Restricted"]), json!("BULK"))
+        ));
+        assert!(!matched(
+            &rule,
+            &data(json!(["restricted"]), json!("single"))
+        ));
+        assert!(!matched(&rule, &data(json!(["restricted"]), Value::Null)));
+        assert!(!matched(&rule, &data(json!(["internal"]), json!("bulk"))));
+        assert_eq!(rule.deny_decision(), Decision::Deny);
+    }
+
+    #[test]
+    fn test_pii_and_authn_conditions() {
+        let rule = DataSensitivityRule::from_metadata(metadata(
+            PolicyType::ContextDeny,
+            json!({"pii": true, "authn_not": ["strong"], "sensitivity_not": ["public"],
+                   "on_match": "step_up"}),
+        ))
+        .unwrap();
+        let data = |pii: Value, sensitivity: &str| {
+            json!({"sensitivity": [sensitivity], "pii": pii,
+                   "volume": "single"})
+        };
+
+        assert!(matched(
+            &rule,
+            &data_access(data(json!(true), "internal"), "required")
+        ));
+        assert!(!matched(
+            &rule,
+            &data_access(data(json!(true), "internal"), "strong")
+        ));
+        assert!(!matched(
+            &rule,
+            &data_access(data(json!(false), "internal"), "required")
+        ));
+        assert!(!matched(
+            &rule,
+            &data_access(data(Value::Null, "internal"), "required")
+        ));
+        assert!(!matched(
+            &rule,
+            &data_access(data(json!(true), "public"), "required")
+        ));
+        assert_eq!(rule.deny_decision(), Decision::StepUp);
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_conditions() {
+        assert!(validate(&metadata(PolicyType::Forbidden, json!({"pii": "true"}))).is_ok());
+        for params in [
+            json!({"rule_type": FAMILY_ID}),
+            json!({"pii": 1}),
+            json!({"sensitivity": [""]}),
+            json!({"volume": [3]}),
+            json!({"pii": true, "on_match": "defer"}),
+        ] {
+            assert!(
+                validate(&metadata(PolicyType::Forbidden, params.clone())).is_err(),
+                "{}",
+                params
+            );
+        }
+        assert!(validate(&metadata(
+            PolicyType::ContextAllow,
+            json!({"pii": true, "on_match": "step_up"})
+        ))
+        .is_err());
+    }
+}
@@ -1,26 +1,14 @@
 use serde_json::Value;
 
-use crate::types::{PolicyType, RuleInstance, RuleScope};
+use crate::types::{PolicyType, RuleInstance, RuleMetadata, RuleScope};
+
+/// Family id (`rule_type`) of design boundary rules.
+pub const FAMILY_ID: &str = "design_boundary";
 
 /// Lightweight rule instance representing a DesignBoundary-derived rule.
 #[derive(Debug)]
 pub struct DesignBoundaryRule {
-    rule_id: String,
-    priority: u32,
-    scope: RuleScope,
-    layer: Option<String>,
-    created_at_ms: u64,
-    description: Option<String>,
-    enabled: bool,
-    params: Value,
-    /// AARM policy classification for this rule.
-    policy_type: PolicyType,
-    /// Drift threshold; 0.0 means drift enforcement is disabled.
-    drift_threshold: f32,
-    /// Optional JSON patch applied when decision is MODIFY.
-    modification_spec: Option<Value>,
-    /// Per-slice weights [action, resource, data, risk].
-    slice_weights: [f32; 4],
+    metadata: RuleMetadata,
 }
 
 impl DesignBoundaryRule {
@@ -34,20 +22,20 @@ impl DesignBoundaryRule {
         description: Option<String>,
         params: Value,
     ) -> Self {
-        Self {
+        Self::new_with_policy(
             rule_id,
             priority,
             scope,
             layer,
             created_at_ms,
-            description,
             enabled,
+            description,
             params,
-            policy_type: PolicyType::default(),
-            drift_threshold: 0.0,
-            modification_spec: None,
-            slice_weights: [0.25; 4],
-        }
+            PolicyType::default(),
+            0.0,
+            None,
+            [0.25; 4],
+        )
     }
 
     /// Construct with explicit AARM policy fields.
@@ -65,69 +53,62 @@ impl DesignBoundaryRule {
         modification_spec: Option<Value>,
         slice_weights: [f32; 4],
     ) -> Self {
-        Self {
+        Self::from_metadata(RuleMetadata {
             rule_id,
             priority,
             scope,
             layer,
             created_at_ms,
-            description,
             enabled,
+            description,
             params,
             policy_type,
             drift_threshold,
             modification_spec,
             slice_weights,
-        }
-    }
-}
-
-impl RuleInstance for DesignBoundaryRule {
-    fn rule_id(&self) -> &str {
-        &self.rule_id
-    }
-
-    fn priority(&self) -> u32 {
-        self.priority
+            not_before: None,
+            expires_at: None,
+            signed_by: None,
+        })
     }
 
-    fn scope(&self) -> &RuleScope {
-        &self.scope
+    /// Restricts the rule to a validity window (epoch ms; `expires_at` is exclusive).
+    pub fn with_validity(mut self, not_before: Option<u64>, expires_at: Option<u64>) -> Self {
+        self.metadata.not_before = not_before;
+        self.metadata.expires_at = expires_at;
+        self
     }
 
-    fn layer(&self) -> Option<&str> {
-        self.layer.as_deref()
+    /// Records the trusted key id that signed the rule.
+    pub fn with_signer(mut self, signed_by: Option<String>) -> Self {
+        self.metadata.signed_by = signed_by;
+        self
     }
 
-    fn created_at(&self) -> u64 {
-        self.created_at_ms
-    }
-
-    fn description(&self) -> Option<&str> {
-        self.description.as_deref()
-    }
-
-    fn is_enabled(&self) -> bool {
-        self.enabled
-    }
-
-    fn management_plane_payload(&self) -> Value {
-        self.params.clone()
-    }
-
-    fn policy_type(&self) -> PolicyType {
-        self.policy_type.clone()
+    /// Rebuilds a rule from its persisted metadata, including AARM policy fields.
+    pub fn from_metadata(metadata: RuleMetadata) -> Self {
+        Self { metadata }
     }
+}
 
-    fn drift_threshold(&self) -> f32 {
-        self.drift_threshold
+/// Checks that a declared `rule_decision` is one the enforcement engine can score with.
+pub fn validate(metadata: &RuleMetadata) -> Result<(), String> {
+    match metadata.params.get("rule_decision") {
+        None => Ok(()),
+        Some(Value::String(mode)) if mode == "min" || mode == "weighted-avg" => Ok(()),
+        Some(other) => Err(format!(
+            "rule_decision {} is not 'min' or 'weighted-avg'",
+            other
+        )),
     }
+}
 
-    fn modification_spec(&self) -> Option<&Value> {
-        self.modification_spec.as_ref()
+impl RuleInstance for DesignBoundaryRule {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
     }
 
-    fn slice_weights(&self) -> [f32; 4] {
-        self.slice_weights
+    fn family_id(&self) -> &str {
+        FAMILY_ID
     }
 }
@@ -1,8 +1,35 @@
 //! # Rule Families Module
 //!
-//! Contains rule implementations supported by the bridge.
+//! Contains rule implementations supported by the bridge and the registry that maps
+//! family ids to them.
 
+pub mod data_sensitivity;
 pub mod design_boundary;
+pub mod net_egress;
+pub(crate) mod params;
+pub mod rate_limit;
+pub mod registry;
+pub mod tool_constraint;
+
+#[cfg(test)]
+mod test_support;
 
 // Re-export rule types
+pub use data_sensitivity::DataSensitivityRule;
 pub use design_boundary::DesignBoundaryRule;
+pub use net_egress::NetworkEgressRule;
+pub use rate_limit::{RateLimitRule, RateLimiter};
+pub use registry::{family, family_ids, RuleFamily, FAMILIES};
+pub use tool_constraint::ToolConstraintRule;
+
+use net_egress::DnsAnswers;
+
+/// Data-plane state deterministic rules read while evaluating an intent.
+#[derive(Debug, Clone, Copy)]
+pub struct EvaluationContext<'a> {
+    pub rate_limits: &'a RateLimiter,
+    /// Lookups of the hostnames rules asked for through `dns_lookups`
+    pub dns: &'a DnsAnswers,
+    /// Evaluation must not change any state (staged dry runs)
+    pub dry_run: bool,
+}
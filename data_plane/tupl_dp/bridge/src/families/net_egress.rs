@@ -0,0 +1,710 @@
+//! # Network Egress Rules
+//!
+//! Deterministic L0 rules over the network destinations a call reaches.
+//!
+//! Destinations are read from `resource.location` when it is a URL, `host:port` or IP
+//! address, and from `tool_params` at any depth: every string that is a URL, an IP
+//! address or a dotted `host:port` (e.g. `evil.com:443`), and every object with a `host`
+//! entry (with optional `port` and `protocol` siblings). A rule covers a destination
+//! that matches every selector it sets:
+//!
+//! ```json
+//! {"rule_type": "net_egress", "dest_domains": ["*.example.com"], "dest_cidrs": ["10.0.0.0/8"],
+//!  "ports": [443, "8000-8080"], "protocols": ["https"]}
+//! ```
+//!
+//! The host must match one of `dest_domains` (globs, case-insensitive) or fall in one of
+//! `dest_cidrs`. Hostnames are not resolved, so domains only match domain patterns and
+//! addresses only CIDR ranges, unless the rule sets `"resolve_dns": true`. The engine
+//! then looks the hostnames up before evaluating, each within `DNS_TIMEOUT`; a host
+//! that cannot be resolved is covered by FORBIDDEN, CONTEXT_DENY and CONTEXT_DEFER rules
+//! and never by CONTEXT_ALLOW rules.
+//!
+//! A rule matches a call whose destinations are all covered under CONTEXT_ALLOW, and a
+//! call with any covered destination under FORBIDDEN, CONTEXT_DENY and CONTEXT_DEFER.
+//! Calls without a destination never match.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use serde_json::Value;
+use url::Url;
+
+use super::params::{glob_match, list_param};
+use super::EvaluationContext;
+use crate::api_types::IntentEvent;
+use crate::types::{NetworkProtocol, PolicyType, RuleInstance, RuleMatch, RuleMetadata};
+
+/// Family id (`rule_type`) of network egress rules.
+pub const FAMILY_ID: &str = "net_egress";
+
+/// How long a hostname lookup may take before it counts as failed.
+pub const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Addresses of looked-up hostnames; None when the lookup failed or timed out.
+pub type DnsAnswers = HashMap<String, Option<Vec<IpAddr>>>;
+
+/// Looks up hostnames concurrently, each within `DNS_TIMEOUT`.
+pub async fn resolve_hosts(hosts: Vec<String>) -> DnsAnswers {
+    let mut lookups = tokio::task::JoinSet::new();
+    for host in hosts {
+        lookups.spawn(async move {
+            let lookup = tokio::net::lookup_host((host.as_str(), 0));
+            let addrs = match tokio::time::timeout(DNS_TIMEOUT, lookup).await {
+                Ok(Ok(addrs)) => Some(addrs.map(|addr| canonical(addr.ip())).collect()),
+                Ok(Err(e)) => {
+                    log::warn!("Could not resolve {}: {}", host, e);
+                    None
+                }
+                Err(_) => {
+                    log::warn!("Resolving {} timed out", host);
+                    None
+                }
+            };
+            (host, addrs)
+        });
+    }
+
+    let mut answers = DnsAnswers::new();
+    while let Some(answer) = lookups.join_next().await {
+        if let Ok((host, addrs)) = answer {
+            answers.insert(host, addrs);
+        }
+    }
+    answers
+}
+
+/// Maps IPv4-mapped IPv6 addresses to IPv4 so both forms match IPv4 ranges.
+fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(addr),
+        v4 => v4,
+    }
+}
+
+/// Address as an integer and its width in bits.
+fn address_bits(addr: IpAddr) -> (u128, u32) {
+    match addr {
+        IpAddr::V4(v4) => (u32::from(v4) as u128, 32),
+        IpAddr::V6(v6) => (u128::from(v6), 128),
+    }
+}
+
+/// An address range such as `10.0.0.0/8`; a bare address is a single-host range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cidr {
+    network: IpAddr,
+    prefix: u32,
+}
+
+impl Cidr {
+    fn parse(text: &str) -> Result<Self, String> {
+        let (addr, prefix) = match text.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (text, None),
+        };
+        let network: IpAddr = addr
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid CIDR range '{}'", text))?;
+        let (_, bits) = address_bits(network);
+        let prefix = match prefix {
+            None => bits,
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|prefix| *prefix <= bits)
+                .ok_or_else(|| format!("invalid prefix length in '{}'", text))?,
+        };
+        Ok(Self { network, prefix })
+    }
+
+    fn contains(&self, addr: IpAddr) -> bool {
+        let (network, bits) = address_bits(self.network);
+        let (addr, addr_bits) = address_bits(canonical(addr));
+        bits == addr_bits
+            && (network ^ addr)
+                .checked_shr(bits - self.prefix)
+                .unwrap_or(0)
+                == 0
+    }
+}
+
+/// A network destination of a call.
+#[derive(Debug, Clone, PartialEq)]
+struct Destination {
+    /// Lowercase, without IPv6 brackets or a trailing dot
+    host: String,
+    ip: Option<IpAddr>,
+    port: Option<u16>,
+    protocol: NetworkProtocol,
+}
+
+impl Destination {
+    fn new(host: &str, port: Option<u16>, protocol: NetworkProtocol) -> Option<Self> {
+        let host = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        if host.is_empty() {
+            return None;
+        }
+        let ip = host.parse::<IpAddr>().ok().map(canonical);
+        Some(Self {
+            host,
+            ip,
+            port,
+            protocol,
+        })
+    }
+
+    /// Parses a URL, `host:port` or IP address; other text (e.g. "cloud") is not a destination.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.contains("://") {
+            let url = Url::parse(text).ok()?;
+            let protocol = NetworkProtocol::from_scheme(url.scheme());
+            return Self::new(url.host_str()?, url.port_or_known_default(), protocol);
+        }
+        if let Ok(ip) = text.parse::<IpAddr>() {
+            return Self::new(&ip.to_string(), None, NetworkProtocol::default());
+        }
+        if let Ok(addr) = text.parse::<SocketAddr>() {
+            return Self::new(
+                &addr.ip().to_string(),
+                Some(addr.port()),
+                NetworkProtocol::default(),
+            );
+        }
+        let (host, port) = text.rsplit_once(':')?;
+        let port = port.parse::<u16>().ok()?;
+        if host.contains(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-')) {
+            return None;
+        }
+        Self::new(host, Some(port), NetworkProtocol::default())
+    }
+
+    /// Parses a string found in `tool_params`. Unlike a location, a `host:port` there
+    /// needs a dotted hostname, so values such as "12:30" or "sort:asc" are not taken
+    /// for destinations.
+    fn parse_param(text: &str) -> Option<Self> {
+        let destination = Self::parse(text)?;
+        if text.contains("://") || destination.ip.is_some() || destination.host == "localhost" {
+            return Some(destination);
+        }
+        match destination.host.rsplit_once('.') {
+            Some((_, tld)) if !tld.chars().all(|c| c.is_ascii_digit()) => Some(destination),
+            _ => None,
+        }
+    }
+
+    /// Parses a `host` entry of `tool_params` with its `port` and `protocol` siblings.
+    fn from_host_params(params: &Value) -> Option<Self> {
+        let host = params.get("host")?.as_str()?;
+        let port = params.get("port").and_then(|port| match port {
+            Value::String(text) => text.parse::<u16>().ok(),
+            other => other.as_u64().and_then(|port| u16::try_from(port).ok()),
+        });
+        let protocol = params
+            .get("protocol")
+            .and_then(Value::as_str)
+            .and_then(NetworkProtocol::parse)
+            .unwrap_or_default();
+        match Self::parse(host) {
+            Some(parsed) if parsed.port.is_some() || host.contains("://") => Some(parsed),
+            _ => Self::new(host, port, protocol),
+        }
+    }
+}
+
+impl fmt::Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}://", self.protocol.as_str())?;
+        match self.ip {
+            Some(IpAddr::V6(_)) => write!(f, "[{}]", self.host)?,
+            _ => write!(f, "{}", self.host)?,
+        }
+        match self.port {
+            Some(port) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Collects the destinations a call reaches.
+fn destinations(intent: &IntentEvent) -> Vec<Destination> {
+    let mut found: Vec<Destination> = Vec::new();
+    let mut push = |destination: Option<Destination>| {
+        if let Some(destination) = destination {
+            if !found.contains(&destination) {
+                found.push(destination);
+            }
+        }
+    };
+
+    push(
+        intent
+            .resource
+            .location
+            .as_deref()
+            .and_then(Destination::parse),
+    );
+    if let Some(params) = &intent.tool_params {
+        param_destinations(params, &mut push);
+    }
+    found
+}
+
+/// Walks `tool_params` depth-first for destinations.
+fn param_destinations(value: &Value, push: &mut impl FnMut(Option<Destination>)) {
+    match value {
+        Value::String(text) => push(Destination::parse_param(text)),
+        Value::Array(items) => {
+            for item in items {
+                param_destinations(item, push);
+            }
+        }
+        Value::Object(entries) => {
+            push(Destination::from_host_params(value));
+            for (key, item) in entries {
+                // A string host is read with its port and protocol siblings above.
+                if key != "host" || !item.is_string() {
+                    param_destinations(item, push);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn parse_port(value: &Value) -> Result<(u16, u16), String> {
+    let port = |text: &str| {
+        text.trim()
+            .parse::<u16>()
+            .map_err(|_| format!("invalid port '{}'", text))
+    };
+    let (low, high) = match value {
+        Value::Number(number) => {
+            let port = number
+                .as_u64()
+                .and_then(|port| u16::try_from(port).ok())
+                .ok_or_else(|| format!("invalid port {}", number))?;
+            (port, port)
+        }
+        Value::String(text) => match text.split_once('-') {
+            Some((low, high)) => (port(low)?, port(high)?),
+            None => (port(text)?, port(text)?),
+        },
+        other => return Err(format!("invalid port {}", other)),
+    };
+    if low > high {
+        return Err(format!("port range {}-{} is empty", low, high));
+    }
+    Ok((low, high))
+}
+
+/// Parsed destination selectors of a rule.
+#[derive(Debug, Clone)]
+struct EgressSpec {
+    /// Lowercase domain globs
+    domains: Vec<String>,
+    cidrs: Vec<Cidr>,
+    /// Inclusive port ranges
+    ports: Vec<(u16, u16)>,
+    protocols: Vec<NetworkProtocol>,
+    resolve_dns: bool,
+}
+
+impl EgressSpec {
+    fn parse(params: &Value) -> Result<Self, String> {
+        let strings = |key: &str| -> Result<Vec<String>, String> {
+            list_param(params, key)?
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(text) if !text.trim().is_empty() => Ok(text.trim().to_string()),
+                    other => Err(format!(
+                        "{} entries must be non-empty strings, got {}",
+                        key, other
+                    )),
+                })
+                .collect()
+        };
+
+        let domains = strings("dest_domains")?
+            .into_iter()
+            .map(|domain| domain.trim_end_matches('.').to_ascii_lowercase())
+            .collect();
+        let cidrs = strings("dest_cidrs")?
+            .iter()
+            .map(|cidr| Cidr::parse(cidr))
+            .collect::<Result<_, _>>()?;
+        let ports = list_param(params, "ports")?
+            .iter()
+            .map(parse_port)
+            .collect::<Result<_, _>>()?;
+        let protocols = strings("protocols")?
+            .iter()
+            .map(|name| {
+                NetworkProtocol::parse(name).ok_or_else(|| format!("unknown protocol '{}'", name))
+            })
+            .collect::<Result<_, _>>()?;
+        let resolve_dns = match params.get("resolve_dns") {
+            None | Some(Value::Null) => false,
+            Some(Value::Bool(resolve)) => *resolve,
+            Some(Value::String(text)) => text == "true",
+            Some(other) => return Err(format!("resolve_dns must be a bool, got {}", other)),
+        };
+
+        Ok(Self {
+            domains,
+            cidrs,
+            ports,
+            protocols,
+            resolve_dns,
+        })
+    }
+
+    /// Checks if the destination's host is looked up before it is matched against
+    /// `dest_cidrs`.
+    fn needs_lookup(&self, destination: &Destination) -> bool {
+        destination.ip.is_none() && self.resolve_dns && !self.cidrs.is_empty()
+    }
+
+    /// Checks the host against the domains and ranges. A host whose lookup failed is
+    /// covered when `unresolved_covered` is set.
+    fn covers_host(
+        &self,
+        destination: &Destination,
+        dns: &DnsAnswers,
+        unresolved_covered: bool,
+    ) -> bool {
+        if self.domains.is_empty() && self.cidrs.is_empty() {
+            return true;
+        }
+        if self
+            .domains
+            .iter()
+            .any(|domain| glob_match(domain, &destination.host))
+        {
+            return true;
+        }
+        if let Some(ip) = destination.ip {
+            return self.cidrs.iter().any(|cidr| cidr.contains(ip));
+        }
+        if !self.needs_lookup(destination) {
+            return false;
+        }
+        match dns.get(&destination.host) {
+            Some(Some(addrs)) => addrs
+                .iter()
+                .any(|addr| self.cidrs.iter().any(|cidr| cidr.contains(*addr))),
+            _ => unresolved_covered,
+        }
+    }
+
+    /// Checks if a destination matches every selector of the rule.
+    fn covers(
+        &self,
+        destination: &Destination,
+        dns: &DnsAnswers,
+        unresolved_covered: bool,
+    ) -> bool {
+        self.covers_host(destination, dns, unresolved_covered)
+            && (self.ports.is_empty()
+                || destination.port.is_some_and(|port| {
+                    self.ports
+                        .iter()
+                        .any(|(low, high)| (*low..=*high).contains(&port))
+                }))
+            && (self.protocols.is_empty() || self.protocols.contains(&destination.protocol))
+    }
+}
+
+/// Checks a network egress rule's selectors.
+pub fn validate(metadata: &RuleMetadata) -> Result<(), String> {
+    EgressSpec::parse(&metadata.params).map(|_| ())
+}
+
+/// Deterministic rule over the network destinations of a call.
+#[derive(Debug)]
+pub struct NetworkEgressRule {
+    metadata: RuleMetadata,
+    spec: EgressSpec,
+}
+
+impl NetworkEgressRule {
+    /// Builds a rule from its metadata. Fails when the params do not parse.
+    pub fn from_metadata(metadata: RuleMetadata) -> Result<Self, String> {
+        let spec = EgressSpec::parse(&metadata.params)?;
+        Ok(Self { metadata, spec })
+    }
+}
+
+impl RuleInstance for NetworkEgressRule {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn family_id(&self) -> &str {
+        FAMILY_ID
+    }
+
+    fn dns_lookups(&self, intent: &IntentEvent) -> Vec<String> {
+        let spec = &self.spec;
+        destinations(intent)
+            .into_iter()
+            .filter(|destination| spec.needs_lookup(destination))
+            .map(|destination| destination.host)
+            .collect()
+    }
+
+    fn evaluate(&self, intent: &IntentEvent, context: &EvaluationContext) -> Option<RuleMatch> {
+        let outcome = |matched: bool, detail: String| Some(RuleMatch { matched, detail });
+
+        let spec = &self.spec;
+        let destinations = destinations(intent);
+        if destinations.is_empty() {
+            return outcome(false, "no network destination".to_string());
+        }
+
+        let dns = context.dns;
+        match self.metadata.policy_type {
+            PolicyType::ContextAllow => {
+                match destinations.iter().find(|d| !spec.covers(d, dns, false)) {
+                    Some(destination) => outcome(false, format!("{} is not covered", destination)),
+                    None => outcome(true, "all destinations covered".to_string()),
+                }
+            }
+            _ => {
+                if let Some(destination) = destinations.iter().find(|d| spec.covers(d, dns, false))
+                {
+                    return outcome(true, format!("{} is covered", destination));
+                }
+                // Deny-type rules fail closed on a host that could not be resolved.
+                match destinations.iter().find(|d| spec.covers(d, dns, true)) {
+                    Some(destination) => {
+                        outcome(true, format!("{} could not be resolved", destination))
+                    }
+                    None => outcome(false, "no destination covered".to_string()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::families::test_support::{evaluate, evaluate_with_dns, intent, metadata};
+    use serde_json::json;
+
+    fn egress_call(location: &str, tool_params: Value) -> IntentEvent {
+        intent(json!({
+            "resource": {"type": "api", "name": "fetch", "location": location},
+            "tool_name": "http.fetch",
+            "tool_params": tool_params
+        }))
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let private = Cidr::parse("10.0.0.0/8").unwrap();
+        assert!(private.contains("10.1.2.3".parse().unwrap()));
+        assert!(private.contains("::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!private.contains("11.0.0.1".parse().unwrap()));
+        assert!(!private.contains("::1".parse().unwrap()));
+
+        assert!(Cidr::parse("0.0.0.0/0")
+            .unwrap()
+            .contains("8.8.8.8".parse().unwrap()));
+        assert!(Cidr::parse("::/0")
+            .unwrap()
+            .contains("2001:db8::1".parse().unwrap()));
+        assert!(Cidr::parse("2001:db8::/32")
+            .unwrap()
+            .contains("2001:db8::1".parse().unwrap()));
+        assert!(Cidr::parse("192.168.1.7")
+            .unwrap()
+            .contains("192.168.1.7".parse().unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_err());
+        assert!(Cidr::parse("example.com/8").is_err());
+    }
+
+    #[test]
+    fn test_destinations_from_location_and_params() {
+        let found = |location: &str, tool_params: Value| -> Vec<String> {
+            destinations(&egress_call(location, tool_params))
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        assert!(found("cloud", json!({})).is_empty());
+        assert_eq!(
+            found("https://API.example.com./v1", json!({})),
+            ["https://api.example.com:443"]
+        );
+        assert_eq!(found("10.0.0.5:5432", json!({})), ["https://10.0.0.5:5432"]);
+        assert_eq!(found("[::1]:8080", json!({})), ["https://[::1]:8080"]);
+        assert_eq!(
+            found(
+                "cloud",
+                json!({"url": "http://example.com/a", "callback": "wss://hooks.example.com",
+                       "note": "a://"})
+            ),
+            ["https://hooks.example.com:443", "http://example.com:80"]
+        );
+        assert_eq!(
+            found(
+                "cloud",
+                json!({"host": "db.internal", "port": 5432, "protocol": "tcp"})
+            ),
+            ["tcp://db.internal:5432"]
+        );
+    }
+
+    #[test]
+    fn test_destinations_from_nested_params() {
+        let found = |tool_params: Value| -> Vec<String> {
+            destinations(&egress_call("cloud", tool_params))
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        assert_eq!(
+            found(json!({
+                "request": {"target": {"host": "db.internal", "port": "5432"}},
+                "mirrors": ["evil.com:443", {"url": "http://10.0.0.1/x"}],
+                "upstream": "[::1]:9000"
+            })),
+            [
+                "https://evil.com:443",
+                "http://10.0.0.1:80",
+                "https://db.internal:5432",
+                "https://[::1]:9000"
+            ]
+        );
+        // Only dotted hostnames count in free-form strings.
+        assert!(found(json!({"at": "12:30", "sort": "name:asc", "v": "1.5:2"})).is_empty());
+    }
+
+    #[test]
+    fn test_unresolved_hosts_fail_closed_for_deny_rules() {
+        let params = json!({"dest_cidrs": ["10.0.0.0/8"], "resolve_dns": true});
+        let deny =
+            NetworkEgressRule::from_metadata(metadata(PolicyType::Forbidden, params.clone()))
+                .unwrap();
+        let allow =
+            NetworkEgressRule::from_metadata(metadata(PolicyType::ContextAllow, params)).unwrap();
+        let call = egress_call("https://api.internal/v1", json!({}));
+        assert_eq!(deny.dns_lookups(&call), ["api.internal"]);
+
+        let failed = DnsAnswers::from([("api.internal".to_string(), None)]);
+        let denied = evaluate_with_dns(&deny, &call, &failed);
+        assert!(denied.matched);
+        assert!(
+            denied.detail.contains("could not be resolved"),
+            "{}",
+            denied.detail
+        );
+        assert!(!evaluate_with_dns(&allow, &call, &failed).matched);
+        // Not looked up at all is treated the same as a failed lookup.
+        assert!(evaluate(&deny, &call).matched);
+
+        let internal = DnsAnswers::from([(
+            "api.internal".to_string(),
+            Some(vec!["10.1.2.3".parse().unwrap()]),
+        )]);
+        assert!(evaluate_with_dns(&deny, &call, &internal).matched);
+        assert!(evaluate_with_dns(&allow, &call, &internal).matched);
+        let public = DnsAnswers::from([(
+            "api.internal".to_string(),
+            Some(vec!["8.8.8.8".parse().unwrap()]),
+        )]);
+        assert!(!evaluate_with_dns(&deny, &call, &public).matched);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_hosts_reports_failed_lookups() {
+        let answers = resolve_hosts(vec![
+            "localhost".to_string(),
+            "no-such-host.invalid".to_string(),
+        ])
+        .await;
+        let localhost = answers["localhost"].as_ref().unwrap();
+        assert!(localhost.iter().all(IpAddr::is_loopback), "{:?}", localhost);
+        assert_eq!(answers["no-such-host.invalid"], None);
+    }
+
+    #[test]
+    fn test_deny_rules_match_covered_destinations() {
+        let rule = NetworkEgressRule::from_metadata(metadata(
+            PolicyType::Forbidden,
+            json!({
+                "rule_type": FAMILY_ID,
+                "dest_domains": ["*.evil.com"],
+                "dest_cidrs": "169.254.0.0/16, fd00::/8",
+            }),
+        ))
+        .unwrap();
+        let matched = |location: &str| evaluate(&rule, &egress_call(location, json!({}))).matched;
+
+        assert!(matched("https://c2.evil.com/beacon"));
+        assert!(matched("http://169.254.169.254/latest/meta-data"));
+        assert!(matched("http://[fd00::1]/"));
+        assert!(!matched("https://evil.com"));
+        assert!(!matched("https://example.com"));
+        assert!(!matched("cloud"));
+        // Hostnames are not resolved by default.
+        assert!(!matched("http://localhost"));
+    }
+
+    #[test]
+    fn test_allow_rules_require_every_destination() {
+        let rule = NetworkEgressRule::from_metadata(metadata(
+            PolicyType::ContextAllow,
+            json!({
+                "rule_type": FAMILY_ID,
+                "dest_domains": ["api.example.com"],
+                "ports": [443, "8000-8080"],
+                "protocols": ["https"],
+            }),
+        ))
+        .unwrap();
+        let matched = |location: &str, tool_params: Value| {
+            evaluate(&rule, &egress_call(location, tool_params)).matched
+        };
+
+        assert!(matched("https://api.example.com/v1", json!({})));
+        assert!(matched("https://api.example.com:8080", json!({})));
+        assert!(!matched("https://api.example.com:9000", json!({})));
+        assert!(!matched("http://api.example.com", json!({})));
+        assert!(!matched(
+            "https://api.example.com",
+            json!({"url": "https://other.example.com"})
+        ));
+        assert!(!matched("cloud", json!({})));
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_selectors() {
+        let metadata = |params: Value| metadata(PolicyType::Forbidden, params);
+
+        assert!(validate(&metadata(json!({"rule_type": FAMILY_ID}))).is_ok());
+        for params in [
+            json!({"dest_cidrs": ["10.0.0.0/40"]}),
+            json!({"dest_domains": [""]}),
+            json!({"ports": ["80-20"]}),
+            json!({"ports": [70000]}),
+            json!({"protocols": ["ftp"]}),
+            json!({"resolve_dns": 1}),
+        ] {
+            assert!(validate(&metadata(params.clone())).is_err(), "{}", params);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! # Rule Params
+//!
+//! Readers shared by the deterministic families for values in a rule's params.
+
+use serde_json::Value;
+
+use crate::types::{Decision, PolicyType};
+
+/// Reads a list param sent as an array, a JSON array string or a comma-separated string.
+pub(crate) fn list_param(params: &Value, key: &str) -> Result<Vec<Value>, String> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(items)) => Ok(items.clone()),
+        Some(Value::String(text)) if text.trim_start().starts_with('[') => {
+            serde_json::from_str(text).map_err(|e| format!("invalid {}: {}", key, e))
+        }
+        Some(Value::String(text)) => Ok(text
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| Value::String(item.to_string()))
+            .collect()),
+        Some(other) => Ok(vec![other.clone()]),
+    }
+}
+
+/// Matches `text` against a glob where `*` is any run of characters and `?` any one.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Reads `on_match`, the decision a matching FORBIDDEN or CONTEXT_DENY rule yields:
+/// `deny` (the default) or `step_up`.
+pub(crate) fn on_match_param(params: &Value) -> Result<Decision, String> {
+    let on_match = match params.get("on_match") {
+        None | Some(Value::Null) => "",
+        Some(Value::String(text)) => text.trim(),
+        Some(other) => return Err(format!("on_match must be a string, got {}", other)),
+    };
+    match on_match {
+        "" | "deny" => Ok(Decision::Deny),
+        "step_up" => Ok(Decision::StepUp),
+        other => Err(format!("on_match must be deny or step_up, got '{}'", other)),
+    }
+}
+
+/// Rejects a STEP_UP `on_match` on rules whose matches do not deny.
+pub(crate) fn check_on_match(on_match: &Decision, policy_type: &PolicyType) -> Result<(), String> {
+    let deny_pass = matches!(policy_type, PolicyType::Forbidden | PolicyType::ContextDeny);
+    if *on_match == Decision::StepUp && !deny_pass {
+        return Err("on_match applies to forbidden and context_deny rules only".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("payments.*", "payments.api"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("db_?", "db_1"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+        assert!(!glob_match("payments", "payments.api"));
+    }
+
+    #[test]
+    fn test_list_param_forms() {
+        let params = json!({
+            "array": ["a", 1],
+            "json": "[\"a\", 1]",
+            "csv": " a, b ,,",
+            "single": 443
+        });
+        assert_eq!(
+            list_param(&params, "array").unwrap(),
+            vec![json!("a"), json!(1)]
+        );
+        assert_eq!(
+            list_param(&params, "json").unwrap(),
+            vec![json!("a"), json!(1)]
+        );
+        assert_eq!(
+            list_param(&params, "csv").unwrap(),
+            vec![json!("a"), json!("b")]
+        );
+        assert_eq!(list_param(&params, "single").unwrap(), vec![json!(443)]);
+        assert!(list_param(&params, "missing").unwrap().is_empty());
+        assert!(list_param(&json!({"bad": "[1,"}), "bad").is_err());
+    }
+
+    #[test]
+    fn test_on_match_param() {
+        let on_match = |params: Value| on_match_param(&params);
+        assert_eq!(on_match(json!({})).unwrap(), Decision::Deny);
+        assert_eq!(
+            on_match(json!({"on_match": " step_up"})).unwrap(),
+            Decision::StepUp
+        );
+        assert!(on_match(json!({"on_match": "defer"})).is_err());
+        assert!(on_match(json!({"on_match": true})).is_err());
+
+        assert!(check_on_match(&Decision::StepUp, &PolicyType::ContextDeny).is_ok());
+        assert!(check_on_match(&Decision::StepUp, &PolicyType::ContextAllow).is_err());
+        assert!(check_on_match(&Decision::Deny, &PolicyType::ContextDefer).is_ok());
+    }
+}
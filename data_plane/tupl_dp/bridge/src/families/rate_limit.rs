@@ -0,0 +1,627 @@
+//! # Rate Limit Rules
+//!
+//! Limits how often calls are made, counted by the data plane instead of trusting the
+//! `rate_limit_context` a client reports.
+//!
+//! ```json
+//! {"rule_type": "rate_limit", "limit": 100, "window_secs": 60, "algorithm": "sliding_window",
+//!  "per": ["agent", "tool"], "tool_name": "db.*", "on_match": "step_up"}
+//! ```
+//!
+//! Every rule keeps its own counters, one per tenant and per value of each dimension in
+//! `per` (`agent`, `tool`, `layer`; all three by default). `sliding_window` admits `limit`
+//! calls in any `window_secs` span; `token_bucket` holds up to `burst` tokens (default
+//! `limit`) and refills `limit` of them per `window_secs`. With `tool_name` (a glob) only
+//! calls to matching tools are counted or matched.
+//!
+//! A rule matches a call over its limit under FORBIDDEN, CONTEXT_DENY and CONTEXT_DEFER
+//! (a FORBIDDEN or CONTEXT_DENY match yields `on_match`: DENY or STEP_UP), and a call
+//! within it under CONTEXT_ALLOW. Only calls within the limit are counted, so an agent
+//! held at its limit regains capacity as time passes. A call is counted when the rule
+//! evaluates it, even if another rule then denies it, so the limit bounds attempts rather
+//! than allowed calls; staged dry runs are never counted.
+//!
+//! Counters are kept in memory by each data-plane process, start empty, and restart when
+//! the rule's limit changes. At most `MAX_TRACKED_COUNTERS` are kept: when a new counter
+//! does not fit, idle counters are dropped first, then the least recently used one.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use super::params::{check_on_match, glob_match, list_param, on_match_param};
+use super::EvaluationContext;
+use crate::api_types::IntentEvent;
+use crate::types::{
+    normalize_tenant, now_ms, Decision, PolicyType, RuleInstance, RuleMatch, RuleMetadata,
+};
+
+/// Family id (`rule_type`) of rate limit rules.
+pub const FAMILY_ID: &str = "rate_limit";
+
+/// Largest sliding window limit (the window keeps one timestamp per admitted call).
+const MAX_WINDOW_LIMIT: u32 = 100_000;
+
+/// Most counters a rate limiter keeps.
+pub const MAX_TRACKED_COUNTERS: usize = 65_536;
+
+/// How a rule counts calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateAlgorithm {
+    SlidingWindow,
+    TokenBucket,
+}
+
+impl RateAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateAlgorithm::SlidingWindow => "sliding_window",
+            RateAlgorithm::TokenBucket => "token_bucket",
+        }
+    }
+}
+
+/// Limit enforced by a rule.
+#[derive(Debug, Clone, PartialEq)]
+struct RateLimitSpec {
+    limit: u32,
+    window_ms: u64,
+    algorithm: RateAlgorithm,
+    /// Token bucket capacity
+    burst: u32,
+    per_agent: bool,
+    per_tool: bool,
+    per_layer: bool,
+    /// Empty = every tool
+    tool_name: String,
+    on_match: Decision,
+}
+
+impl RateLimitSpec {
+    fn parse(params: &Value) -> Result<Self, String> {
+        let number = |key: &str| -> Result<Option<f64>, String> {
+            match params.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(Value::Number(number)) => Ok(number.as_f64()),
+                Some(Value::String(text)) => text
+                    .trim()
+                    .parse::<f64>()
+                    .map(Some)
+                    .map_err(|_| format!("{} must be a number, got '{}'", key, text)),
+                Some(other) => Err(format!("{} must be a number, got {}", key, other)),
+            }
+        };
+        let count = |key: &str| -> Result<Option<u32>, String> {
+            match number(key)? {
+                None => Ok(None),
+                Some(value) if value.fract() == 0.0 && value >= 1.0 && value <= u32::MAX as f64 => {
+                    Ok(Some(value as u32))
+                }
+                Some(value) => Err(format!("{} must be a positive integer, got {}", key, value)),
+            }
+        };
+        let text = |key: &str| -> Result<String, String> {
+            match params.get(key) {
+                None | Some(Value::Null) => Ok(String::new()),
+                Some(Value::String(text)) => Ok(text.trim().to_string()),
+                Some(other) => Err(format!("{} must be a string, got {}", key, other)),
+            }
+        };
+
+        let limit = count("limit")?.ok_or("limit is required")?;
+        let window_secs = number("window_secs")?.ok_or("window_secs is required")?;
+        let window_ms = (window_secs * 1000.0).round();
+        if !window_ms.is_finite() || window_ms < 1.0 {
+            return Err(format!("window_secs must be positive, got {}", window_secs));
+        }
+
+        let algorithm = match text("algorithm")?.as_str() {
+            "" | "sliding_window" => RateAlgorithm::SlidingWindow,
+            "token_bucket" => RateAlgorithm::TokenBucket,
+            other => return Err(format!("unknown algorithm '{}'", other)),
+        };
+        let burst = match (algorithm, count("burst")?) {
+            (RateAlgorithm::TokenBucket, burst) => burst.unwrap_or(limit),
+            (RateAlgorithm::SlidingWindow, None) if limit <= MAX_WINDOW_LIMIT => limit,
+            (RateAlgorithm::SlidingWindow, None) => {
+                return Err(format!(
+                    "sliding_window limits are capped at {}; use token_bucket",
+                    MAX_WINDOW_LIMIT
+                ))
+            }
+            (RateAlgorithm::SlidingWindow, Some(_)) => {
+                return Err("burst only applies to token_bucket".to_string())
+            }
+        };
+
+        let (mut per_agent, mut per_tool, mut per_layer) = (true, true, true);
+        if params.get("per").is_some_and(|per| !per.is_null()) {
+            (per_agent, per_tool, per_layer) = (false, false, false);
+            for dimension in list_param(params, "per")? {
+                match dimension.as_str() {
+                    Some("agent") => per_agent = true,
+                    Some("tool") => per_tool = true,
+                    Some("layer") => per_layer = true,
+                    _ => return Err(format!("unknown per dimension {}", dimension)),
+                }
+            }
+        }
+
+        Ok(Self {
+            limit,
+            window_ms: window_ms as u64,
+            algorithm,
+            burst,
+            per_agent,
+            per_tool,
+            per_layer,
+            tool_name: text("tool_name")?,
+            on_match: on_match_param(params)?,
+        })
+    }
+
+    /// Counter a call is counted against.
+    fn key(&self, rule_id: &str, intent: &IntentEvent) -> RateLimitKey {
+        let dimension = |counted: bool, value: Option<&str>| match counted {
+            true => value.unwrap_or("").to_string(),
+            false => String::new(),
+        };
+        RateLimitKey {
+            tenant_id: normalize_tenant(&intent.tenant_id).to_string(),
+            rule_id: rule_id.to_string(),
+            agent_id: dimension(self.per_agent, intent.agent_id()),
+            tool_name: dimension(self.per_tool, intent.tool_name.as_deref()),
+            layer: dimension(self.per_layer, intent.layer_str()),
+        }
+    }
+}
+
+/// Identifies a counter; dimensions the rule does not count by are empty.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RateLimitKey {
+    pub tenant_id: String,
+    pub rule_id: String,
+    pub agent_id: String,
+    pub tool_name: String,
+    pub layer: String,
+}
+
+/// Snapshot of a counter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitState {
+    pub key: RateLimitKey,
+    pub algorithm: RateAlgorithm,
+    /// Calls admitted per window
+    pub limit: u32,
+    pub window_ms: u64,
+    /// Calls that can be admitted at once (the limit, or the bucket's burst)
+    pub capacity: u32,
+    pub remaining: u32,
+    /// Time until another call is admitted (0 = now)
+    pub retry_after_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Counter {
+    /// Admission times inside the window, oldest first
+    Window(VecDeque<u64>),
+    Bucket {
+        tokens: f64,
+        refilled_at_ms: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Tracked {
+    spec: RateLimitSpec,
+    counter: Counter,
+    /// Last time a call was counted against it
+    used_at_ms: u64,
+}
+
+impl Tracked {
+    fn new(spec: &RateLimitSpec, now: u64) -> Self {
+        let counter = match spec.algorithm {
+            RateAlgorithm::SlidingWindow => Counter::Window(VecDeque::new()),
+            RateAlgorithm::TokenBucket => Counter::Bucket {
+                tokens: spec.burst as f64,
+                refilled_at_ms: now,
+            },
+        };
+        Self {
+            spec: spec.clone(),
+            counter,
+            used_at_ms: now,
+        }
+    }
+
+    /// Expires old admissions or refills tokens up to `now`.
+    fn advance(&mut self, now: u64) {
+        let spec = &self.spec;
+        match &mut self.counter {
+            Counter::Window(times) => {
+                while times
+                    .front()
+                    .is_some_and(|time| time + spec.window_ms <= now)
+                {
+                    times.pop_front();
+                }
+            }
+            Counter::Bucket {
+                tokens,
+                refilled_at_ms,
+            } => {
+                let elapsed = now.saturating_sub(*refilled_at_ms) as f64;
+                let refill = elapsed * spec.limit as f64 / spec.window_ms as f64;
+                *tokens = (*tokens + refill).min(spec.burst as f64);
+                *refilled_at_ms = now.max(*refilled_at_ms);
+            }
+        }
+    }
+
+    fn capacity(&self) -> u32 {
+        match self.counter {
+            Counter::Window(_) => self.spec.limit,
+            Counter::Bucket { .. } => self.spec.burst,
+        }
+    }
+
+    fn remaining(&self) -> u32 {
+        match &self.counter {
+            Counter::Window(times) => self.spec.limit.saturating_sub(times.len() as u32),
+            Counter::Bucket { tokens, .. } => tokens.floor() as u32,
+        }
+    }
+
+    fn retry_after_ms(&self, now: u64) -> u64 {
+        if self.remaining() > 0 {
+            return 0;
+        }
+        match &self.counter {
+            Counter::Window(times) => times
+                .front()
+                .map_or(0, |time| (time + self.spec.window_ms).saturating_sub(now)),
+            Counter::Bucket { tokens, .. } => {
+                ((1.0 - tokens) * self.spec.window_ms as f64 / self.spec.limit as f64).ceil() as u64
+            }
+        }
+    }
+
+    fn admit(&mut self, now: u64) {
+        match &mut self.counter {
+            Counter::Window(times) => times.push_back(now),
+            Counter::Bucket { tokens, .. } => *tokens -= 1.0,
+        }
+        self.used_at_ms = now;
+    }
+
+    fn state(&self, key: RateLimitKey, now: u64) -> RateLimitState {
+        RateLimitState {
+            key,
+            algorithm: self.spec.algorithm,
+            limit: self.spec.limit,
+            window_ms: self.spec.window_ms,
+            capacity: self.capacity(),
+            remaining: self.remaining(),
+            retry_after_ms: self.retry_after_ms(now),
+        }
+    }
+}
+
+/// Selects counters; empty fields match any value, except the tenant (empty = "default").
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitFilter {
+    pub tenant_id: String,
+    pub rule_id: String,
+    pub agent_id: String,
+    pub tool_name: String,
+}
+
+impl RateLimitFilter {
+    fn selects(&self, key: &RateLimitKey) -> bool {
+        let field = |filter: &str, value: &str| filter.is_empty() || filter == value;
+        key.tenant_id == normalize_tenant(&self.tenant_id)
+            && field(&self.rule_id, &key.rule_id)
+            && field(&self.agent_id, &key.agent_id)
+            && field(&self.tool_name, &key.tool_name)
+    }
+}
+
+/// Data-plane counters behind rate limit rules.
+#[derive(Debug)]
+pub struct RateLimiter {
+    counters: Mutex<BTreeMap<RateLimitKey, Tracked>>,
+    /// Most counters kept at once
+    max_counters: usize,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::with_max_counters(MAX_TRACKED_COUNTERS)
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A limiter keeping at most `max_counters` counters (at least one).
+    pub fn with_max_counters(max_counters: usize) -> Self {
+        Self {
+            counters: Mutex::new(BTreeMap::new()),
+            max_counters: max_counters.max(1),
+        }
+    }
+
+    /// Admits a call if the counter has capacity left and returns whether it did, with
+    /// the counter's state afterwards. Counters are left untouched unless `record`.
+    fn acquire(
+        &self,
+        key: RateLimitKey,
+        spec: &RateLimitSpec,
+        now: u64,
+        record: bool,
+    ) -> (bool, RateLimitState) {
+        let mut counters = self.counters.lock();
+        if record && counters.len() >= self.max_counters && !counters.contains_key(&key) {
+            counters.retain(|_, tracked| {
+                tracked.advance(now);
+                tracked.remaining() < tracked.capacity()
+            });
+            while counters.len() >= self.max_counters {
+                let Some(oldest) = counters
+                    .iter()
+                    .min_by_key(|(_, tracked)| tracked.used_at_ms)
+                    .map(|(key, _)| key.clone())
+                else {
+                    break;
+                };
+                counters.remove(&oldest);
+            }
+        }
+
+        let mut scratch;
+        let tracked = if record {
+            let tracked = counters
+                .entry(key.clone())
+                .or_insert_with(|| Tracked::new(spec, now));
+            if tracked.spec != *spec {
+                *tracked = Tracked::new(spec, now);
+            }
+            tracked
+        } else {
+            scratch = counters
+                .get(&key)
+                .filter(|tracked| tracked.spec == *spec)
+                .cloned()
+                .unwrap_or_else(|| Tracked::new(spec, now));
+            &mut scratch
+        };
+
+        tracked.advance(now);
+        let admitted = tracked.remaining() > 0;
+        if admitted {
+            tracked.admit(now);
+        }
+        (admitted, tracked.state(key, now))
+    }
+
+    /// Returns the state of the selected counters, ordered by key.
+    pub fn states(&self, filter: &RateLimitFilter) -> Vec<RateLimitState> {
+        let now = now_ms();
+        let mut counters = self.counters.lock();
+        counters
+            .iter_mut()
+            .filter(|(key, _)| filter.selects(key))
+            .map(|(key, tracked)| {
+                tracked.advance(now);
+                tracked.state(key.clone(), now)
+            })
+            .collect()
+    }
+
+    /// Drops the selected counters and returns how many there were.
+    pub fn reset(&self, filter: &RateLimitFilter) -> usize {
+        let mut counters = self.counters.lock();
+        let before = counters.len();
+        counters.retain(|key, _| !filter.selects(key));
+        before - counters.len()
+    }
+}
+
+/// Checks a rate limit rule's limit and its on_match decision against its policy type.
+pub fn validate(metadata: &RuleMetadata) -> Result<(), String> {
+    let spec = RateLimitSpec::parse(&metadata.params)?;
+    check_on_match(&spec.on_match, &metadata.policy_type)
+}
+
+/// Rule limiting the rate of calls, with counters kept by the data plane.
+#[derive(Debug)]
+pub struct RateLimitRule {
+    metadata: RuleMetadata,
+    spec: RateLimitSpec,
+}
+
+impl RateLimitRule {
+    /// Builds a rule from its metadata. Fails when the params do not parse.
+    pub fn from_metadata(metadata: RuleMetadata) -> Result<Self, String> {
+        let spec = RateLimitSpec::parse(&metadata.params)?;
+        Ok(Self { metadata, spec })
+    }
+}
+
+impl RuleInstance for RateLimitRule {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn family_id(&self) -> &str {
+        FAMILY_ID
+    }
+
+    fn evaluate(&self, intent: &IntentEvent, context: &EvaluationContext) -> Option<RuleMatch> {
+        let outcome = |matched: bool, detail: String| Some(RuleMatch { matched, detail });
+
+        let spec = &self.spec;
+        let tool_name = intent.tool_name.as_deref().unwrap_or("");
+        if !spec.tool_name.is_empty() && !glob_match(&spec.tool_name, tool_name) {
+            return outcome(false, format!("tool {} is not rate limited", tool_name));
+        }
+
+        let key = spec.key(&self.metadata.rule_id, intent);
+        let (admitted, state) = context
+            .rate_limits
+            .acquire(key, spec, now_ms(), !context.dry_run);
+        let detail = if admitted {
+            format!("{} of {} calls left", state.remaining, state.capacity)
+        } else {
+            format!(
+                "limit of {} calls per {} ms reached, retry in {} ms",
+                state.limit, state.window_ms, state.retry_after_ms
+            )
+        };
+        let matched = match self.metadata.policy_type {
+            PolicyType::ContextAllow => admitted,
+            _ => !admitted,
+        };
+        outcome(matched, detail)
+    }
+
+    fn deny_decision(&self) -> Decision {
+        self.spec.on_match.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(params: Value) -> RateLimitSpec {
+        RateLimitSpec::parse(&params).unwrap()
+    }
+
+    fn key(agent_id: &str) -> RateLimitKey {
+        RateLimitKey {
+            tenant_id: "default".to_string(),
+            rule_id: "limit".to_string(),
+            agent_id: agent_id.to_string(),
+            tool_name: String::new(),
+            layer: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_sliding_window_admits_limit_per_window() {
+        let limiter = RateLimiter::new();
+        let spec = spec(json!({"limit": 2, "window_secs": 10}));
+        let admit = |agent_id: &str, now: u64| limiter.acquire(key(agent_id), &spec, now, true);
+
+        assert!(admit("agent-1", 0).0);
+        assert!(admit("agent-1", 4_000).0);
+        let (admitted, state) = admit("agent-1", 9_000);
+        assert!(!admitted);
+        assert_eq!(state.retry_after_ms, 1_000);
+        // Counters are per key.
+        assert!(admit("agent-2", 9_000).0);
+        // The first call leaves the window; the rejected one was never counted.
+        assert!(admit("agent-1", 10_000).0);
+        assert!(!admit("agent-1", 13_999).0);
+        assert!(admit("agent-1", 14_000).0);
+    }
+
+    #[test]
+    fn test_token_bucket_refills_over_time() {
+        let limiter = RateLimiter::new();
+        let spec =
+            spec(json!({"limit": 1, "window_secs": 1, "algorithm": "token_bucket", "burst": 3}));
+        let admit = |now: u64| limiter.acquire(key("agent-1"), &spec, now, true).0;
+
+        assert!(admit(0) && admit(0) && admit(0));
+        assert!(!admit(500));
+        assert!(admit(1_000));
+        assert!(!admit(1_000));
+        // Refills stop at the burst.
+        assert!(admit(60_000) && admit(60_000) && admit(60_000));
+        assert!(!admit(60_000));
+    }
+
+    #[test]
+    fn test_dry_runs_and_resets() {
+        let limiter = RateLimiter::new();
+        let spec = spec(json!({"limit": 1, "window_secs": 3600}));
+
+        assert!(limiter.acquire(key("agent-1"), &spec, 0, false).0);
+        assert!(limiter.acquire(key("agent-1"), &spec, 0, false).0);
+        assert!(limiter.acquire(key("agent-1"), &spec, 0, true).0);
+        assert!(!limiter.acquire(key("agent-1"), &spec, 0, false).0);
+
+        // A changed limit starts a new counter.
+        let raised = RateLimitSpec {
+            limit: 2,
+            burst: 2,
+            ..spec.clone()
+        };
+        assert!(limiter.acquire(key("agent-1"), &raised, 0, true).0);
+
+        let filter = RateLimitFilter {
+            agent_id: "agent-2".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(limiter.reset(&filter), 0);
+        assert_eq!(limiter.reset(&RateLimitFilter::default()), 1);
+        assert!(limiter.acquire(key("agent-1"), &spec, 0, true).0);
+    }
+
+    #[test]
+    fn test_full_limiter_evicts_least_recently_used_counter() {
+        let limiter = RateLimiter::with_max_counters(2);
+        let spec = spec(json!({"limit": 1, "window_secs": 3600}));
+        let admit = |agent_id: &str, now: u64| limiter.acquire(key(agent_id), &spec, now, true).0;
+        let tracked = || -> Vec<String> {
+            limiter
+                .counters
+                .lock()
+                .keys()
+                .map(|key| key.agent_id.clone())
+                .collect()
+        };
+
+        assert!(admit("agent-1", 0));
+        assert!(admit("agent-2", 1));
+        // Neither counter is idle, so agent-1's, used longest ago, makes room.
+        assert!(admit("agent-3", 2));
+        assert_eq!(tracked(), vec!["agent-2", "agent-3"]);
+        // Dry runs never add counters.
+        assert!(limiter.acquire(key("agent-4"), &spec, 3, false).0);
+        assert_eq!(tracked(), vec!["agent-2", "agent-3"]);
+        // An evicted counter starts over.
+        assert!(admit("agent-1", 4));
+        assert_eq!(tracked(), vec!["agent-1", "agent-3"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_limits() {
+        let parsed = spec(json!({"limit": "5", "window_secs": 0.5, "per": ["agent"]}));
+        assert_eq!(parsed.window_ms, 500);
+        assert!(parsed.per_agent && !parsed.per_tool && !parsed.per_layer);
+        assert_eq!(parsed.on_match, Decision::Deny);
+
+        for params in [
+            json!({"window_secs": 60}),
+            json!({"limit": 0, "window_secs": 60}),
+            json!({"limit": 1.5, "window_secs": 60}),
+            json!({"limit": 5}),
+            json!({"limit": 5, "window_secs": 0}),
+            json!({"limit": 5, "window_secs": 60, "algorithm": "leaky"}),
+            json!({"limit": 5, "window_secs": 60, "burst": 10}),
+            json!({"limit": 1_000_000, "window_secs": 60}),
+            json!({"limit": 5, "window_secs": 60, "per": ["session"]}),
+            json!({"limit": 5, "window_secs": 60, "on_match": "defer"}),
+        ] {
+            assert!(RateLimitSpec::parse(&params).is_err(), "{}", params);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! # Rule Family Registry
+//!
+//! Maps a family id to the hooks that validate and build its rules. Every family is
+//! persisted as the `RuleMetadata` it was built from.
+//!
+//! A rule's family is the `rule_type` in its params (rules persisted before families
+//! were tracked are design boundaries). The gRPC server, the `Bridge` and bundle import
+//! only go through this table, so a family is added by implementing `RuleInstance` in
+//! the `families` module and listing it in `FAMILIES`.
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use super::data_sensitivity::{self, DataSensitivityRule};
+use super::design_boundary::{self, DesignBoundaryRule};
+use super::net_egress::{self, NetworkEgressRule};
+use super::rate_limit::{self, RateLimitRule};
+use super::tool_constraint::{self, ToolConstraintRule};
+use crate::types::{RuleInstance, RuleMetadata};
+
+/// Checks a rule's metadata before it is installed or imported.
+pub type ValidateFn = fn(&RuleMetadata) -> Result<(), String>;
+
+/// Builds a rule from its metadata, on install and when loading from storage.
+pub type BuildFn = fn(RuleMetadata) -> Result<Arc<dyn RuleInstance>, String>;
+
+/// A registered rule family.
+#[derive(Clone, Copy)]
+pub struct RuleFamily {
+    /// Id rules are installed and persisted under (`rule_type`)
+    pub family_id: &'static str,
+    pub description: &'static str,
+    /// Whether installs must carry pre-encoded anchors
+    pub requires_anchors: bool,
+    pub validate: ValidateFn,
+    pub build: BuildFn,
+}
+
+/// Every family this binary can install and load.
+pub const FAMILIES: &[RuleFamily] = &[
+    RuleFamily {
+        family_id: design_boundary::FAMILY_ID,
+        description: "semantic boundary compared against pre-encoded anchors",
+        requires_anchors: true,
+        validate: design_boundary::validate,
+        build: |metadata| Ok(Arc::new(DesignBoundaryRule::from_metadata(metadata))),
+    },
+    RuleFamily {
+        family_id: tool_constraint::FAMILY_ID,
+        description: "typed constraints on a tool call's name, method and parameters",
+        requires_anchors: false,
+        validate: tool_constraint::validate,
+        build: |metadata| Ok(Arc::new(ToolConstraintRule::from_metadata(metadata)?)),
+    },
+    RuleFamily {
+        family_id: net_egress::FAMILY_ID,
+        description: "network destinations by domain, CIDR range, port and protocol",
+        requires_anchors: false,
+        validate: net_egress::validate,
+        build: |metadata| Ok(Arc::new(NetworkEgressRule::from_metadata(metadata)?)),
+    },
+    RuleFamily {
+        family_id: rate_limit::FAMILY_ID,
+        description: "call rate per tenant, agent, tool and layer, counted by the data plane",
+        requires_anchors: false,
+        validate: rate_limit::validate,
+        build: |metadata| Ok(Arc::new(RateLimitRule::from_metadata(metadata)?)),
+    },
+    RuleFamily {
+        family_id: data_sensitivity::FAMILY_ID,
+        description: "exact conditions on data sensitivity, PII, volume and authn",
+        requires_anchors: false,
+        validate: data_sensitivity::validate,
+        build: |metadata| Ok(Arc::new(DataSensitivityRule::from_metadata(metadata)?)),
+    },
+];
+
+/// Looks up a registered family.
+pub fn family(family_id: &str) -> Result<&'static RuleFamily, String> {
+    FAMILIES
+        .iter()
+        .find(|family| family.family_id == family_id)
+        .ok_or_else(|| format!("Unsupported rule family '{}'", family_id))
+}
+
+/// Returns the registered family ids.
+pub fn family_ids() -> Vec<&'static str> {
+    FAMILIES.iter().map(|family| family.family_id).collect()
+}
+
+/// Returns the family id recorded in a rule's params.
+pub fn family_of(params: &Value) -> &str {
+    params
+        .get("rule_type")
+        .and_then(Value::as_str)
+        .filter(|rule_type| !rule_type.is_empty())
+        .unwrap_or(design_boundary::FAMILY_ID)
+}
+
+/// Validates a rule against its family's checks.
+pub fn validate_rule(metadata: &RuleMetadata) -> Result<&'static RuleFamily, String> {
+    let family = family(family_of(&metadata.params))?;
+    (family.validate)(metadata).map_err(|e| {
+        format!(
+            "Invalid {} rule {}: {}",
+            family.family_id, metadata.rule_id, e
+        )
+    })?;
+    Ok(family)
+}
+
+/// Builds a rule with its family's constructor.
+pub fn build_rule(metadata: RuleMetadata) -> Result<Arc<dyn RuleInstance>, String> {
+    let family = family(family_of(&metadata.params))?;
+    (family.build)(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_family_ids_are_unique() {
+        let mut ids = family_ids();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), FAMILIES.len());
+    }
+
+    #[test]
+    fn test_family_of_defaults_to_design_boundary() {
+        assert_eq!(family_of(&json!({})), design_boundary::FAMILY_ID);
+        assert_eq!(
+            family_of(&json!({"rule_type": ""})),
+            design_boundary::FAMILY_ID
+        );
+        assert_eq!(family_of(&json!({"rule_type": "other"})), "other");
+        assert!(family("other").is_err());
+    }
+}
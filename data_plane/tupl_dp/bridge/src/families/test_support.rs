@@ -0,0 +1,69 @@
+//! Fixtures shared by the family unit tests.
+
+use serde_json::{json, Value};
+
+use crate::api_types::IntentEvent;
+use crate::families::net_egress::DnsAnswers;
+use crate::families::rate_limit::RateLimiter;
+use crate::families::EvaluationContext;
+use crate::types::{PolicyType, RuleInstance, RuleMatch, RuleMetadata, RuleScope};
+
+/// Metadata of a global L4 rule with the given policy type and params.
+pub fn metadata(policy_type: PolicyType, params: Value) -> RuleMetadata {
+    RuleMetadata {
+        rule_id: "rule-1".to_string(),
+        priority: 10,
+        scope: RuleScope::global(),
+        layer: Some("L4".to_string()),
+        created_at_ms: 1,
+        enabled: true,
+        description: None,
+        params,
+        policy_type,
+        drift_threshold: 0.0,
+        modification_spec: None,
+        slice_weights: [0.25; 4],
+        not_before: None,
+        expires_at: None,
+        signed_by: None,
+    }
+}
+
+/// An intent from agent-1, with `fields` replacing the top-level fields they name.
+pub fn intent(fields: Value) -> IntentEvent {
+    let mut intent = json!({
+        "id": "evt-1",
+        "schemaVersion": "v1.3",
+        "tenantId": "",
+        "timestamp": 1699564800.0,
+        "actor": {"id": "agent-1", "type": "agent"},
+        "action": "execute",
+        "resource": {"type": "api", "name": "tools", "location": "cloud"},
+        "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+        "risk": {"authn": "required"}
+    });
+    if let (Value::Object(intent), Value::Object(fields)) = (&mut intent, fields) {
+        intent.extend(fields);
+    }
+    serde_json::from_value(intent).unwrap()
+}
+
+/// Evaluates a deterministic rule as a dry run against a fresh rate limiter.
+pub fn evaluate(rule: &dyn RuleInstance, intent: &IntentEvent) -> RuleMatch {
+    evaluate_with_dns(rule, intent, &DnsAnswers::new())
+}
+
+/// `evaluate` with the given hostname lookups.
+pub fn evaluate_with_dns(
+    rule: &dyn RuleInstance,
+    intent: &IntentEvent,
+    dns: &DnsAnswers,
+) -> RuleMatch {
+    let limits = RateLimiter::new();
+    let context = EvaluationContext {
+        rate_limits: &limits,
+        dns,
+        dry_run: true,
+    };
+    rule.evaluate(intent, &context).unwrap()
+}
@@ -0,0 +1,372 @@
+//! # Tool Constraint Rules
+//!
+//! Deterministic rules over a tool call's name, method and parameters, for guarantees
+//! embeddings are too fuzzy to give (e.g. "amount must be below 1000").
+//!
+//! A rule selects calls by `tool_name` and optional `tool_method`, each matched exactly
+//! or as a glob (`*`, `?`), and states what valid `tool_params` look like:
+//!
+//! ```json
+//! {"rule_type": "tool_constraint", "tool_name": "payments.*", "tool_method": "transfer",
+//!  "constraints": [{"param": "amount", "type": "float", "required": true, "max_exclusive": 1000}]}
+//! ```
+//!
+//! `constraints` may also be sent as a JSON string. A rule matches a selected call that
+//! satisfies every constraint under CONTEXT_ALLOW, and a selected call that violates one
+//! under FORBIDDEN, CONTEXT_DENY and CONTEXT_DEFER. Calls to other tools never match.
+
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::params::glob_match;
+use super::EvaluationContext;
+use crate::api_types::IntentEvent;
+use crate::types::{ParamType, PolicyType, RuleInstance, RuleMatch, RuleMetadata};
+
+/// Family id (`rule_type`) of tool constraint rules.
+pub const FAMILY_ID: &str = "tool_constraint";
+
+/// A typed constraint on one tool parameter.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParamConstraint {
+    /// Key in `tool_params`
+    pub param: String,
+    #[serde(default, rename = "type")]
+    pub param_type: Option<ParamType>,
+    /// Whether the key must be present (absent or null otherwise passes)
+    #[serde(default)]
+    pub required: bool,
+    /// Inclusive numeric bounds
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Exclusive numeric bounds
+    pub min_exclusive: Option<f64>,
+    pub max_exclusive: Option<f64>,
+    /// Allowed values
+    #[serde(rename = "enum")]
+    pub one_of: Option<Vec<Value>>,
+    /// Regex a string value must match (anchor it with ^...$ for a full match)
+    pub pattern: Option<String>,
+    /// Maximum length of a string (in characters) or array
+    pub max_length: Option<usize>,
+}
+
+impl ParamConstraint {
+    /// Checks the constraint's own consistency.
+    fn check(&self) -> Result<(), String> {
+        if self.param.is_empty() {
+            return Err("constraint has an empty param".to_string());
+        }
+        if let (Some(min), Some(max)) = (self.min, self.max) {
+            if min > max {
+                return Err(format!("{}: min {} is above max {}", self.param, min, max));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns why `params` violates the constraint, or None when it satisfies it.
+    fn violation(&self, params: Option<&Value>, pattern: Option<&Regex>) -> Option<String> {
+        let name = &self.param;
+        let value = match params.and_then(|params| params.get(name)) {
+            None | Some(Value::Null) if self.required => {
+                return Some(format!("{} is required", name))
+            }
+            None | Some(Value::Null) => return None,
+            Some(value) => value,
+        };
+
+        if let Some(param_type) = self.param_type {
+            if !param_type.matches(value) {
+                return Some(format!("{} is not a {}", name, param_type.as_str()));
+            }
+        }
+
+        let bounded = self.min.is_some()
+            || self.max.is_some()
+            || self.min_exclusive.is_some()
+            || self.max_exclusive.is_some();
+        if bounded {
+            let Some(number) = value.as_f64() else {
+                return Some(format!("{} is not a number", name));
+            };
+            if let Some(bound) = self.min.filter(|bound| number < *bound) {
+                return Some(format!("{} {} is below {}", name, number, bound));
+            }
+            if let Some(bound) = self.max.filter(|bound| number > *bound) {
+                return Some(format!("{} {} is above {}", name, number, bound));
+            }
+            if let Some(bound) = self.min_exclusive.filter(|bound| number <= *bound) {
+                return Some(format!("{} {} is not above {}", name, number, bound));
+            }
+            if let Some(bound) = self.max_exclusive.filter(|bound| number >= *bound) {
+                return Some(format!("{} {} is not below {}", name, number, bound));
+            }
+        }
+
+        if let Some(allowed) = &self.one_of {
+            if !allowed.contains(value) {
+                return Some(format!("{} {} is not an allowed value", name, value));
+            }
+        }
+
+        if let Some(pattern) = pattern {
+            match value.as_str() {
+                Some(text) if pattern.is_match(text) => {}
+                Some(_) => return Some(format!("{} does not match {}", name, pattern)),
+                None => return Some(format!("{} is not a string", name)),
+            }
+        }
+
+        if let Some(max_length) = self.max_length {
+            let length = match value {
+                Value::String(text) => text.chars().count(),
+                Value::Array(items) => items.len(),
+                _ => return Some(format!("{} has no length", name)),
+            };
+            if length > max_length {
+                return Some(format!(
+                    "{} is {} long, more than {}",
+                    name, length, max_length
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// Parsed tool selector and constraints of a rule.
+#[derive(Debug, Clone)]
+struct ToolSpec {
+    tool_name: String,
+    /// Empty = any method
+    tool_method: String,
+    constraints: Vec<(ParamConstraint, Option<Regex>)>,
+}
+
+impl ToolSpec {
+    fn parse(params: &Value) -> Result<Self, String> {
+        let text = |key: &str| -> Result<String, String> {
+            match params.get(key) {
+                None | Some(Value::Null) => Ok(String::new()),
+                Some(Value::String(text)) => Ok(text.clone()),
+                Some(other) => Err(format!("{} must be a string, got {}", key, other)),
+            }
+        };
+
+        let tool_name = text("tool_name")?;
+        if tool_name.is_empty() {
+            return Err("tool_name is required".to_string());
+        }
+        let tool_method = text("tool_method")?;
+
+        let constraints: Vec<ParamConstraint> = match params.get("constraints") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::String(json)) => {
+                serde_json::from_str(json).map_err(|e| format!("invalid constraints: {}", e))?
+            }
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| format!("invalid constraints: {}", e))?,
+        };
+
+        let constraints = constraints
+            .into_iter()
+            .map(|constraint| {
+                constraint.check()?;
+                let pattern = constraint
+                    .pattern
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .map_err(|e| format!("{}: invalid pattern: {}", constraint.param, e))?;
+                Ok((constraint, pattern))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            tool_name,
+            tool_method,
+            constraints,
+        })
+    }
+
+    /// Checks if the intent is a call this rule covers.
+    fn selects(&self, intent: &IntentEvent) -> bool {
+        let Some(tool_name) = intent.tool_name.as_deref() else {
+            return false;
+        };
+        glob_match(&self.tool_name, tool_name)
+            && (self.tool_method.is_empty()
+                || glob_match(
+                    &self.tool_method,
+                    intent.tool_method.as_deref().unwrap_or(""),
+                ))
+    }
+
+    /// Returns the first violated constraint, if any.
+    fn violation(&self, intent: &IntentEvent) -> Option<String> {
+        self.constraints.iter().find_map(|(constraint, pattern)| {
+            constraint.violation(intent.tool_params.as_ref(), pattern.as_ref())
+        })
+    }
+}
+
+/// Checks a tool constraint rule's selector and constraints.
+pub fn validate(metadata: &RuleMetadata) -> Result<(), String> {
+    ToolSpec::parse(&metadata.params).map(|_| ())
+}
+
+/// Deterministic rule over a tool call's name, method and parameters.
+#[derive(Debug)]
+pub struct ToolConstraintRule {
+    metadata: RuleMetadata,
+    spec: ToolSpec,
+}
+
+impl ToolConstraintRule {
+    /// Builds a rule from its metadata. Fails when the params do not parse.
+    pub fn from_metadata(metadata: RuleMetadata) -> Result<Self, String> {
+        let spec = ToolSpec::parse(&metadata.params)?;
+        Ok(Self { metadata, spec })
+    }
+}
+
+impl RuleInstance for ToolConstraintRule {
+    fn metadata(&self) -> &RuleMetadata {
+        &self.metadata
+    }
+
+    fn family_id(&self) -> &str {
+        FAMILY_ID
+    }
+
+    fn evaluate(&self, intent: &IntentEvent, _context: &EvaluationContext) -> Option<RuleMatch> {
+        let outcome = |matched: bool, detail: String| Some(RuleMatch { matched, detail });
+
+        let spec = &self.spec;
+        if !spec.selects(intent) {
+            return outcome(
+                false,
+                format!(
+                    "tool {} is not covered",
+                    intent.tool_name.as_deref().unwrap_or("-")
+                ),
+            );
+        }
+
+        let violation = spec.violation(intent);
+        let matched = match self.metadata.policy_type {
+            PolicyType::ContextAllow => violation.is_none(),
+            _ => violation.is_some(),
+        };
+        outcome(
+            matched,
+            violation.unwrap_or_else(|| "all constraints satisfied".to_string()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::families::test_support::{evaluate, intent, metadata};
+    use serde_json::json;
+
+    fn tool_call(tool_name: &str, tool_method: &str, tool_params: Value) -> IntentEvent {
+        intent(json!({
+            "tool_name": tool_name,
+            "tool_method": tool_method,
+            "tool_params": tool_params
+        }))
+    }
+
+    fn transfer_params() -> Value {
+        json!({
+            "rule_type": FAMILY_ID,
+            "tool_name": "payments.*",
+            "tool_method": "transfer",
+            "constraints": json!([
+                {"param": "amount", "type": "float", "required": true, "min": 0,
+                 "max_exclusive": 1000},
+                {"param": "currency", "enum": ["USD", "EUR"]},
+                {"param": "memo", "type": "string", "pattern": "^[a-z ]*$", "max_length": 8}
+            ])
+            .to_string()
+        })
+    }
+
+    #[test]
+    fn test_constraints_decide_allow_matches() {
+        let rule = ToolConstraintRule::from_metadata(metadata(
+            PolicyType::ContextAllow,
+            transfer_params(),
+        ))
+        .unwrap();
+        let matched = |tool_params: Value| {
+            evaluate(&rule, &tool_call("payments.api", "transfer", tool_params)).matched
+        };
+
+        assert!(matched(
+            json!({"amount": 999.5, "currency": "USD", "memo": "rent"})
+        ));
+        assert!(!matched(json!({"amount": 1000})));
+        assert!(!matched(json!({"amount": -1})));
+        assert!(!matched(json!({"amount": "10"})));
+        assert!(!matched(json!({"currency": "USD"})));
+        assert!(!matched(json!({"amount": 5, "currency": "GBP"})));
+        assert!(!matched(json!({"amount": 5, "memo": "Rent"})));
+        assert!(!matched(json!({"amount": 5, "memo": "rent for may"})));
+
+        let other_method = evaluate(
+            &rule,
+            &tool_call("payments.api", "refund", json!({"amount": 5})),
+        );
+        assert!(!other_method.matched);
+    }
+
+    #[test]
+    fn test_deny_policies_match_violations() {
+        let rule =
+            ToolConstraintRule::from_metadata(metadata(PolicyType::Forbidden, transfer_params()))
+                .unwrap();
+
+        let violation = evaluate(
+            &rule,
+            &tool_call("payments.api", "transfer", json!({"amount": 5000})),
+        );
+        assert!(violation.matched);
+        assert!(violation.detail.contains("amount"), "{}", violation.detail);
+
+        let valid = evaluate(
+            &rule,
+            &tool_call("payments.api", "transfer", json!({"amount": 5})),
+        );
+        assert!(!valid.matched);
+        let other_tool = evaluate(
+            &rule,
+            &tool_call("search", "transfer", json!({"amount": 5000})),
+        );
+        assert!(!other_tool.matched);
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_specs() {
+        let metadata = |params: Value| metadata(PolicyType::ContextAllow, params);
+
+        assert!(validate(&metadata(transfer_params())).is_ok());
+        assert!(validate(&metadata(json!({"rule_type": FAMILY_ID}))).is_err());
+        for constraints in [
+            json!([{"param": "", "type": "int"}]),
+            json!([{"param": "a", "type": "decimal"}]),
+            json!([{"param": "a", "min": 5, "max": 1}]),
+            json!([{"param": "a", "pattern": "("}]),
+            json!([{"param": "a", "maximum": 1}]),
+        ] {
+            let params = json!({"tool_name": "t", "constraints": constraints});
+            assert!(validate(&metadata(params)).is_err(), "{}", constraints);
+        }
+    }
+}
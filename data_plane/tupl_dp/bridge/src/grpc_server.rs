@@ -6,13 +6,17 @@
 //!
 //! This server provides the data plane side of the control/data plane integration.
 
-use crate::bridge::Bridge;
+use crate::bridge::{Bridge, RuleStatus};
+use crate::bundle::{ImportMode, RuleBundle};
 use crate::enforcement_engine::EnforcementEngine;
-use crate::families::DesignBoundaryRule;
-use crate::refresh::{RefreshScheduler, RefreshService, SchedulerConfig};
+use crate::families::rate_limit::{RateLimitFilter, RateLimitState};
+use crate::families::registry::{family, family_ids, validate_rule};
+use crate::refresh::{RefreshScheduler, RefreshService, ReplicaSync, SchedulerConfig, SyncConfig};
 use crate::rule_converter::{ControlPlaneRule, ParamValue};
 use crate::rule_vector::{convert_anchor_block, RuleVector};
-use crate::types::{RuleInstance, RuleScope};
+use crate::signing::{SequenceClaim, TrustedKeys};
+use crate::types::{normalize_tenant, now_ms, RuleInstance, RuleMetadata, RuleScope};
+use prost::Message;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::convert::TryInto;
@@ -100,13 +104,54 @@ pub mod rule_installation {
 
 use rule_installation::{
     data_plane_server::{DataPlane, DataPlaneServer},
-    EnforceRequest, EnforceResponse, EnforcementSessionSummary, GetRuleStatsRequest,
-    GetRuleStatsResponse, GetSessionRequest, GetSessionResponse, InstallRulesRequest,
-    InstallRulesResponse, QueryTelemetryRequest, QueryTelemetryResponse, RefreshRulesRequest,
-    RefreshRulesResponse, RemoveAgentRulesRequest, RemoveAgentRulesResponse, RemovePolicyRequest,
-    RemovePolicyResponse, RuleAnchorsPayload, RuleEvidence,
+    DiffRuleVersionsRequest, DiffRuleVersionsResponse, DiffStagedRulesRequest,
+    DiffStagedRulesResponse, DiscardStagedRulesRequest, DiscardStagedRulesResponse, EnforceRequest,
+    EnforceResponse, EnforcementSessionSummary, ExportRuleBundleRequest, ExportRuleBundleResponse,
+    GetAgentLabelsRequest, GetAgentLabelsResponse, GetRateLimitsRequest, GetRateLimitsResponse,
+    GetRuleStatsRequest, GetRuleStatsResponse, GetSessionRequest, GetSessionResponse,
+    HotCacheStats, ImportRuleBundleRequest, ImportRuleBundleResponse, InstallRulesRequest,
+    InstallRulesResponse, ListQuarantinedRulesRequest, ListQuarantinedRulesResponse,
+    ListRuleVersionsRequest, ListRuleVersionsResponse, ListRulesRequest, ListRulesResponse,
+    PromoteStagedRulesRequest, PromoteStagedRulesResponse, QuarantinedRule as ProtoQuarantinedRule,
+    QueryTelemetryRequest, QueryTelemetryResponse, RateLimitSelector,
+    RateLimitState as ProtoRateLimitState, RefreshRulesRequest, RefreshRulesResponse,
+    RemoveAgentRulesRequest, RemoveAgentRulesResponse, RemovePolicyRequest, RemovePolicyResponse,
+    ResetRateLimitsRequest, ResetRateLimitsResponse, RollbackRulesRequest, RollbackRulesResponse,
+    RuleAnchorsPayload, RuleEvidence, RuleInstallFailure, RuleInstance as ProtoRuleInstance,
+    RuleSignature, RuleStatusRequest, RuleStatusResponse, RuleSummary, SetAgentLabelsRequest,
+    SetAgentLabelsResponse, SignedCommand, SignedRuleBatch, StageRulesRequest, StageRulesResponse,
+    TableStats,
 };
 
+/// Requests that change rules or their enforcement and so must be signed when trusted
+/// keys are configured.
+trait SignedRequest: Message + Default {
+    /// Takes the request's `signed_command` and signature out of it.
+    fn take_envelope(&mut self) -> (Vec<u8>, Option<RuleSignature>);
+}
+
+macro_rules! impl_signed_request {
+    ($($request:ty),* $(,)?) => {$(
+        impl SignedRequest for $request {
+            fn take_envelope(&mut self) -> (Vec<u8>, Option<RuleSignature>) {
+                (std::mem::take(&mut self.signed_command), self.signature.take())
+            }
+        }
+    )*};
+}
+
+impl_signed_request!(
+    RemoveAgentRulesRequest,
+    RemovePolicyRequest,
+    PromoteStagedRulesRequest,
+    DiscardStagedRulesRequest,
+    RollbackRulesRequest,
+    RuleStatusRequest,
+    ImportRuleBundleRequest,
+    SetAgentLabelsRequest,
+    ResetRateLimitsRequest,
+);
+
 // ================================================================================================
 // DATA PLANE SERVICE IMPLEMENTATION
 // ================================================================================================
@@ -124,6 +169,9 @@ pub struct DataPlaneService {
 
     /// Service for rule refresh from warm storage
     refresh_service: Arc<RefreshService>,
+
+    /// Keys accepted for signed installs; None = signatures not required
+    trusted_keys: Option<Arc<TrustedKeys>>,
 }
 
 impl DataPlaneService {
@@ -161,177 +209,282 @@ impl DataPlaneService {
         // Initialize refresh service for warm storage refresh
         let refresh_service = Arc::new(RefreshService::new(Arc::clone(&bridge)));
 
+        // Require signed installs when trusted keys are configured
+        let trusted_keys = TrustedKeys::from_env().expect("Failed to load trusted signing keys");
+
         DataPlaneService {
             bridge,
             enforcement_engine,
             hitlog_query,
             refresh_service,
+            trusted_keys: None,
         }
+        .with_trusted_keys(trusted_keys)
     }
 
-}
+    /// Requires every request that changes rules or their enforcement to be signed by
+    /// one of `keys`.
+    ///
+    /// None turns verification off.
+    pub fn with_trusted_keys(mut self, keys: Option<TrustedKeys>) -> Self {
+        if let Some(keys) = &keys {
+            println!(
+                "Signature verification enabled (trusted keys: {})",
+                keys.key_ids().join(", ")
+            );
+        }
+        self.trusted_keys = keys.map(Arc::new);
+        self
+    }
 
-#[tonic::async_trait]
-impl DataPlane for DataPlaneService {
-    /// Install rules from the control plane into the bridge
-    async fn install_rules(
+    /// Verifies a detached signature over `payload` when trusted keys are configured.
+    ///
+    /// Returns the signer's key id, or None when verification is off.
+    #[allow(clippy::result_large_err)]
+    fn verify_signature(
         &self,
-        request: Request<InstallRulesRequest>,
-    ) -> Result<Response<InstallRulesResponse>, Status> {
-        let req = request.into_inner();
-
-        println!("================================================");
-        println!("  Installing Rules for Agent: {}", req.agent_id);
-        println!("================================================");
-        println!("  Config ID: {}", req.config_id);
-        println!("  Owner: {}", req.owner);
-        println!("  Rules to install: {}", req.rules.len());
-        println!();
-
-        let mut installed_count = 0;
-        let mut rules_by_layer = HashMap::new();
-        let mut failed_rules = Vec::new();
-
-        for proto_rule in req.rules {
-            let anchor_payload = proto_rule.anchors.clone();
-            // Convert proto RuleInstance to ControlPlaneRule
-            let cp_rule = ControlPlaneRule {
-                rule_id: proto_rule.rule_id.clone(),
-                family_id: proto_rule.family_id.clone(),
-                layer: proto_rule.layer.clone(),
-                agent_id: proto_rule.agent_id.clone(),
-                priority: proto_rule.priority,
-                enabled: proto_rule.enabled,
-                created_at_ms: proto_rule.created_at_ms,
-                policy_type: proto_rule.policy_type.clone(),
-                drift_threshold: proto_rule.drift_threshold,
-                modification_spec: proto_rule.modification_spec.clone(),
-                slice_weights: {
-                    let w = &proto_rule.slice_weights;
-                    if w.len() == 4 {
-                        [w[0], w[1], w[2], w[3]]
-                    } else {
-                        [0.25, 0.25, 0.25, 0.25]
-                    }
-                },
-                params: proto_rule
-                    .params
-                    .into_iter()
-                    .map(|(k, v)| {
-                        let param_value = if let Some(value) = v.value {
-                            match value {
-                                rule_installation::param_value::Value::StringValue(s) => {
-                                    ParamValue::String(s)
-                                }
-                                rule_installation::param_value::Value::IntValue(i) => {
-                                    ParamValue::Int(i)
-                                }
-                                rule_installation::param_value::Value::FloatValue(f) => {
-                                    ParamValue::Float(f)
-                                }
-                                rule_installation::param_value::Value::BoolValue(b) => {
-                                    ParamValue::Bool(b)
-                                }
-                                rule_installation::param_value::Value::StringList(list) => {
-                                    ParamValue::StringList(list.values)
-                                }
-                            }
-                        } else {
-                            ParamValue::String(String::new())
-                        };
-                        (k, param_value)
-                    })
-                    .collect(),
-            };
-
-            let rule_type = cp_rule
-                .params
-                .get("rule_type")
-                .and_then(|value| value.as_string())
-                .unwrap_or_default();
+        payload: &[u8],
+        signature: Option<RuleSignature>,
+    ) -> Result<Option<String>, Status> {
+        let Some(keys) = &self.trusted_keys else {
+            return Ok(None);
+        };
+        let signature =
+            signature.ok_or_else(|| Status::unauthenticated("Request must be signed"))?;
+        keys.verify(&signature.key_id, payload, &signature.signature)
+            .map_err(rejected_signature)?;
+        Ok(Some(signature.key_id))
+    }
 
-            let layer_label = if cp_rule.layer.is_empty() {
-                "global"
-            } else {
-                cp_rule.layer.as_str()
-            };
+    /// Resolves the rules of an install or staging request and verifies its signature.
+    ///
+    /// Signed requests carry their rules and install options inside `signed_batch`;
+    /// `unsigned` holds the ones sent in the request itself. When trusted keys are
+    /// configured nothing else is accepted. Returns the batch to act on and the claim
+    /// on its sequence.
+    #[allow(clippy::result_large_err)]
+    fn signed_rules(
+        &self,
+        unsigned: SignedRuleBatch,
+        signed_batch: Vec<u8>,
+        signature: Option<RuleSignature>,
+    ) -> Result<(SignedRuleBatch, Option<SequenceClaim>), Status> {
+        if signed_batch.is_empty() {
+            if self.trusted_keys.is_some() {
+                return Err(Status::unauthenticated(
+                    "Rules must be sent in a signed_batch with a signature",
+                ));
+            }
+            return Ok((unsigned, None));
+        }
+        if !unsigned.rules.is_empty() {
+            return Err(Status::invalid_argument(
+                "Send rules either in `rules` or in `signed_batch`, not both",
+            ));
+        }
 
-            println!(
-                "Processing rule: {} (type: {}, layer: {})",
-                cp_rule.rule_id,
-                rule_type,
-                layer_label
-            );
+        let signer = self.verify_signature(&signed_batch, signature)?;
+        let batch = SignedRuleBatch::decode(signed_batch.as_slice())
+            .map_err(|e| Status::invalid_argument(format!("Invalid signed_batch: {}", e)))?;
+        if batch.agent_id != unsigned.agent_id {
+            return Err(Status::invalid_argument(format!(
+                "Signed batch is for agent '{}', not '{}'",
+                batch.agent_id, unsigned.agent_id
+            )));
+        }
+        let claim = self.claim_sequence(signer.as_deref(), batch.sequence, batch.expires_at_ms)?;
+        Ok((batch, claim))
+    }
 
-            if rule_type != "design_boundary" {
-                let error_msg = format!(
-                    "Unsupported rule_type '{}' for rule {}",
-                    rule_type, cp_rule.rule_id
-                );
-                eprintln!("  ✗ {}\n", error_msg);
-                failed_rules.push(error_msg);
-                continue;
+    /// Resolves a request to `method` that changes rules or their enforcement.
+    ///
+    /// Signed requests carry the request itself inside `signed_command`, bound to the
+    /// method. When trusted keys are configured nothing else is accepted. Returns the
+    /// request to act on and the claim on its sequence; run the request in `signed`.
+    #[allow(clippy::result_large_err)]
+    fn authorize<T: SignedRequest>(
+        &self,
+        method: &str,
+        mut request: T,
+    ) -> Result<(T, Option<SequenceClaim>), Status> {
+        let (signed_command, signature) = request.take_envelope();
+        if signed_command.is_empty() {
+            if self.trusted_keys.is_some() {
+                return Err(Status::unauthenticated(format!(
+                    "{} must be sent in a signed_command with a signature",
+                    method
+                )));
             }
+            return Ok((request, None));
+        }
 
-            let bridge_rule = match convert_design_boundary_rule(&cp_rule) {
-                Ok(rule) => rule,
-                Err(e) => {
-                    let error_msg = format!("Failed to convert rule {}: {}", cp_rule.rule_id, e);
-                    eprintln!("  ✗ {}\n", error_msg);
-                    failed_rules.push(error_msg);
-                    continue;
-                }
-            };
+        let signer = self.verify_signature(&signed_command, signature)?;
+        let command = SignedCommand::decode(signed_command.as_slice())
+            .map_err(|e| Status::invalid_argument(format!("Invalid signed_command: {}", e)))?;
+        if command.method != method {
+            return Err(Status::invalid_argument(format!(
+                "Signed command is for {}, not {}",
+                command.method, method
+            )));
+        }
+        let claim =
+            self.claim_sequence(signer.as_deref(), command.sequence, command.expires_at_ms)?;
+        let request = T::decode(command.request.as_slice())
+            .map_err(|e| Status::invalid_argument(format!("Invalid signed {}: {}", method, e)))?;
+        Ok((request, claim))
+    }
 
-            // Design boundary rules must always include anchor payloads
-            let payload = match anchor_payload.clone() {
-                Some(payload) => payload,
-                None => {
-                    let error_msg = format!(
-                        "Design boundary '{}' missing pre-encoded anchors",
-                        cp_rule.rule_id
-                    );
-                    eprintln!("  ✗ {}\n", error_msg);
-                    failed_rules.push(error_msg);
-                    continue;
-                }
-            };
+    /// Refuses a verified payload that has expired; otherwise claims its sequence.
+    #[allow(clippy::result_large_err)]
+    fn claim_sequence(
+        &self,
+        signer: Option<&str>,
+        sequence: u64,
+        expires_at_ms: i64,
+    ) -> Result<Option<SequenceClaim>, Status> {
+        let Some(key_id) = signer else {
+            return Ok(None);
+        };
+        SequenceClaim::new(key_id, sequence, expires_at_ms, now_ms())
+            .map(Some)
+            .map_err(rejected_signature)
+    }
 
-            let rule_vector = match convert_proto_rule_anchors(payload) {
-                Ok(vector) => vector,
-                Err(err) => {
-                    let error_msg = format!("Invalid anchors for {}: {}", cp_rule.rule_id, err);
-                    eprintln!("  ✗ {}\n", error_msg);
-                    failed_rules.push(error_msg);
-                    continue;
-                }
-            };
+    /// Runs a signed request's changes, recording its sequence claim with them.
+    ///
+    /// Refuses the request if its sequence was already used.
+    #[allow(clippy::result_large_err)]
+    fn signed<T>(
+        &self,
+        claim: Option<&SequenceClaim>,
+        write: impl FnOnce() -> Result<T, Status>,
+    ) -> Result<T, Status> {
+        self.bridge
+            .signed_write(claim, write)
+            .map_err(rejected_signature)?
+    }
+
+    /// Validates and converts the whole batch first, then commits it in one step.
+    ///
+    /// If any rule fails validation nothing is installed and the response lists every
+    /// failure; storage errors during the commit are returned as errors.
+    fn install_rules_atomic(
+        &self,
+        req: InstallRulesRequest,
+        signer: Option<&str>,
+    ) -> Result<InstallRulesResponse, String> {
+        let mut batch = Vec::with_capacity(req.rules.len());
+        let mut rules_by_layer: HashMap<String, i32> = HashMap::new();
+        let mut failures = Vec::new();
 
-            match self.bridge.add_rule_with_anchors(bridge_rule, rule_vector) {
-                Ok(_) => {
-                    installed_count += 1;
-                    let layer_key = if cp_rule.layer.is_empty() {
-                        "global".to_string()
-                    } else {
-                        cp_rule.layer.clone()
-                    };
+        for proto_rule in req.rules {
+            match prepare_rule(proto_rule, signer) {
+                Ok((rule, vector, layer_key)) => {
                     *rules_by_layer.entry(layer_key).or_insert(0) += 1;
-                    println!("  ✓ Successfully installed\n");
+                    batch.push((rule, vector));
                 }
-                Err(e) => {
-                    let error_msg =
-                        format!("Failed to add rule {} to bridge: {}", cp_rule.rule_id, e);
-                    eprintln!("  ✗ {}\n", error_msg);
-                    failed_rules.push(error_msg);
+                Err(failure) => {
+                    eprintln!("  ✗ {}\n", failure.message);
+                    failures.push(failure);
                 }
             }
         }
 
+        if !failures.is_empty() {
+            self.print_install_summary(0, failures.len());
+            return Ok(InstallRulesResponse {
+                success: false,
+                message: format!(
+                    "Rejected batch for agent {}: {} of {} rules failed validation",
+                    req.agent_id,
+                    failures.len(),
+                    failures.len() + batch.len()
+                ),
+                rules_installed: 0,
+                rules_by_layer: HashMap::new(),
+                bridge_version: self.bridge.version() as i64,
+                failures,
+            });
+        }
+
+        let installed_count = batch.len();
+        self.bridge.add_rules_batch(batch).map_err(|e| {
+            eprintln!("  ✗ Atomic install failed: {}\n", e);
+            format!("Failed to commit rule batch: {}", e)
+        })?;
+
+        self.print_install_summary(installed_count, 0);
+
+        Ok(InstallRulesResponse {
+            success: true,
+            message: format!(
+                "Atomically installed {} rules for agent {}",
+                installed_count, req.agent_id
+            ),
+            rules_installed: installed_count as i32,
+            rules_by_layer,
+            bridge_version: self.bridge.version() as i64,
+            failures: Vec::new(),
+        })
+    }
+
+    /// Applies a status change to the rules selected by a RuleStatusRequest.
+    fn change_rule_status(
+        &self,
+        req: RuleStatusRequest,
+        status: RuleStatus,
+    ) -> Result<RuleStatusResponse, String> {
+        if req.rule_ids.is_empty() && req.agent_id.is_empty() {
+            return Ok(RuleStatusResponse {
+                success: false,
+                message: "No rule_ids or agent_id given".to_string(),
+                rules_updated: 0,
+                bridge_version: self.bridge.version() as i64,
+            });
+        }
+
+        let tenant = normalize_tenant(&req.tenant_id);
+        let mut changed = self
+            .bridge
+            .set_tenant_rule_status(tenant, &req.rule_ids, status)?;
+        if !req.agent_id.is_empty() {
+            changed.extend(
+                self.bridge
+                    .set_agent_status(tenant, &req.agent_id, status)?,
+            );
+        }
+
+        println!(
+            "Set {} rules to {} (bridge version {})",
+            changed.len(),
+            status.as_str(),
+            self.bridge.version()
+        );
+
+        Ok(RuleStatusResponse {
+            success: true,
+            message: format!("{} rules set to {}", changed.len(), status.as_str()),
+            rules_updated: changed.len() as i32,
+            bridge_version: self.bridge.version() as i64,
+        })
+    }
+
+    /// Validates a version number from a request against the recorded history.
+    fn recorded_version(&self, version: i64) -> Result<u64, String> {
+        let version =
+            u64::try_from(version).map_err(|_| format!("Invalid rule-set version {}", version))?;
+        match self.bridge.has_version(version)? {
+            true => Ok(version),
+            false => Err(format!("Unknown rule-set version {}", version)),
+        }
+    }
+
+    fn print_install_summary(&self, installed_count: usize, failed_count: usize) {
         println!("================================================");
         println!("  Installation Summary");
         println!("================================================");
         println!("  Successfully installed: {}", installed_count);
-        println!("  Failed: {}", failed_rules.len());
+        println!("  Failed: {}", failed_count);
         println!("  Bridge version: {}", self.bridge.version());
         println!("=================================================\n");
 
@@ -341,28 +494,110 @@ impl DataPlane for DataPlaneService {
         println!("  - Global rules: {}", current_stats.global_rules);
         println!("  - Scoped rules: {}", current_stats.scoped_rules);
         println!();
+    }
+}
 
-        if !failed_rules.is_empty() {
-            return Err(Status::internal(format!(
-                "Failed to install {} rules: {:?}",
-                failed_rules.len(),
-                failed_rules
-            )));
-        }
+#[tonic::async_trait]
+// Signed handlers run inside `signed` closures that return Status, like the handlers.
+#[allow(clippy::result_large_err)]
+impl DataPlane for DataPlaneService {
+    /// Install rules from the control plane into the bridge
+    async fn install_rules(
+        &self,
+        request: Request<InstallRulesRequest>,
+    ) -> Result<Response<InstallRulesResponse>, Status> {
+        let mut req = request.into_inner();
+        let unsigned = SignedRuleBatch {
+            agent_id: req.agent_id.clone(),
+            rules: std::mem::take(&mut req.rules),
+            config_id: std::mem::take(&mut req.config_id),
+            owner: std::mem::take(&mut req.owner),
+            atomic: req.atomic,
+            ..SignedRuleBatch::default()
+        };
+        let (batch, claim) = self.signed_rules(
+            unsigned,
+            std::mem::take(&mut req.signed_batch),
+            req.signature.take(),
+        )?;
+        req.rules = batch.rules;
+        req.config_id = batch.config_id;
+        req.owner = batch.owner;
+        req.atomic = batch.atomic;
+        let signer = claim.as_ref().map(|claim| claim.key_id.as_str());
+        self.signed(claim.as_ref(), || {
+            println!("================================================");
+            println!("  Installing Rules for Agent: {}", req.agent_id);
+            println!("================================================");
+            println!("  Config ID: {}", req.config_id);
+            println!("  Owner: {}", req.owner);
+            println!("  Rules to install: {}", req.rules.len());
+            println!("  Atomic: {}", req.atomic);
+            println!("  Signed by: {}", signer.unwrap_or("-"));
+            println!();
+
+            if req.atomic {
+                return self
+                    .install_rules_atomic(req, signer)
+                    .map(Response::new)
+                    .map_err(Status::internal);
+            }
 
-        Ok(Response::new(InstallRulesResponse {
-            success: true,
-            message: format!(
-                "Successfully installed {} rules for agent {}",
-                installed_count, req.agent_id
-            ),
-            rules_installed: installed_count as i32,
-            rules_by_layer: rules_by_layer
-                .into_iter()
-                .map(|(k, v)| (k, v as i32))
-                .collect(),
-            bridge_version: self.bridge.version() as i64,
-        }))
+            let mut installed_count = 0;
+            let mut rules_by_layer = HashMap::new();
+            let mut failed_rules = Vec::new();
+
+            for proto_rule in req.rules {
+                let prepared = prepare_rule(proto_rule, signer);
+                let (bridge_rule, rule_vector, layer_key) = match prepared {
+                    Ok(prepared) => prepared,
+                    Err(failure) => {
+                        eprintln!("  ✗ {}\n", failure.message);
+                        failed_rules.push(failure.message);
+                        continue;
+                    }
+                };
+                let rule_id = bridge_rule.rule_id().to_string();
+
+                match self.bridge.add_rule_with_anchors(bridge_rule, rule_vector) {
+                    Ok(_) => {
+                        installed_count += 1;
+                        *rules_by_layer.entry(layer_key).or_insert(0) += 1;
+                        println!("  ✓ Successfully installed\n");
+                    }
+                    Err(e) => {
+                        let error_msg = format!("Failed to add rule {} to bridge: {}", rule_id, e);
+                        eprintln!("  ✗ {}\n", error_msg);
+                        failed_rules.push(error_msg);
+                    }
+                }
+            }
+
+            self.print_install_summary(installed_count, failed_rules.len());
+
+            if !failed_rules.is_empty() {
+                return Err(Status::internal(format!(
+                    "Failed to install {} rules: {:?}",
+                    failed_rules.len(),
+                    failed_rules
+                )));
+            }
+
+            Ok(Response::new(InstallRulesResponse {
+                success: true,
+                message: format!(
+                    "Successfully installed {} rules for agent {}",
+                    installed_count, req.agent_id
+                ),
+                rules_installed: installed_count as i32,
+                rules_by_layer: rules_by_layer
+                    .into_iter()
+                    .map(|(k, v)| (k, v as i32))
+                    .collect(),
+                bridge_version: self.bridge.version() as i64,
+                failures: Vec::new(),
+            }))
+        })
     }
 
     /// Remove all rules for an agent from the bridge
@@ -370,28 +605,35 @@ impl DataPlane for DataPlaneService {
         &self,
         request: Request<RemoveAgentRulesRequest>,
     ) -> Result<Response<RemoveAgentRulesResponse>, Status> {
-        let req = request.into_inner();
-        println!("Removing rules for agent {}", req.agent_id);
+        let (req, claim) = self.authorize("RemoveAgentRules", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            let tenant = normalize_tenant(&req.tenant_id);
+            println!(
+                "Removing rules for agent {} of tenant {}",
+                req.agent_id, tenant
+            );
 
-        let mut removed_count = 0;
+            let mut removed_count = 0;
 
-        for rule in self.bridge.all_rules() {
-            if !rule.scope().applies_to(&req.agent_id) {
-                continue;
-            }
+            // Global and tag-selected rules are shared by other agents, so they stay.
+            for rule in self.bridge.tenant_rules(tenant) {
+                if !rule.scope().is_scoped_to(&req.agent_id) {
+                    continue;
+                }
 
-            match self.bridge.remove_rule(rule.rule_id()) {
-                Ok(true) => removed_count += 1,
-                Ok(false) => {}
-                Err(e) => eprintln!("Failed to remove rule {}: {}", rule.rule_id(), e),
+                match self.bridge.remove_rule(rule.rule_id()) {
+                    Ok(true) => removed_count += 1,
+                    Ok(false) => {}
+                    Err(e) => eprintln!("Failed to remove rule {}: {}", rule.rule_id(), e),
+                }
             }
-        }
 
-        Ok(Response::new(RemoveAgentRulesResponse {
-            success: true,
-            message: format!("Removed {} rules for agent {}", removed_count, req.agent_id),
-            rules_removed: removed_count as i32,
-        }))
+            Ok(Response::new(RemoveAgentRulesResponse {
+                success: true,
+                message: format!("Removed {} rules for agent {}", removed_count, req.agent_id),
+                rules_removed: removed_count as i32,
+            }))
+        })
     }
 
     /// Remove a specific policy rule for an agent from the bridge
@@ -399,47 +641,51 @@ impl DataPlane for DataPlaneService {
         &self,
         request: Request<RemovePolicyRequest>,
     ) -> Result<Response<RemovePolicyResponse>, Status> {
-        let req = request.into_inner();
-        println!(
-            "Removing policy {} for agent {}",
-            req.policy_id, req.agent_id
-        );
+        let (req, claim) = self.authorize("RemovePolicy", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            let tenant = normalize_tenant(&req.tenant_id);
+            println!(
+                "Removing policy {} for agent {} of tenant {}",
+                req.policy_id, req.agent_id, tenant
+            );
 
-        let Some(rule) = self.bridge.get_rule(&req.policy_id) else {
-            return Ok(Response::new(RemovePolicyResponse {
-                success: false,
-                message: format!("Policy not found: {}", req.policy_id),
-                rules_removed: 0,
-            }));
-        };
+            // Another tenant's policy is reported as not found.
+            let Some(rule) = self.bridge.get_tenant_rule(tenant, &req.policy_id) else {
+                return Ok(Response::new(RemovePolicyResponse {
+                    success: false,
+                    message: format!("Policy not found: {}", req.policy_id),
+                    rules_removed: 0,
+                }));
+            };
 
-        if !rule.scope().applies_to(&req.agent_id) {
-            return Ok(Response::new(RemovePolicyResponse {
-                success: false,
-                message: format!(
-                    "Policy {} does not belong to agent {}",
-                    req.policy_id, req.agent_id
-                ),
-                rules_removed: 0,
-            }));
-        }
+            if !rule.scope().is_scoped_to(&req.agent_id) {
+                return Ok(Response::new(RemovePolicyResponse {
+                    success: false,
+                    message: format!(
+                        "Policy {} does not belong to agent {}",
+                        req.policy_id, req.agent_id
+                    ),
+                    rules_removed: 0,
+                }));
+            }
 
-        match self.bridge.remove_rule(rule.rule_id()) {
-            Ok(true) => Ok(Response::new(RemovePolicyResponse {
-                success: true,
-                message: format!("Removed policy {}", req.policy_id),
-                rules_removed: 1,
-            })),
-            Ok(false) => Ok(Response::new(RemovePolicyResponse {
-                success: false,
-                message: format!("Policy not found: {}", req.policy_id),
-                rules_removed: 0,
-            })),
-            Err(err) => Err(Status::internal(format!(
-                "Failed to remove policy {}: {}",
-                req.policy_id, err
-            ))),
-        }
+            match self.bridge.remove_rule(rule.rule_id()) {
+                Ok(true) => Ok(Response::new(RemovePolicyResponse {
+                    success: true,
+                    message: format!("Removed policy {}", req.policy_id),
+                    rules_removed: 1,
+                })),
+                Ok(false) => Ok(Response::new(RemovePolicyResponse {
+                    success: false,
+                    message: format!("Policy not found: {}", req.policy_id),
+                    rules_removed: 0,
+                })),
+                Err(err) => Err(Status::internal(format!(
+                    "Failed to remove policy {}: {}",
+                    req.policy_id, err
+                ))),
+            }
+        })
     }
 
     /// Get current rule statistics from the bridge
@@ -451,11 +697,34 @@ impl DataPlane for DataPlaneService {
 
         Ok(Response::new(GetRuleStatsResponse {
             bridge_version: stats.version as i64,
-            total_tables: 1,
+            total_tables: stats.tables.len() as i32,
             total_rules: stats.total_rules as i32,
             total_global_rules: stats.global_rules as i32,
             total_scoped_rules: stats.scoped_rules as i32,
-            table_stats: Vec::new(),
+            table_stats: stats
+                .tables
+                .into_iter()
+                .map(|table| TableStats {
+                    family_id: table.family_id,
+                    layer_id: table.layer,
+                    version: stats.version as i64,
+                    rule_count: table.rule_count as i32,
+                    global_count: table.global_count as i32,
+                    scoped_count: (table.rule_count - table.global_count) as i32,
+                })
+                .collect(),
+            last_synced_version: stats.version as i64,
+            last_synced_at_ms: stats.last_synced_at as i64,
+            hot_cache: Some(HotCacheStats {
+                entries: stats.hot_cache.entries as i64,
+                capacity: stats.hot_cache.capacity as i64,
+                hits: stats.hot_cache.hits as i64,
+                misses: stats.hot_cache.misses as i64,
+                evictions: stats.hot_cache.total_evictions as i64,
+                evicted_entries: stats.hot_cache.total_evicted as i64,
+            }),
+            quarantined_rules: stats.quarantined_rules as i32,
+            registered_families: family_ids().into_iter().map(str::to_string).collect(),
         }))
     }
 
@@ -494,12 +763,30 @@ impl DataPlane for DataPlaneService {
 
         let drift_score = req.drift_score;
 
-        // Call enforcement engine
-        let result = self
-            .enforcement_engine
-            .enforce(&req.intent_event_json, vector_override, &request_id, drift_score)
-            .await
-            .map_err(|e| Status::internal(format!("Enforcement failed: {}", e)))?;
+        // Call enforcement engine (staged dry runs evaluate the candidate rule set)
+        let result = if req.evaluate_staged {
+            self.enforcement_engine
+                .enforce_staged(
+                    &req.intent_event_json,
+                    vector_override,
+                    &request_id,
+                    drift_score,
+                )
+                .await
+                .map_err(|e| {
+                    Status::failed_precondition(format!("Staged enforcement failed: {}", e))
+                })?
+        } else {
+            self.enforcement_engine
+                .enforce(
+                    &req.intent_event_json,
+                    vector_override,
+                    &request_id,
+                    drift_score,
+                )
+                .await
+                .map_err(|e| Status::internal(format!("Enforcement failed: {}", e)))?
+        };
 
         // Derive legacy 0/1 decision from EnforcementDecision for backward compat
         let legacy_decision = if let Some(ref ed) = result.enforcement_decision {
@@ -571,9 +858,11 @@ impl DataPlane for DataPlaneService {
             _ => None,
         };
 
+        // Telemetry is always confined to one tenant.
+        let tenant = normalize_tenant(req.tenant_id.as_deref().unwrap_or_default());
         let filter = crate::telemetry::query::QueryFilter {
             agent_id: req.agent_id,
-            tenant_id: req.tenant_id,
+            tenant_id: Some(tenant.to_string()),
             decision: decision_filter,
             layer: req.layer,
             start_time_ms: req.start_time_ms.map(|t| t as u64),
@@ -619,9 +908,11 @@ impl DataPlane for DataPlaneService {
     ) -> Result<Response<GetSessionResponse>, Status> {
         let req = request.into_inner();
 
-        // Query for specific session using session_id filter
+        // Query for specific session using session_id filter; other tenants' sessions
+        // are not found.
         let filter = crate::telemetry::query::QueryFilter {
             session_id: Some(req.session_id.clone()),
+            tenant_id: Some(normalize_tenant(&req.tenant_id).to_string()),
             limit: Some(1),
             ..Default::default()
         };
@@ -657,29 +948,47 @@ impl DataPlane for DataPlaneService {
     /// - Testing refresh mechanism
     async fn refresh_rules(
         &self,
-        _request: Request<RefreshRulesRequest>,
+        request: Request<RefreshRulesRequest>,
     ) -> Result<Response<RefreshRulesResponse>, Status> {
+        let req = request.into_inner();
         println!("================================================");
-        println!("  RefreshRules RPC called");
+        println!("  RefreshRules RPC called (full: {})", req.full);
         println!("================================================");
 
         // Call refresh service
-        match self.refresh_service.refresh_from_storage().await {
+        let result = if req.full {
+            self.refresh_service.full_refresh_from_storage().await
+        } else {
+            self.refresh_service.refresh_from_storage().await
+        };
+
+        match result {
             Ok(stats) => {
                 println!(
-                    "Refresh completed: {} rules in {}ms",
-                    stats.rules_refreshed, stats.duration_ms
+                    "Refresh completed: {} rules in {}ms (+{} ~{} -{})",
+                    stats.rules_refreshed,
+                    stats.duration_ms,
+                    stats.rules_added,
+                    stats.rules_updated,
+                    stats.rules_removed
                 );
                 println!("=================================================\n");
 
                 Ok(Response::new(RefreshRulesResponse {
                     success: true,
                     message: format!(
-                        "Refreshed {} rules in {}ms",
-                        stats.rules_refreshed, stats.duration_ms
+                        "Refreshed {} rules in {}ms: {} added, {} updated, {} removed",
+                        stats.rules_refreshed,
+                        stats.duration_ms,
+                        stats.rules_added,
+                        stats.rules_updated,
+                        stats.rules_removed
                     ),
                     rules_refreshed: stats.rules_refreshed as i32,
                     duration_ms: stats.duration_ms as i64,
+                    rules_added: stats.rules_added as i32,
+                    rules_updated: stats.rules_updated as i32,
+                    rules_removed: stats.rules_removed as i32,
                 }))
             }
             Err(e) => {
@@ -689,12 +998,581 @@ impl DataPlane for DataPlaneService {
             }
         }
     }
+
+    /// Upload rules into the staging area.
+    ///
+    /// The batch is validated as a whole; if any rule fails nothing is staged.
+    async fn stage_rules(
+        &self,
+        request: Request<StageRulesRequest>,
+    ) -> Result<Response<StageRulesResponse>, Status> {
+        let req = request.into_inner();
+        let unsigned = SignedRuleBatch {
+            rules: req.rules,
+            ..SignedRuleBatch::default()
+        };
+        let (batch, claim) = self.signed_rules(unsigned, req.signed_batch, req.signature)?;
+        let rules = batch.rules;
+        let signer = claim.as_ref().map(|claim| claim.key_id.as_str());
+        self.signed(claim.as_ref(), || {
+            println!("Staging {} rules (replace: {})", rules.len(), req.replace);
+
+            let mut batch = Vec::with_capacity(rules.len());
+            let mut failures = Vec::new();
+            for proto_rule in rules {
+                match prepare_rule(proto_rule, signer) {
+                    Ok((rule, vector, _)) => batch.push((rule, vector)),
+                    Err(failure) => {
+                        eprintln!("  ✗ {}\n", failure.message);
+                        failures.push(failure);
+                    }
+                }
+            }
+
+            if !failures.is_empty() {
+                return Ok(Response::new(StageRulesResponse {
+                    success: false,
+                    message: format!("{} rules failed validation; nothing staged", failures.len()),
+                    staged_count: self.bridge.staged_rule_count().unwrap_or(0) as i32,
+                    failures,
+                }));
+            }
+
+            let staged_count = self.bridge.stage_rules(batch, req.replace);
+
+            Ok(Response::new(StageRulesResponse {
+                success: true,
+                message: format!("{} rules staged", staged_count),
+                staged_count: staged_count as i32,
+                failures: Vec::new(),
+            }))
+        })
+    }
+
+    /// Compare the staged rule set against the active one
+    async fn diff_staged_rules(
+        &self,
+        request: Request<DiffStagedRulesRequest>,
+    ) -> Result<Response<DiffStagedRulesResponse>, Status> {
+        let req = request.into_inner();
+        let diff = self
+            .bridge
+            .diff_staged(normalize_tenant(&req.tenant_id))
+            .map_err(|e| Status::internal(format!("Failed to diff staged rules: {}", e)))?;
+
+        Ok(Response::new(DiffStagedRulesResponse {
+            has_staged: diff.is_some(),
+            staged_count: self.bridge.staged_rule_count().unwrap_or(0) as i32,
+            base_version: self.bridge.staged_version().unwrap_or(0) as i64,
+            diff: diff.map(proto_rule_set_diff),
+        }))
+    }
+
+    /// Atomically replace the active rule set with the staged one
+    async fn promote_staged_rules(
+        &self,
+        request: Request<PromoteStagedRulesRequest>,
+    ) -> Result<Response<PromoteStagedRulesResponse>, Status> {
+        let (req, claim) = self.authorize("PromoteStagedRules", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            if self.bridge.staged_rule_count().is_none() {
+                return Ok(Response::new(PromoteStagedRulesResponse {
+                    success: false,
+                    message: "No staged rule set to promote".to_string(),
+                    bridge_version: self.bridge.version() as i64,
+                    diff: None,
+                }));
+            }
+
+            let diff = self
+                .bridge
+                .promote_staged(normalize_tenant(&req.tenant_id))
+                .map_err(|e| Status::internal(format!("Failed to promote staged rules: {}", e)))?;
+
+            println!(
+                "Promoted staged rules: +{} -{} ~{} (bridge version {})",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len(),
+                self.bridge.version()
+            );
+
+            Ok(Response::new(PromoteStagedRulesResponse {
+                success: true,
+                message: format!(
+                    "Promoted staged rules: {} added, {} removed, {} changed",
+                    diff.added.len(),
+                    diff.removed.len(),
+                    diff.changed.len()
+                ),
+                bridge_version: self.bridge.version() as i64,
+                diff: Some(proto_rule_set_diff(diff)),
+            }))
+        })
+    }
+
+    /// Drop the staged rule set
+    async fn discard_staged_rules(
+        &self,
+        request: Request<DiscardStagedRulesRequest>,
+    ) -> Result<Response<DiscardStagedRulesResponse>, Status> {
+        let (_, claim) = self.authorize("DiscardStagedRules", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            let response = match self.bridge.discard_staged() {
+                Some(count) => DiscardStagedRulesResponse {
+                    success: true,
+                    message: format!("Discarded {} staged rules", count),
+                    rules_discarded: count as i32,
+                },
+                None => DiscardStagedRulesResponse {
+                    success: false,
+                    message: "No staged rule set to discard".to_string(),
+                    rules_discarded: 0,
+                },
+            };
+            Ok(Response::new(response))
+        })
+    }
+
+    /// Pause rules without deleting them
+    async fn disable_rules(
+        &self,
+        request: Request<RuleStatusRequest>,
+    ) -> Result<Response<RuleStatusResponse>, Status> {
+        let (req, claim) = self.authorize("DisableRules", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            self.change_rule_status(req, RuleStatus::Disabled)
+                .map(Response::new)
+                .map_err(|e| Status::internal(format!("Failed to disable rules: {}", e)))
+        })
+    }
+
+    /// Re-enable disabled or archived rules
+    async fn enable_rules(
+        &self,
+        request: Request<RuleStatusRequest>,
+    ) -> Result<Response<RuleStatusResponse>, Status> {
+        let (req, claim) = self.authorize("EnableRules", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            self.change_rule_status(req, RuleStatus::Active)
+                .map(Response::new)
+                .map_err(|e| Status::internal(format!("Failed to enable rules: {}", e)))
+        })
+    }
+
+    /// Retire rules without deleting them
+    async fn archive_rules(
+        &self,
+        request: Request<RuleStatusRequest>,
+    ) -> Result<Response<RuleStatusResponse>, Status> {
+        let (req, claim) = self.authorize("ArchiveRules", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            self.change_rule_status(req, RuleStatus::Archived)
+                .map(Response::new)
+                .map_err(|e| Status::internal(format!("Failed to archive rules: {}", e)))
+        })
+    }
+
+    /// List stored rules in every status
+    async fn list_rules(
+        &self,
+        request: Request<ListRulesRequest>,
+    ) -> Result<Response<ListRulesResponse>, Status> {
+        let req = request.into_inner();
+        let status = if req.status.is_empty() {
+            None
+        } else {
+            Some(RuleStatus::parse(&req.status).map_err(Status::invalid_argument)?)
+        };
+        let agent_id = (!req.agent_id.is_empty()).then_some(req.agent_id.as_str());
+
+        let listings = self
+            .bridge
+            .list_rules(Some(normalize_tenant(&req.tenant_id)), agent_id)
+            .map_err(|e| Status::internal(format!("Failed to list rules: {}", e)))?;
+
+        Ok(Response::new(ListRulesResponse {
+            rules: listings
+                .into_iter()
+                .filter(|listing| status.is_none_or(|status| listing.status == status))
+                .map(|listing| RuleSummary {
+                    rule_id: listing.rule_id,
+                    tenant_id: listing.tenant_id,
+                    layer: listing.layer.unwrap_or_default(),
+                    priority: listing.priority as i32,
+                    policy_type: listing.policy_type.as_str().to_string(),
+                    status: listing.status.as_str().to_string(),
+                    updated_at_ms: listing.updated_at_ms as i64,
+                    signed_by: listing.signed_by.unwrap_or_default(),
+                    tags: listing.tags,
+                })
+                .collect(),
+        }))
+    }
+
+    /// List rule rows that failed to load and were moved to quarantine
+    async fn list_quarantined_rules(
+        &self,
+        request: Request<ListQuarantinedRulesRequest>,
+    ) -> Result<Response<ListQuarantinedRulesResponse>, Status> {
+        let req = request.into_inner();
+        let quarantined = self
+            .bridge
+            .list_tenant_quarantined(normalize_tenant(&req.tenant_id))
+            .map_err(|e| Status::internal(format!("Failed to list quarantined rules: {}", e)))?;
+
+        Ok(Response::new(ListQuarantinedRulesResponse {
+            rules: quarantined
+                .into_iter()
+                .map(|quarantined| ProtoQuarantinedRule {
+                    rule_id: quarantined.row.rule_id,
+                    tenant_id: quarantined.row.tenant_id,
+                    layer: quarantined.row.layer.unwrap_or_default(),
+                    priority: quarantined.row.priority as i32,
+                    reason: quarantined.reason,
+                    quarantined_at_ms: quarantined.quarantined_at_ms as i64,
+                })
+                .collect(),
+        }))
+    }
+
+    /// List recorded rule-set versions, newest first
+    async fn list_rule_versions(
+        &self,
+        request: Request<ListRuleVersionsRequest>,
+    ) -> Result<Response<ListRuleVersionsResponse>, Status> {
+        let req = request.into_inner();
+        let limit = if req.limit <= 0 {
+            50
+        } else {
+            req.limit.min(500)
+        } as usize;
+
+        let versions = self
+            .bridge
+            .list_tenant_versions(normalize_tenant(&req.tenant_id), limit)
+            .map_err(|e| Status::internal(format!("Failed to list versions: {}", e)))?;
+
+        Ok(Response::new(ListRuleVersionsResponse {
+            versions: versions
+                .into_iter()
+                .map(|v| rule_installation::RuleSetVersion {
+                    version: v.version as i64,
+                    kind: v.kind,
+                    description: v.description,
+                    rule_count: v.rule_count as i32,
+                    created_at_ms: v.created_at_ms as i64,
+                })
+                .collect(),
+            current_version: self.bridge.version() as i64,
+        }))
+    }
+
+    /// Compare the rule sets of two recorded versions
+    async fn diff_rule_versions(
+        &self,
+        request: Request<DiffRuleVersionsRequest>,
+    ) -> Result<Response<DiffRuleVersionsResponse>, Status> {
+        let req = request.into_inner();
+        let from = self
+            .recorded_version(req.from_version)
+            .map_err(Status::not_found)?;
+        let to = self
+            .recorded_version(req.to_version)
+            .map_err(Status::not_found)?;
+
+        let diff = self
+            .bridge
+            .diff_tenant_versions(normalize_tenant(&req.tenant_id), from, to)
+            .map_err(|e| Status::internal(format!("Failed to diff versions: {}", e)))?;
+
+        Ok(Response::new(DiffRuleVersionsResponse {
+            diff: Some(proto_rule_set_diff(diff)),
+        }))
+    }
+
+    /// Roll one tenant's rules, or one agent's, back to a recorded version
+    async fn rollback_rules(
+        &self,
+        request: Request<RollbackRulesRequest>,
+    ) -> Result<Response<RollbackRulesResponse>, Status> {
+        let (req, claim) = self.authorize("RollbackRules", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            let version = self
+                .recorded_version(req.version)
+                .map_err(Status::not_found)?;
+            let agent_id = (!req.agent_id.is_empty()).then_some(req.agent_id.as_str());
+            let tenant = normalize_tenant(&req.tenant_id);
+
+            println!("\n=================================================");
+            println!(
+                "Rolling back tenant {} agent {} to version {}",
+                tenant,
+                agent_id.unwrap_or("*"),
+                version
+            );
+
+            let diff = self
+                .bridge
+                .rollback_to(version, tenant, agent_id)
+                .map_err(|e| {
+                    eprintln!("  ✗ Rollback failed: {}", e);
+                    Status::internal(format!("Rollback failed: {}", e))
+                })?;
+
+            println!(
+                "  ✓ +{} -{} ~{} (bridge version {})",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len(),
+                self.bridge.version()
+            );
+            println!("=================================================\n");
+
+            Ok(Response::new(RollbackRulesResponse {
+                success: true,
+                message: format!(
+                    "Rolled back to version {}: {} added, {} removed, {} changed",
+                    version,
+                    diff.added.len(),
+                    diff.removed.len(),
+                    diff.changed.len()
+                ),
+                bridge_version: self.bridge.version() as i64,
+                diff: Some(proto_rule_set_diff(diff)),
+            }))
+        })
+    }
+
+    /// Export a tenant's active rules, with their anchors, as a portable bundle
+    async fn export_rule_bundle(
+        &self,
+        request: Request<ExportRuleBundleRequest>,
+    ) -> Result<Response<ExportRuleBundleResponse>, Status> {
+        let req = request.into_inner();
+        let bundle = self
+            .bridge
+            .export_bundle(normalize_tenant(&req.tenant_id))
+            .map_err(|e| Status::internal(format!("Failed to export bundle: {}", e)))?;
+        let bytes = bundle
+            .to_bytes()
+            .map_err(|e| Status::internal(format!("Failed to export bundle: {}", e)))?;
+
+        println!(
+            "Exported bundle of {} rules at version {} ({} bytes)",
+            bundle.rules.len(),
+            bundle.source_version,
+            bytes.len()
+        );
+
+        Ok(Response::new(ExportRuleBundleResponse {
+            bundle: bytes,
+            rule_count: bundle.rules.len() as i32,
+            bridge_version: bundle.source_version as i64,
+        }))
+    }
+
+    /// Validate and import a bundle produced by ExportRuleBundle
+    async fn import_rule_bundle(
+        &self,
+        request: Request<ImportRuleBundleRequest>,
+    ) -> Result<Response<ImportRuleBundleResponse>, Status> {
+        let (req, claim) = self.authorize("ImportRuleBundle", request.into_inner())?;
+        let signer = claim.as_ref().map(|claim| claim.key_id.as_str());
+        self.signed(claim.as_ref(), || {
+            let mut bundle =
+                RuleBundle::from_bytes(&req.bundle).map_err(Status::invalid_argument)?;
+            bundle.set_signer(signer);
+            let mode = if req.replace {
+                ImportMode::Replace
+            } else {
+                ImportMode::Merge
+            };
+
+            println!(
+                "Importing bundle of {} rules (mode: {}, dry run: {})",
+                bundle.rules.len(),
+                mode.as_str(),
+                req.dry_run
+            );
+
+            let import = self
+                .bridge
+                .import_bundle(normalize_tenant(&req.tenant_id), bundle, mode, req.dry_run)
+                .map_err(|e| Status::internal(format!("Failed to import bundle: {}", e)))?;
+
+            if !import.failures.is_empty() {
+                for failure in &import.failures {
+                    eprintln!("  ✗ {}: {}", failure.rule_id, failure.message);
+                }
+                return Ok(Response::new(ImportRuleBundleResponse {
+                    success: false,
+                    message: format!(
+                        "{} rules failed validation; nothing imported",
+                        import.failures.len()
+                    ),
+                    bridge_version: self.bridge.version() as i64,
+                    diff: None,
+                    failures: import
+                        .failures
+                        .into_iter()
+                        .map(|failure| RuleInstallFailure {
+                            rule_id: failure.rule_id,
+                            stage: failure.stage.to_string(),
+                            message: failure.message,
+                        })
+                        .collect(),
+                }));
+            }
+
+            let diff = import.diff;
+            let summary = format!(
+                "{} added, {} removed, {} changed",
+                diff.added.len(),
+                diff.removed.len(),
+                diff.changed.len()
+            );
+            let message = match import.version {
+                Some(version) => format!("Imported bundle as version {}: {}", version, summary),
+                None => format!(
+                    "Dry run: bundle is valid; importing would apply {}",
+                    summary
+                ),
+            };
+            println!("  ✓ {}", message);
+
+            Ok(Response::new(ImportRuleBundleResponse {
+                success: true,
+                message,
+                bridge_version: self.bridge.version() as i64,
+                diff: Some(proto_rule_set_diff(diff)),
+                failures: Vec::new(),
+            }))
+        })
+    }
+
+    /// Register the labels rule selector tags are matched against for an agent
+    async fn set_agent_labels(
+        &self,
+        request: Request<SetAgentLabelsRequest>,
+    ) -> Result<Response<SetAgentLabelsResponse>, Status> {
+        let (req, claim) = self.authorize("SetAgentLabels", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            let tenant = normalize_tenant(&req.tenant_id);
+            println!(
+                "Setting {} labels for agent {} of tenant {}",
+                req.labels.len(),
+                req.agent_id,
+                tenant
+            );
+
+            match self
+                .bridge
+                .set_agent_labels(tenant, &req.agent_id, req.labels)
+            {
+                Ok(()) => Ok(Response::new(SetAgentLabelsResponse {
+                    success: true,
+                    message: format!("Updated labels for agent {}", req.agent_id),
+                })),
+                Err(e) => Ok(Response::new(SetAgentLabelsResponse {
+                    success: false,
+                    message: e,
+                })),
+            }
+        })
+    }
+
+    /// Get the labels registered for an agent
+    async fn get_agent_labels(
+        &self,
+        request: Request<GetAgentLabelsRequest>,
+    ) -> Result<Response<GetAgentLabelsResponse>, Status> {
+        let req = request.into_inner();
+        Ok(Response::new(GetAgentLabelsResponse {
+            labels: self.bridge.agent_labels(&req.tenant_id, &req.agent_id),
+        }))
+    }
+
+    /// Inspect the data-plane counters of rate limit rules
+    async fn get_rate_limits(
+        &self,
+        request: Request<GetRateLimitsRequest>,
+    ) -> Result<Response<GetRateLimitsResponse>, Status> {
+        let filter = rate_limit_filter(request.into_inner().selector);
+        let states = self.bridge.rate_limits().states(&filter);
+        Ok(Response::new(GetRateLimitsResponse {
+            states: states.into_iter().map(proto_rate_limit_state).collect(),
+        }))
+    }
+
+    /// Reset the data-plane counters of rate limit rules
+    async fn reset_rate_limits(
+        &self,
+        request: Request<ResetRateLimitsRequest>,
+    ) -> Result<Response<ResetRateLimitsResponse>, Status> {
+        let (req, claim) = self.authorize("ResetRateLimits", request.into_inner())?;
+        self.signed(claim.as_ref(), || {
+            let filter = rate_limit_filter(req.selector);
+            let reset = self.bridge.rate_limits().reset(&filter);
+            println!(
+                "Reset {} rate limit counters of tenant {}",
+                reset,
+                normalize_tenant(&filter.tenant_id)
+            );
+
+            Ok(Response::new(ResetRateLimitsResponse {
+                success: true,
+                message: format!("Reset {} rate limit counters", reset),
+                reset_count: reset as i32,
+            }))
+        })
+    }
 }
 
 // ================================================================================================
 // HELPER FUNCTIONS
 // ================================================================================================
 
+fn rejected_signature(error: String) -> Status {
+    eprintln!("  ✗ Rejected signature: {}", error);
+    Status::unauthenticated(error)
+}
+
+fn proto_rule_set_diff(diff: crate::bridge::RuleSetDiff) -> rule_installation::RuleSetDiff {
+    rule_installation::RuleSetDiff {
+        added: diff.added,
+        removed: diff.removed,
+        changed: diff.changed,
+        unchanged_count: diff.unchanged as i32,
+    }
+}
+
+fn rate_limit_filter(selector: Option<RateLimitSelector>) -> RateLimitFilter {
+    let selector = selector.unwrap_or_default();
+    RateLimitFilter {
+        tenant_id: selector.tenant_id,
+        rule_id: selector.rule_id,
+        agent_id: selector.agent_id,
+        tool_name: selector.tool_name,
+    }
+}
+
+fn proto_rate_limit_state(state: RateLimitState) -> ProtoRateLimitState {
+    ProtoRateLimitState {
+        tenant_id: state.key.tenant_id,
+        rule_id: state.key.rule_id,
+        agent_id: state.key.agent_id,
+        tool_name: state.key.tool_name,
+        layer: state.key.layer,
+        algorithm: state.algorithm.as_str().to_string(),
+        limit: state.limit as i64,
+        window_ms: state.window_ms as i64,
+        capacity: state.capacity as i64,
+        remaining: state.remaining as i64,
+        retry_after_ms: state.retry_after_ms as i64,
+    }
+}
+
 /// Extract a summary from the intent JSON (tool_name or action)
 fn extract_intent_summary(intent_json: &str) -> String {
     // Try to parse JSON and extract tool_name or action
@@ -712,16 +1590,166 @@ fn extract_intent_summary(intent_json: &str) -> String {
     "unknown".to_string()
 }
 
-fn convert_design_boundary_rule(
+fn proto_rule_to_control_plane(proto_rule: ProtoRuleInstance) -> ControlPlaneRule {
+    ControlPlaneRule {
+        rule_id: proto_rule.rule_id,
+        family_id: proto_rule.family_id,
+        layer: proto_rule.layer,
+        agent_id: proto_rule.agent_id,
+        tenant_id: proto_rule.tenant_id,
+        tags: proto_rule.tags,
+        priority: proto_rule.priority,
+        enabled: proto_rule.enabled,
+        created_at_ms: proto_rule.created_at_ms,
+        policy_type: proto_rule.policy_type,
+        drift_threshold: proto_rule.drift_threshold,
+        modification_spec: proto_rule.modification_spec,
+        not_before_ms: proto_rule.not_before_ms,
+        expires_at_ms: proto_rule.expires_at_ms,
+        slice_weights: {
+            let w = &proto_rule.slice_weights;
+            if w.len() == 4 {
+                [w[0], w[1], w[2], w[3]]
+            } else {
+                [0.25, 0.25, 0.25, 0.25]
+            }
+        },
+        params: proto_rule
+            .params
+            .into_iter()
+            .map(|(k, v)| {
+                let param_value = if let Some(value) = v.value {
+                    match value {
+                        rule_installation::param_value::Value::StringValue(s) => {
+                            ParamValue::String(s)
+                        }
+                        rule_installation::param_value::Value::IntValue(i) => ParamValue::Int(i),
+                        rule_installation::param_value::Value::FloatValue(f) => {
+                            ParamValue::Float(f)
+                        }
+                        rule_installation::param_value::Value::BoolValue(b) => ParamValue::Bool(b),
+                        rule_installation::param_value::Value::StringList(list) => {
+                            ParamValue::StringList(list.values)
+                        }
+                    }
+                } else {
+                    ParamValue::String(String::new())
+                };
+                (k, param_value)
+            })
+            .collect(),
+    }
+}
+
+/// Converts a proto rule into a bridge rule plus its anchors and layer label.
+///
+/// The rule's family is looked up in the registry, which validates and builds it.
+fn prepare_rule(
+    proto_rule: ProtoRuleInstance,
+    signer: Option<&str>,
+) -> Result<(Arc<dyn RuleInstance>, RuleVector, String), RuleInstallFailure> {
+    let anchor_payload = proto_rule.anchors.clone();
+    let cp_rule = proto_rule_to_control_plane(proto_rule);
+
+    let failure = |stage: &str, message: String| RuleInstallFailure {
+        rule_id: cp_rule.rule_id.clone(),
+        stage: stage.to_string(),
+        message,
+    };
+
+    let rule_type = cp_rule
+        .params
+        .get("rule_type")
+        .and_then(|value| value.as_string())
+        .unwrap_or_default();
+
+    let layer_label = if cp_rule.layer.is_empty() {
+        "global"
+    } else {
+        cp_rule.layer.as_str()
+    };
+
+    println!(
+        "Processing rule: {} (type: {}, family: {}, layer: {})",
+        cp_rule.rule_id, rule_type, cp_rule.family_id, layer_label
+    );
+
+    // `rule_type` names the family; `family_id` is only used for rules without one.
+    let family_id = if rule_type.is_empty() {
+        cp_rule.family_id.as_str()
+    } else {
+        rule_type.as_str()
+    };
+    let family = family(family_id)
+        .map_err(|e| failure("rule_type", format!("{} for rule {}", e, cp_rule.rule_id)))?;
+
+    let bridge_rule = control_plane_rule_metadata(&cp_rule, family.family_id, signer)
+        .and_then(|metadata| validate_rule(&metadata).map(|_| metadata))
+        .and_then(family.build)
+        .map_err(|e| {
+            failure(
+                "conversion",
+                format!("Failed to convert rule {}: {}", cp_rule.rule_id, e),
+            )
+        })?;
+
+    let rule_vector = match anchor_payload {
+        Some(payload) => convert_proto_rule_anchors(payload).map_err(|err| {
+            failure(
+                "anchors",
+                format!("Invalid anchors for {}: {}", cp_rule.rule_id, err),
+            )
+        })?,
+        None if family.requires_anchors => {
+            return Err(failure(
+                "anchors",
+                format!(
+                    "{} rule '{}' missing pre-encoded anchors",
+                    family.family_id, cp_rule.rule_id
+                ),
+            ))
+        }
+        None => RuleVector::default(),
+    };
+
+    Ok((bridge_rule, rule_vector, layer_label.to_string()))
+}
+
+/// Builds the persisted metadata for a control plane rule of `family_id`.
+fn control_plane_rule_metadata(
     cp_rule: &ControlPlaneRule,
-) -> Result<Arc<dyn RuleInstance>, String> {
+    family_id: &str,
+    signer: Option<&str>,
+) -> Result<RuleMetadata, String> {
     use crate::types::PolicyType;
 
-    let params_value = cp_params_to_value(&cp_rule.params);
+    let mut params_value = cp_params_to_value(&cp_rule.params);
+    // The family is persisted as the rule_type, also when only family_id was sent.
+    if let Value::Object(map) = &mut params_value {
+        map.insert(
+            "rule_type".to_string(),
+            Value::String(family_id.to_string()),
+        );
+    }
 
     let description = cp_rule.params.get("notes").and_then(|value| value.as_string());
 
-    let scope = RuleScope::for_agent(cp_rule.agent_id.clone());
+    if cp_rule.tags.keys().any(|key| key.is_empty()) {
+        return Err(format!(
+            "Rule {} has a selector tag with an empty key",
+            cp_rule.rule_id
+        ));
+    }
+
+    // A tagged rule without an agent covers every agent of the tenant its tags select.
+    // An untagged one keeps the older meaning of belonging to the "" agent.
+    let scope = if cp_rule.agent_id.is_empty() && !cp_rule.tags.is_empty() {
+        RuleScope::global()
+    } else {
+        RuleScope::for_agent(cp_rule.agent_id.clone())
+    }
+    .with_tenant(&cp_rule.tenant_id)
+    .with_tags(cp_rule.tags.clone());
 
     let layer = if cp_rule.layer.is_empty() {
         None
@@ -745,26 +1773,43 @@ fn convert_design_boundary_rule(
         }
     };
 
-    Ok(Arc::new(DesignBoundaryRule::new_with_policy(
-        cp_rule.rule_id.clone(),
-        cp_rule.priority as u32,
+    let not_before = (cp_rule.not_before_ms > 0).then_some(cp_rule.not_before_ms as u64);
+    let expires_at = (cp_rule.expires_at_ms > 0).then_some(cp_rule.expires_at_ms as u64);
+    if let (Some(start), Some(end)) = (not_before, expires_at) {
+        if end <= start {
+            return Err(format!(
+                "Rule {} expires_at ({}) must be after not_before ({})",
+                cp_rule.rule_id, end, start
+            ));
+        }
+    }
+
+    Ok(RuleMetadata {
+        rule_id: cp_rule.rule_id.clone(),
+        priority: cp_rule.priority as u32,
         scope,
         layer,
-        cp_rule.created_at_ms as u64,
-        cp_rule.enabled,
+        created_at_ms: cp_rule.created_at_ms as u64,
+        enabled: cp_rule.enabled,
         description,
-        params_value,
+        params: params_value,
         policy_type,
-        cp_rule.drift_threshold,
+        drift_threshold: cp_rule.drift_threshold,
         modification_spec,
-        cp_rule.slice_weights,
-    )) as Arc<dyn RuleInstance>)
+        slice_weights: cp_rule.slice_weights,
+        not_before,
+        expires_at,
+        signed_by: signer.map(|key_id| key_id.to_string()),
+    })
 }
 
 // ================================================================================================
 // SERVER STARTUP
 // ================================================================================================
 
+/// Largest gRPC message accepted or sent. Rule bundles carry every rule's anchors.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
 /// Start the gRPC server for rule installation and enforcement
 pub async fn start_grpc_server(
     bridge: Arc<Bridge>,
@@ -789,10 +1834,35 @@ pub async fn start_grpc_server(
         });
     }
 
+    {
+        let sweeper = Arc::clone(&scheduler);
+        tokio::spawn(async move {
+            println!("Spawning rule expiry background task");
+            sweeper.start_expiry_sweeper().await;
+        });
+    }
+
+    let replica_sync = Arc::new(ReplicaSync::new(
+        Arc::clone(&bridge),
+        SyncConfig::from_env(),
+    ));
+
+    {
+        let sync_runner = Arc::clone(&replica_sync);
+        tokio::spawn(async move {
+            println!("Spawning replica sync background task");
+            sync_runner.start().await;
+        });
+    }
+
     let service = DataPlaneService::new(bridge, management_plane_url);
 
     Server::builder()
-        .add_service(DataPlaneServer::new(service))
+        .add_service(
+            DataPlaneServer::new(service)
+                .max_decoding_message_size(MAX_MESSAGE_BYTES)
+                .max_encoding_message_size(MAX_MESSAGE_BYTES),
+        )
         .serve(addr)
         .await?;
 
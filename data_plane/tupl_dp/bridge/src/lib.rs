@@ -5,12 +5,15 @@
 // Core modules
 pub mod api_types;
 pub mod bridge;
+pub mod bundle;
 pub mod enforcement_engine;
 pub mod families;
 pub mod grpc_server;
 pub mod refresh;
 pub mod rule_converter;
+pub mod rule_index;
 pub mod rule_vector;
+pub mod signing;
 pub mod storage;
 pub mod telemetry;
 pub mod types;
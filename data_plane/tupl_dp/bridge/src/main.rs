@@ -24,6 +24,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("✓ Bridge initialized");
     println!("  - {} rules loaded", bridge_inst.rule_count());
     println!("  - Version: {}", bridge_inst.version());
+
+    // Rules that failed to load are quarantined and not enforced; optionally refuse to serve.
+    let quarantined = bridge_inst.quarantined_on_load();
+    if !quarantined.is_empty() {
+        println!(
+            "  - {} rules quarantined: {}",
+            quarantined.len(),
+            quarantined.join(", ")
+        );
+        let refuse = std::env::var("BRIDGE_REFUSE_QUARANTINED")
+            .is_ok_and(|v| matches!(v.as_str(), "1" | "true"));
+        if refuse {
+            let e = format!(
+                "{} active rules failed to load and were quarantined",
+                quarantined.len()
+            );
+            eprintln!("✗ Refusing to serve: {}", e);
+            return Err(e.into());
+        }
+    }
     println!();
 
     // Start gRPC server for rule installation and enforcement
@@ -3,9 +3,12 @@
 //! Provides two refresh mechanisms:
 //! 1. **Event-driven refresh** (RefreshService): Triggered on-demand via gRPC
 //! 2. **Scheduled refresh** (RefreshScheduler): Periodic background task (6-hour default)
+//! 3. **Replica sync** (ReplicaSync): Polls for writes by other processes sharing the database
 
 pub mod scheduler;
 pub mod service;
+pub mod sync;
 
 pub use scheduler::{RefreshScheduler, SchedulerConfig};
 pub use service::{RefreshService, RefreshStats};
+pub use sync::{ReplicaSync, SyncConfig};
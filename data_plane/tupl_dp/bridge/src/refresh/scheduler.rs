@@ -1,6 +1,6 @@
 //! Scheduled rule refresh service - background task for periodic rule reloading.
 //!
-//! Periodically rebuilds the in-memory HashMap from SQLite to ensure consistency.
+//! Periodically applies rule changes committed to SQLite to keep the in-memory HashMap consistent.
 
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -16,6 +16,8 @@ use log::{error, info};
 pub struct SchedulerConfig {
     /// Interval between refresh operations
     pub refresh_interval: Duration,
+    /// Interval between sweeps that archive expired rules
+    pub expiry_interval: Duration,
     /// Whether the scheduler is enabled
     pub enabled: bool,
 }
@@ -24,6 +26,7 @@ impl Default for SchedulerConfig {
     fn default() -> Self {
         Self {
             refresh_interval: Duration::from_secs(6 * 60 * 60),
+            expiry_interval: Duration::from_secs(60),
             enabled: true,
         }
     }
@@ -32,7 +35,7 @@ impl Default for SchedulerConfig {
 /// Scheduler for periodic rule refresh from SQLite.
 ///
 /// Runs as a background task spawned during server initialization.
-/// Periodically rebuilds the in-memory HashMap from SQLite.
+/// Periodically applies the delta between SQLite and the in-memory HashMap.
 pub struct RefreshScheduler {
     /// Reference to the bridge instance
     bridge: Arc<Bridge>,
@@ -79,14 +82,57 @@ impl RefreshScheduler {
         }
     }
 
+    /// Starts the expiry sweeper background task.
+    ///
+    /// Archives rules whose `expires_at` has passed every `expiry_interval`. Runs
+    /// indefinitely and should be spawned as a tokio task.
+    pub async fn start_expiry_sweeper(self: Arc<Self>) {
+        if !self.config.enabled {
+            info!("Scheduled expiry sweep is disabled, skipping");
+            return;
+        }
+
+        info!(
+            "Starting expiry sweeper with {}-second interval",
+            self.config.expiry_interval.as_secs()
+        );
+
+        let mut ticker = interval(self.config.expiry_interval);
+
+        loop {
+            ticker.tick().await;
+            self.do_expiry_sweep();
+        }
+    }
+
+    /// Archives every expired rule once.
+    fn do_expiry_sweep(&self) {
+        match self.bridge.archive_expired(now_ms()) {
+            Ok(expired) if expired.is_empty() => {}
+            Ok(expired) => {
+                info!(
+                    "Expiry sweep archived {} rules (version {}): {}",
+                    expired.len(),
+                    self.bridge.version(),
+                    expired.join(", ")
+                );
+            }
+            Err(e) => {
+                error!("Expiry sweep failed: {}", e);
+            }
+        }
+    }
+
     /// Executes one refresh cycle.
     async fn do_refresh(&self) {
-        match self.bridge.rebuild_from_db_public() {
-            Ok(()) => {
-                let num_rules = self.bridge.rule_count();
+        match self.bridge.refresh_delta() {
+            Ok(delta) => {
                 info!(
-                    "Scheduled refresh completed: {} rules loaded from SQLite",
-                    num_rules
+                    "Scheduled refresh completed: {} added, {} updated, {} removed ({} rules loaded)",
+                    delta.added,
+                    delta.updated,
+                    delta.removed,
+                    self.bridge.rule_count()
                 );
                 *self.last_refresh_at.write() = now_ms();
             }
@@ -106,6 +152,7 @@ mod tests {
         let config = SchedulerConfig::default();
         assert!(config.enabled);
         assert_eq!(config.refresh_interval.as_secs(), 6 * 60 * 60);
+        assert_eq!(config.expiry_interval.as_secs(), 60);
     }
 
     #[test]
@@ -119,6 +166,7 @@ mod tests {
     fn test_scheduler_config_custom() {
         let config = SchedulerConfig {
             refresh_interval: Duration::from_secs(3600),
+            expiry_interval: Duration::from_secs(30),
             enabled: false,
         };
         assert!(!config.enabled);
@@ -1,17 +1,26 @@
 //! Event-driven rule refresh service.
 //!
 //! Triggers rule reload from SQLite on demand via gRPC endpoint.
-//! Replaces in-memory HashMap with latest from persistent storage.
+//! By default only rules changed since the last sync are applied (delta refresh);
+//! a full rebuild swaps in a freshly loaded HashMap.
 
-use crate::bridge::Bridge;
+use crate::bridge::{Bridge, RefreshDelta};
 use crate::types::now_ms;
 use std::sync::Arc;
 
 /// Statistics from a refresh operation.
 #[derive(Debug, Clone)]
 pub struct RefreshStats {
-    /// Number of rules reloaded
+    /// Number of rules loaded after the refresh
     pub rules_refreshed: usize,
+    /// Rules that were not in memory before the refresh
+    pub rules_added: usize,
+    /// Rules whose in-memory entry was replaced
+    pub rules_updated: usize,
+    /// Rules dropped from memory
+    pub rules_removed: usize,
+    /// Whether every rule was reloaded instead of applying a delta
+    pub full_rebuild: bool,
     /// Duration of refresh operation in milliseconds
     pub duration_ms: u64,
     /// Timestamp of refresh
@@ -32,23 +41,35 @@ impl RefreshService {
     /// Trigger immediate refresh from SQLite.
     ///
     /// Called via gRPC RefreshRules() endpoint.
-    /// Rebuilds the in-memory HashMap from SQLite.
+    /// Applies only the rules changed in SQLite since the last sync.
     ///
     /// # Returns
     /// Stats about the refresh operation or error message.
     pub async fn refresh_from_storage(&self) -> Result<RefreshStats, String> {
         let start = now_ms();
+        let delta = self.bridge.refresh_delta()?;
+        Ok(self.stats(delta, false, start))
+    }
 
-        self.bridge.rebuild_from_db_public()?;
-        let num_rules = self.bridge.rule_count();
-
-        let duration_ms = now_ms() - start;
+    /// Trigger a full rebuild of the in-memory HashMap from SQLite.
+    ///
+    /// Picks up rows edited outside the bridge (which bypass the version history).
+    pub async fn full_refresh_from_storage(&self) -> Result<RefreshStats, String> {
+        let start = now_ms();
+        let delta = self.bridge.rebuild_from_db_public()?;
+        Ok(self.stats(delta, true, start))
+    }
 
-        Ok(RefreshStats {
-            rules_refreshed: num_rules,
-            duration_ms,
+    fn stats(&self, delta: RefreshDelta, full_rebuild: bool, start: u64) -> RefreshStats {
+        RefreshStats {
+            rules_refreshed: self.bridge.rule_count(),
+            rules_added: delta.added,
+            rules_updated: delta.updated,
+            rules_removed: delta.removed,
+            full_rebuild,
+            duration_ms: now_ms() - start,
             timestamp: now_ms(),
-        })
+        }
     }
 }
 
@@ -60,11 +81,16 @@ mod tests {
     fn test_refresh_stats_creation() {
         let stats = RefreshStats {
             rules_refreshed: 100,
+            rules_added: 3,
+            rules_updated: 2,
+            rules_removed: 1,
+            full_rebuild: false,
             duration_ms: 50,
             timestamp: 1234567890,
         };
 
         assert_eq!(stats.rules_refreshed, 100);
+        assert_eq!(stats.rules_added, 3);
         assert_eq!(stats.duration_ms, 50);
     }
 }
@@ -0,0 +1,146 @@
+//! Replica sync service - background task that picks up writes from other processes.
+//!
+//! Several bridge processes may share one cold storage database. Each replica polls
+//! SQLite for foreign commits and applies them, bounding how stale its rules can get.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+
+use crate::bridge::Bridge;
+use crate::types::now_ms;
+use log::{error, info, warn};
+
+/// Configuration for replica change detection.
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// Longest a replica may go without checking storage for foreign writes
+    pub max_staleness: Duration,
+    /// Whether change detection is enabled
+    pub enabled: bool,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            max_staleness: Duration::from_secs(5),
+            enabled: true,
+        }
+    }
+}
+
+impl SyncConfig {
+    /// Reads `BRIDGE_SYNC_MAX_STALENESS_MS` (0 disables change detection).
+    pub fn from_env() -> Self {
+        match std::env::var("BRIDGE_SYNC_MAX_STALENESS_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            Some(0) => Self {
+                enabled: false,
+                ..Self::default()
+            },
+            Some(ms) => Self {
+                max_staleness: Duration::from_millis(ms),
+                enabled: true,
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Polling interval: half the staleness budget so one missed tick stays within it.
+    pub fn poll_interval(&self) -> Duration {
+        (self.max_staleness / 2).max(Duration::from_millis(50))
+    }
+}
+
+/// Background poller that keeps a replica in sync with shared cold storage.
+pub struct ReplicaSync {
+    /// Reference to the bridge instance
+    bridge: Arc<Bridge>,
+    /// Sync configuration
+    config: SyncConfig,
+}
+
+impl ReplicaSync {
+    /// Creates a new replica sync service.
+    pub fn new(bridge: Arc<Bridge>, config: SyncConfig) -> Self {
+        Self { bridge, config }
+    }
+
+    /// Returns how long ago the bridge was last confirmed up to date, in milliseconds.
+    pub fn staleness_ms(&self) -> u64 {
+        now_ms().saturating_sub(self.bridge.last_synced_at())
+    }
+
+    /// Starts the sync background task.
+    ///
+    /// This method runs indefinitely and should be spawned as a tokio task.
+    pub async fn start(self: Arc<Self>) {
+        if !self.config.enabled {
+            info!("Replica sync is disabled, skipping");
+            return;
+        }
+
+        info!(
+            "Starting replica sync with {}ms max staleness",
+            self.config.max_staleness.as_millis()
+        );
+
+        let mut ticker = interval(self.config.poll_interval());
+
+        loop {
+            ticker.tick().await;
+            self.do_sync();
+        }
+    }
+
+    /// Executes one change check.
+    fn do_sync(&self) {
+        match self.bridge.sync_if_changed() {
+            Ok(Some(delta)) if delta.added + delta.updated + delta.removed > 0 => {
+                info!(
+                    "Replica sync applied foreign writes: {} added, {} updated, {} removed (version {})",
+                    delta.added,
+                    delta.updated,
+                    delta.removed,
+                    self.bridge.version()
+                );
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!("Replica sync failed: {}", e);
+                let staleness_ms = self.staleness_ms();
+                if staleness_ms > self.config.max_staleness.as_millis() as u64 {
+                    warn!(
+                        "Replica is {}ms stale (max {}ms)",
+                        staleness_ms,
+                        self.config.max_staleness.as_millis()
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_config_defaults() {
+        let config = SyncConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.max_staleness, Duration::from_secs(5));
+        assert_eq!(config.poll_interval(), Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn test_poll_interval_has_floor() {
+        let config = SyncConfig {
+            max_staleness: Duration::from_millis(10),
+            enabled: true,
+        };
+        assert_eq!(config.poll_interval(), Duration::from_millis(50));
+    }
+}
@@ -9,6 +9,10 @@ pub struct ControlPlaneRule {
     pub family_id: String,
     pub layer: String,
     pub agent_id: String,
+    /// Owning tenant; empty = the default tenant
+    pub tenant_id: String,
+    /// Selector tags matched against request/agent labels; empty = no selector
+    pub tags: HashMap<String, String>,
     pub priority: i32,
     pub enabled: bool,
     pub created_at_ms: i64,
@@ -21,6 +25,10 @@ pub struct ControlPlaneRule {
     pub modification_spec: String,
     /// Per-slice weights [action, resource, data, risk]; defaults to [0.25; 4]
     pub slice_weights: [f32; 4],
+    /// Validity window start in epoch ms; 0 = valid from creation
+    pub not_before_ms: i64,
+    /// Validity window end in epoch ms (exclusive); 0 = never expires
+    pub expires_at_ms: i64,
 }
 
 /// Parameter value from the control plane payload.
@@ -0,0 +1,352 @@
+//! # Rule Index
+//!
+//! Secondary index over an in-memory rule set, keyed by (tenant, agent, layer).
+//!
+//! Each bucket holds the enabled rules for that key pre-sorted by priority, so an
+//! enforcement lookup only touches the rules that can apply to the request instead of
+//! cloning and sorting the whole store. The index is maintained incrementally by
+//! `IndexedRules` whenever the rule map changes.
+
+use crate::rule_vector::RuleVector;
+use crate::types::RuleInstance;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A rule instance paired with its pre-encoded anchors.
+pub type RuleEntry = (Arc<dyn RuleInstance>, RuleVector);
+
+/// Rule set keyed by rule_id.
+pub type RuleMap = HashMap<String, RuleEntry>;
+
+/// Rule instances keyed by rule_id (anchors held elsewhere).
+pub type RuleInstances = HashMap<String, Arc<dyn RuleInstance>>;
+
+/// Bucket key matching any tenant or any agent.
+const ANY: &str = "*";
+
+/// Agent key for global rules (they apply to every agent).
+const GLOBAL_AGENT: &str = "";
+
+/// Layer key for rules without a layer (they apply to every layer).
+const ALL_LAYERS: &str = "";
+
+type IndexKey = (String, String, String);
+
+/// Enabled rules bucketed by (tenant, agent, layer), highest priority first.
+#[derive(Debug, Default)]
+pub struct RuleIndex {
+    buckets: HashMap<IndexKey, Vec<Arc<dyn RuleInstance>>>,
+}
+
+impl RuleIndex {
+    /// Builds an index over every rule in `rules`.
+    pub fn build<'a>(rules: impl IntoIterator<Item = &'a Arc<dyn RuleInstance>>) -> Self {
+        let mut index = RuleIndex::default();
+        for rule in rules {
+            for key in Self::keys(rule.as_ref()) {
+                index.buckets.entry(key).or_default().push(Arc::clone(rule));
+            }
+        }
+        for bucket in index.buckets.values_mut() {
+            bucket.sort_by(by_priority);
+        }
+        index
+    }
+
+    /// Adds a rule to every bucket it belongs to, keeping priority order.
+    pub fn insert(&mut self, rule: &Arc<dyn RuleInstance>) {
+        for key in Self::keys(rule.as_ref()) {
+            let bucket = self.buckets.entry(key).or_default();
+            let at =
+                bucket.partition_point(|existing| by_priority(existing, rule) == Ordering::Less);
+            bucket.insert(at, Arc::clone(rule));
+        }
+    }
+
+    /// Removes a rule from every bucket it belongs to.
+    pub fn remove(&mut self, rule: &dyn RuleInstance) {
+        for key in Self::keys(rule) {
+            if let Some(bucket) = self.buckets.get_mut(&key) {
+                bucket.retain(|existing| existing.rule_id() != rule.rule_id());
+                if bucket.is_empty() {
+                    self.buckets.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Returns the enabled rules applicable to a request, highest priority first.
+    ///
+    /// `tenant`/`agent` of None match rules for any tenant/agent; a specific agent also
+    /// matches global rules, but only those of the requested tenant, and an empty agent
+    /// matches only global rules. Rules without a layer apply to every layer, and an
+    /// empty `layer` matches only those.
+    pub fn lookup(
+        &self,
+        tenant: Option<&str>,
+        agent: Option<&str>,
+        layer: &str,
+    ) -> Vec<Arc<dyn RuleInstance>> {
+        let layers: &[&str] = if layer.is_empty() {
+            &[ALL_LAYERS]
+        } else {
+            &[layer, ALL_LAYERS]
+        };
+
+        let mut keys = Vec::with_capacity(4);
+        for layer in layers {
+            keys.push((tenant.unwrap_or(ANY), agent.unwrap_or(ANY), *layer));
+            // Global rules apply to every agent of their own tenant.
+            if tenant.is_some() || agent.is_some() {
+                keys.push((tenant.unwrap_or(ANY), GLOBAL_AGENT, *layer));
+            }
+        }
+
+        let mut matched = Vec::new();
+        for (tenant, agent, layer) in keys {
+            let key = (tenant.to_string(), agent.to_string(), layer.to_string());
+            if let Some(bucket) = self.buckets.get(&key) {
+                matched.extend(bucket.iter().cloned());
+            }
+        }
+
+        matched.sort_by(by_priority);
+        matched.dedup_by(|a, b| a.rule_id() == b.rule_id());
+        matched
+    }
+
+    /// Index keys for a rule; disabled rules are not indexed.
+    fn keys(rule: &dyn RuleInstance) -> Vec<IndexKey> {
+        if !rule.is_enabled() {
+            return Vec::new();
+        }
+
+        let scope = rule.scope();
+        let layer = rule.layer().unwrap_or(ALL_LAYERS).to_string();
+        let tenants = [scope.tenant().to_string(), ANY.to_string()];
+        let agents: Vec<String> = if scope.is_global || scope.agent_ids.is_empty() {
+            vec![GLOBAL_AGENT.to_string(), ANY.to_string()]
+        } else {
+            scope
+                .agent_ids
+                .iter()
+                .cloned()
+                .chain(std::iter::once(ANY.to_string()))
+                .collect()
+        };
+
+        let mut keys = Vec::with_capacity(tenants.len() * agents.len());
+        for tenant in &tenants {
+            for agent in &agents {
+                keys.push((tenant.clone(), agent.clone(), layer.clone()));
+            }
+        }
+        keys
+    }
+}
+
+/// Priority descending, then rule_id for a stable order between equal priorities.
+fn by_priority(a: &Arc<dyn RuleInstance>, b: &Arc<dyn RuleInstance>) -> Ordering {
+    b.priority()
+        .cmp(&a.priority())
+        .then_with(|| a.rule_id().cmp(b.rule_id()))
+}
+
+/// Rule instances together with their index; every mutation updates both.
+///
+/// Dereferences to the underlying `RuleInstances` for reads.
+#[derive(Debug, Default)]
+pub struct IndexedRules {
+    rules: RuleInstances,
+    index: RuleIndex,
+}
+
+impl IndexedRules {
+    /// Inserts or replaces a rule. Returns the previous instance, if any.
+    pub fn insert(
+        &mut self,
+        rule_id: String,
+        rule: Arc<dyn RuleInstance>,
+    ) -> Option<Arc<dyn RuleInstance>> {
+        if let Some(old) = self.rules.get(&rule_id) {
+            self.index.remove(old.as_ref());
+        }
+        self.index.insert(&rule);
+        self.rules.insert(rule_id, rule)
+    }
+
+    /// Removes a rule. Returns the removed instance, if any.
+    pub fn remove(&mut self, rule_id: &str) -> Option<Arc<dyn RuleInstance>> {
+        let removed = self.rules.remove(rule_id);
+        if let Some(old) = &removed {
+            self.index.remove(old.as_ref());
+        }
+        removed
+    }
+
+    /// Replaces the whole rule set and rebuilds the index.
+    pub fn replace(&mut self, rules: RuleInstances) {
+        self.index = RuleIndex::build(rules.values());
+        self.rules = rules;
+    }
+
+    /// Removes every rule.
+    pub fn clear(&mut self) {
+        self.rules.clear();
+        self.index = RuleIndex::default();
+    }
+
+    /// Returns the index over the current rules.
+    pub fn index(&self) -> &RuleIndex {
+        &self.index
+    }
+}
+
+impl Deref for IndexedRules {
+    type Target = RuleInstances;
+
+    fn deref(&self) -> &RuleInstances {
+        &self.rules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::families::DesignBoundaryRule;
+    use crate::types::{RuleScope, DEFAULT_TENANT};
+    use serde_json::json;
+
+    fn rule(
+        id: &str,
+        priority: u32,
+        scope: RuleScope,
+        layer: Option<&str>,
+    ) -> Arc<dyn RuleInstance> {
+        Arc::new(DesignBoundaryRule::new(
+            id.to_string(),
+            priority,
+            scope,
+            layer.map(str::to_string),
+            0,
+            true,
+            None,
+            json!({}),
+        ))
+    }
+
+    fn ids(rules: &[Arc<dyn RuleInstance>]) -> Vec<&str> {
+        rules.iter().map(|r| r.rule_id()).collect()
+    }
+
+    fn sample() -> IndexedRules {
+        let mut rules = IndexedRules::default();
+        let agent = |id: &str| RuleScope::for_agent(id.to_string());
+        for (id, priority, scope, layer) in [
+            ("a-l4", 10, agent("agent-a"), Some("L4")),
+            ("a-l1", 50, agent("agent-a"), Some("L1")),
+            ("b-l4", 30, agent("agent-b"), Some("L4")),
+            ("global-l4", 20, RuleScope::global(), Some("L4")),
+            ("a-any", 5, agent("agent-a"), None),
+        ] {
+            rules.insert(id.to_string(), rule(id, priority, scope, layer));
+        }
+        rules
+    }
+
+    #[test]
+    fn test_lookup_any_agent_by_layer() {
+        let rules = sample();
+        let found = rules.index().lookup(None, None, "L4");
+        assert_eq!(ids(&found), vec!["b-l4", "global-l4", "a-l4", "a-any"]);
+
+        let layerless = rules.index().lookup(None, None, "");
+        assert_eq!(ids(&layerless), vec!["a-any"]);
+    }
+
+    #[test]
+    fn test_lookup_by_agent_includes_global_rules() {
+        let rules = sample();
+        let found = rules.index().lookup(None, Some("agent-a"), "L4");
+        assert_eq!(ids(&found), vec!["global-l4", "a-l4", "a-any"]);
+
+        let found = rules
+            .index()
+            .lookup(Some(DEFAULT_TENANT), Some("agent-b"), "L4");
+        assert_eq!(ids(&found), vec!["b-l4", "global-l4"]);
+
+        let found = rules.index().lookup(Some(DEFAULT_TENANT), Some(""), "L4");
+        assert_eq!(ids(&found), vec!["global-l4"]);
+    }
+
+    #[test]
+    fn test_lookup_is_confined_to_tenant() {
+        let mut rules = sample();
+        let scope = |tenant: &str| RuleScope::for_agent("agent-a".to_string()).with_tenant(tenant);
+        rules.insert(
+            "acme-l4".to_string(),
+            rule("acme-l4", 40, scope("acme"), Some("L4")),
+        );
+        rules.insert(
+            "acme-global".to_string(),
+            rule(
+                "acme-global",
+                60,
+                RuleScope::global().with_tenant("acme"),
+                Some("L4"),
+            ),
+        );
+
+        let found = rules.index().lookup(Some("acme"), Some("agent-a"), "L4");
+        assert_eq!(ids(&found), vec!["acme-global", "acme-l4"]);
+
+        let found = rules
+            .index()
+            .lookup(Some(DEFAULT_TENANT), Some("agent-a"), "L4");
+        assert_eq!(ids(&found), vec!["global-l4", "a-l4", "a-any"]);
+
+        let found = rules.index().lookup(Some("acme"), None, "L4");
+        assert_eq!(ids(&found), vec!["acme-global", "acme-l4"]);
+        assert!(rules
+            .index()
+            .lookup(Some("other"), Some("agent-a"), "L4")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_mutations_keep_index_in_sync() {
+        let mut rules = sample();
+        rules.insert(
+            "a-l4".to_string(),
+            rule(
+                "a-l4",
+                99,
+                RuleScope::for_agent("agent-a".to_string()),
+                Some("L4"),
+            ),
+        );
+        rules.remove("b-l4");
+
+        let found = rules.index().lookup(None, None, "L4");
+        assert_eq!(ids(&found), vec!["a-l4", "global-l4", "a-any"]);
+
+        rules.clear();
+        assert!(rules.index().lookup(None, None, "L4").is_empty());
+    }
+
+    #[test]
+    fn test_replace_matches_incremental_build() {
+        let incremental = sample();
+        let mut rebuilt = IndexedRules::default();
+        rebuilt.replace(incremental.rules.clone());
+
+        for (agent, layer) in [(None, "L4"), (Some("agent-a"), "L1"), (None, "")] {
+            assert_eq!(
+                ids(&incremental.index().lookup(None, agent, layer)),
+                ids(&rebuilt.index().lookup(None, agent, layer))
+            );
+        }
+    }
+}
@@ -60,3 +60,394 @@ pub fn convert_anchor_block(
 
     Ok((block, count))
 }
+
+type AnchorBlock = [[f32; SLOT_WIDTH]; MAX_ANCHORS_PER_SLOT];
+
+/// Bytes in one encoded slot: the anchors as f32 LE followed by the count as u64 LE.
+const ENCODED_BLOCK_LEN: usize = MAX_ANCHORS_PER_SLOT * SLOT_WIDTH * 4 + 8;
+
+impl RuleVector {
+    /// Length of the raw little-endian encoding (four slots).
+    pub const ENCODED_LEN: usize = 4 * ENCODED_BLOCK_LEN;
+
+    /// Encodes the anchors as raw little-endian bytes.
+    ///
+    /// This fixed-size layout is used for warm storage records and by rows persisted
+    /// before the compact format (`encode`); `decode` reads both.
+    ///
+    /// Layout: action_anchors (16×32 f32s) + action_count (u64 LE) +
+    ///         resource_anchors (16×32 f32s) + resource_count (u64 LE) +
+    ///         data_anchors (16×32 f32s) + data_count (u64 LE) +
+    ///         risk_anchors (16×32 f32s) + risk_count (u64 LE)
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(Self::ENCODED_LEN);
+
+        let write_block = |out: &mut Vec<u8>, block: &AnchorBlock, count: usize| {
+            for row in block.iter() {
+                for &f in row.iter() {
+                    out.extend_from_slice(&f.to_le_bytes());
+                }
+            }
+            out.extend_from_slice(&(count as u64).to_le_bytes());
+        };
+
+        write_block(&mut out, &self.action_anchors, self.action_count);
+        write_block(&mut out, &self.resource_anchors, self.resource_count);
+        write_block(&mut out, &self.data_anchors, self.data_count);
+        write_block(&mut out, &self.risk_anchors, self.risk_count);
+
+        out
+    }
+
+    /// Decodes anchors written by `to_le_bytes`.
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(format!(
+                "Expected {} bytes for RuleVector, got {}",
+                Self::ENCODED_LEN,
+                bytes.len()
+            ));
+        }
+
+        let read_block = |data: &[u8]| -> Result<(AnchorBlock, usize), String> {
+            let mut block = [[0f32; SLOT_WIDTH]; MAX_ANCHORS_PER_SLOT];
+            let mut offset = 0;
+            for row in block.iter_mut() {
+                for f in row.iter_mut() {
+                    let chunk: [u8; 4] = data[offset..offset + 4]
+                        .try_into()
+                        .map_err(|_| "Slice conversion failed".to_string())?;
+                    *f = f32::from_le_bytes(chunk);
+                    offset += 4;
+                }
+            }
+            let count_chunk: [u8; 8] = data[offset..offset + 8]
+                .try_into()
+                .map_err(|_| "Count slice conversion failed".to_string())?;
+            let count = u64::from_le_bytes(count_chunk) as usize;
+            if count > MAX_ANCHORS_PER_SLOT {
+                return Err(format!(
+                    "Anchor count {} exceeds max {}",
+                    count, MAX_ANCHORS_PER_SLOT
+                ));
+            }
+            Ok((block, count))
+        };
+
+        let mut blocks = bytes.chunks_exact(ENCODED_BLOCK_LEN);
+        let mut next = || read_block(blocks.next().expect("length checked above"));
+        let (action_anchors, action_count) = next()?;
+        let (resource_anchors, resource_count) = next()?;
+        let (data_anchors, data_count) = next()?;
+        let (risk_anchors, risk_count) = next()?;
+
+        Ok(RuleVector {
+            action_anchors,
+            action_count,
+            resource_anchors,
+            resource_count,
+            data_anchors,
+            data_count,
+            risk_anchors,
+            risk_count,
+        })
+    }
+}
+
+/// Precision of persisted anchor values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnchorPrecision {
+    /// Lossless
+    #[default]
+    F32,
+    /// Half the size; values are rounded to the nearest f16
+    F16,
+}
+
+impl AnchorPrecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnchorPrecision::F32 => "f32",
+            AnchorPrecision::F16 => "f16",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "f32" => Ok(AnchorPrecision::F32),
+            "f16" => Ok(AnchorPrecision::F16),
+            other => Err(format!("Unknown anchor precision '{}'", other)),
+        }
+    }
+
+    /// Reads `BRIDGE_ANCHOR_PRECISION` ("f32" or "f16"), defaulting to f32.
+    pub fn from_env() -> Self {
+        std::env::var("BRIDGE_ANCHOR_PRECISION")
+            .ok()
+            .and_then(|v| Self::parse(&v).ok())
+            .unwrap_or_default()
+    }
+
+    fn tag(self) -> u8 {
+        match self {
+            AnchorPrecision::F32 => 0,
+            AnchorPrecision::F16 => 1,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, String> {
+        match tag {
+            0 => Ok(AnchorPrecision::F32),
+            1 => Ok(AnchorPrecision::F16),
+            other => Err(format!("Unknown anchor precision tag {}", other)),
+        }
+    }
+
+    fn value_len(self) -> usize {
+        match self {
+            AnchorPrecision::F32 => 4,
+            AnchorPrecision::F16 => 2,
+        }
+    }
+}
+
+/// Prefix of the compact anchor encoding.
+const COMPACT_MAGIC: &[u8; 3] = b"TRV";
+
+/// Compact anchor format written by this binary.
+pub const ANCHOR_FORMAT_VERSION: u8 = 1;
+
+/// Magic, format version, precision tag and slot width (u16 LE).
+const COMPACT_HEADER_LEN: usize = 7;
+
+// A full compact encoding is always shorter than the legacy layout, so the two are
+// told apart by length alone.
+const _: () = assert!(
+    COMPACT_HEADER_LEN + 4 * (1 + MAX_ANCHORS_PER_SLOT * SLOT_WIDTH * 4) < RuleVector::ENCODED_LEN
+);
+
+impl RuleVector {
+    /// Encodes only the populated anchors in the versioned compact format.
+    ///
+    /// Layout: "TRV" + format version (u8) + precision tag (u8) + slot width (u16 LE),
+    /// then for each slot (action, resource, data, risk) its count (u8) followed by that
+    /// many anchors of slot-width values (f32 or f16, LE).
+    pub fn encode(&self, precision: AnchorPrecision) -> Vec<u8> {
+        let slots = self.slots();
+        let populated: usize = slots.iter().map(|(_, count)| count).sum();
+        let mut out = Vec::with_capacity(
+            COMPACT_HEADER_LEN + slots.len() + populated * SLOT_WIDTH * precision.value_len(),
+        );
+
+        out.extend_from_slice(COMPACT_MAGIC);
+        out.push(ANCHOR_FORMAT_VERSION);
+        out.push(precision.tag());
+        out.extend_from_slice(&(SLOT_WIDTH as u16).to_le_bytes());
+
+        for (block, count) in slots {
+            let count = count.min(MAX_ANCHORS_PER_SLOT);
+            out.push(count as u8);
+            for row in &block[..count] {
+                for &f in row {
+                    match precision {
+                        AnchorPrecision::F32 => out.extend_from_slice(&f.to_le_bytes()),
+                        AnchorPrecision::F16 => {
+                            out.extend_from_slice(&half::f16::from_f32(f).to_le_bytes())
+                        }
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Decodes anchors in the compact format or the legacy fixed layout (`to_le_bytes`).
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if Self::is_legacy_encoding(bytes) {
+            return Self::from_le_bytes(bytes);
+        }
+
+        let (precision, slots) = compact_slots(bytes)?;
+        let read_block = |(count, values): (usize, &[u8])| {
+            let mut block = [[0f32; SLOT_WIDTH]; MAX_ANCHORS_PER_SLOT];
+            let mut values = values.chunks_exact(precision.value_len());
+            for row in block[..count].iter_mut() {
+                for (f, chunk) in row.iter_mut().zip(&mut values) {
+                    *f = match precision {
+                        AnchorPrecision::F32 => {
+                            f32::from_le_bytes(chunk.try_into().expect("4-byte value"))
+                        }
+                        AnchorPrecision::F16 => {
+                            half::f16::from_le_bytes(chunk.try_into().expect("2-byte value"))
+                                .to_f32()
+                        }
+                    };
+                }
+            }
+            (block, count)
+        };
+
+        let [action, resource, data, risk] = slots;
+        let (action_anchors, action_count) = read_block(action);
+        let (resource_anchors, resource_count) = read_block(resource);
+        let (data_anchors, data_count) = read_block(data);
+        let (risk_anchors, risk_count) = read_block(risk);
+
+        Ok(RuleVector {
+            action_anchors,
+            action_count,
+            resource_anchors,
+            resource_count,
+            data_anchors,
+            data_count,
+            risk_anchors,
+            risk_count,
+        })
+    }
+
+    /// Checks that `bytes` would decode, without materializing the anchors.
+    pub fn check_encoding(bytes: &[u8]) -> Result<(), String> {
+        if !Self::is_legacy_encoding(bytes) {
+            return compact_slots(bytes).map(|_| ());
+        }
+        for block in bytes.chunks_exact(ENCODED_BLOCK_LEN) {
+            let count_chunk: [u8; 8] = block[ENCODED_BLOCK_LEN - 8..]
+                .try_into()
+                .expect("8-byte count");
+            let count = u64::from_le_bytes(count_chunk) as usize;
+            if count > MAX_ANCHORS_PER_SLOT {
+                return Err(format!(
+                    "Anchor count {} exceeds max {}",
+                    count, MAX_ANCHORS_PER_SLOT
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true when `bytes` is the legacy fixed layout written by `to_le_bytes`.
+    pub fn is_legacy_encoding(bytes: &[u8]) -> bool {
+        bytes.len() == Self::ENCODED_LEN
+    }
+
+    fn slots(&self) -> [(&AnchorBlock, usize); 4] {
+        [
+            (&self.action_anchors, self.action_count),
+            (&self.resource_anchors, self.resource_count),
+            (&self.data_anchors, self.data_count),
+            (&self.risk_anchors, self.risk_count),
+        ]
+    }
+}
+
+/// Anchor count and raw values of one slot in the compact encoding.
+type CompactSlot<'a> = (usize, &'a [u8]);
+
+/// Validates a compact encoding and splits it into its four slots.
+fn compact_slots(bytes: &[u8]) -> Result<(AnchorPrecision, [CompactSlot<'_>; 4]), String> {
+    if bytes.len() < COMPACT_HEADER_LEN || &bytes[..3] != COMPACT_MAGIC {
+        return Err(format!(
+            "Unrecognized anchor encoding ({} bytes, no header)",
+            bytes.len()
+        ));
+    }
+    if bytes[3] != ANCHOR_FORMAT_VERSION {
+        return Err(format!(
+            "Anchor format version {} is not supported (expected {})",
+            bytes[3], ANCHOR_FORMAT_VERSION
+        ));
+    }
+    let precision = AnchorPrecision::from_tag(bytes[4])?;
+    let slot_width = u16::from_le_bytes([bytes[5], bytes[6]]) as usize;
+    if slot_width != SLOT_WIDTH {
+        return Err(format!(
+            "Anchors were written with slot width {}, expected {}",
+            slot_width, SLOT_WIDTH
+        ));
+    }
+
+    let mut data = &bytes[COMPACT_HEADER_LEN..];
+    let mut slots = [(0, &bytes[..0]); 4];
+    for slot in slots.iter_mut() {
+        let (&count, rest) = data
+            .split_first()
+            .ok_or_else(|| "Anchor encoding is truncated".to_string())?;
+        let count = count as usize;
+        if count > MAX_ANCHORS_PER_SLOT {
+            return Err(format!(
+                "Anchor count {} exceeds max {}",
+                count, MAX_ANCHORS_PER_SLOT
+            ));
+        }
+        let len = count * SLOT_WIDTH * precision.value_len();
+        if rest.len() < len {
+            return Err("Anchor encoding is truncated".to_string());
+        }
+        *slot = (count, &rest[..len]);
+        data = &rest[len..];
+    }
+    if !data.is_empty() {
+        return Err(format!("Anchor encoding has {} trailing bytes", data.len()));
+    }
+
+    Ok((precision, slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> RuleVector {
+        let mut vector = RuleVector {
+            action_count: 1,
+            risk_count: 2,
+            ..Default::default()
+        };
+        vector.action_anchors[0][0] = 0.5;
+        vector.risk_anchors[1][SLOT_WIDTH - 1] = -0.1;
+        vector
+    }
+
+    #[test]
+    fn test_compact_encoding_stores_only_populated_anchors() {
+        let vector = sample();
+
+        let full = vector.encode(AnchorPrecision::F32);
+        assert_eq!(full.len(), COMPACT_HEADER_LEN + 4 + 3 * SLOT_WIDTH * 4);
+        let decoded = RuleVector::decode(&full).unwrap();
+        assert_eq!(decoded.to_le_bytes(), vector.to_le_bytes());
+
+        let half = vector.encode(AnchorPrecision::F16);
+        assert_eq!(half.len(), COMPACT_HEADER_LEN + 4 + 3 * SLOT_WIDTH * 2);
+        let decoded = RuleVector::decode(&half).unwrap();
+        assert_eq!(decoded.risk_count, 2);
+        assert_eq!(decoded.action_anchors[0][0], 0.5);
+        assert!((decoded.risk_anchors[1][SLOT_WIDTH - 1] + 0.1).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_decode_reads_legacy_and_rejects_foreign_layouts() {
+        let vector = sample();
+        let legacy = RuleVector::decode(&vector.to_le_bytes()).unwrap();
+        assert_eq!(legacy.to_le_bytes(), vector.to_le_bytes());
+
+        let encoded = vector.encode(AnchorPrecision::F32);
+        let mut wider = encoded.clone();
+        wider[5..7].copy_from_slice(&((SLOT_WIDTH + 1) as u16).to_le_bytes());
+        let err = RuleVector::decode(&wider).unwrap_err();
+        assert!(err.contains("slot width"), "{}", err);
+
+        let mut newer = encoded.clone();
+        newer[3] = ANCHOR_FORMAT_VERSION + 1;
+        assert!(RuleVector::decode(&newer).is_err());
+        assert!(RuleVector::decode(&encoded[..encoded.len() - 1]).is_err());
+        assert!(RuleVector::decode(&[encoded.as_slice(), &[0]].concat()).is_err());
+        assert!(RuleVector::decode(&[0u8; 10]).is_err());
+
+        assert!(RuleVector::check_encoding(&encoded).is_ok());
+        assert!(RuleVector::check_encoding(&vector.to_le_bytes()).is_ok());
+        assert!(RuleVector::check_encoding(&wider).is_err());
+        assert!(RuleVector::check_encoding(&encoded[..encoded.len() - 1]).is_err());
+    }
+}
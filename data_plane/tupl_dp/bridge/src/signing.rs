@@ -0,0 +1,252 @@
+//! # Signed Installs
+//!
+//! Ed25519 verification of rule batches and bundles against a set of trusted keys.
+//!
+//! Signatures are detached: the signer signs the exact payload bytes (an encoded
+//! `SignedRuleBatch` or `SignedCommand`) and sends the signature next to them with the
+//! id of the key that made it. When trusted keys are configured, the gRPC service
+//! rejects any request that changes rules or their enforcement if it is unsigned or
+//! fails verification before the `Bridge` is touched, and records the key id on every
+//! accepted rule.
+//!
+//! Every payload carries a sequence number and an expiry, so a captured request cannot
+//! be sent again to undo later changes. The last sequence accepted from each key is kept
+//! in the rule store (see `SequenceClaim`), so it holds across restarts and replicas.
+//!
+//! Trusted keys are read from the file named by `BRIDGE_TRUSTED_KEYS_FILE`, one key per
+//! line as `<key_id> <hex-encoded 32-byte public key>`. Blank lines and lines starting
+//! with `#` are ignored.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Longest time ahead a signed payload may expire.
+pub const MAX_SIGNATURE_LIFETIME_MS: u64 = 10 * 60 * 1000;
+
+/// Public keys whose signatures are accepted, by key id.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedKeys {
+    keys: HashMap<String, VerifyingKey>,
+}
+
+impl TrustedKeys {
+    /// Creates an empty key set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts `public_key` (32 raw bytes) under `key_id`.
+    pub fn insert(&mut self, key_id: &str, public_key: &[u8]) -> Result<(), String> {
+        if key_id.is_empty() || key_id.chars().any(char::is_whitespace) {
+            return Err(format!("Invalid key id '{}'", key_id));
+        }
+        let bytes: [u8; 32] = public_key.try_into().map_err(|_| {
+            format!(
+                "Public key {} is {} bytes, expected 32",
+                key_id,
+                public_key.len()
+            )
+        })?;
+        let key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|e| format!("Invalid public key {}: {}", key_id, e))?;
+        self.keys.insert(key_id.to_string(), key);
+        Ok(())
+    }
+
+    /// Parses a trusted key file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut keys = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key_id, public_key) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("Line {}: expected '<key_id> <public key>'", index + 1))?;
+            let public_key =
+                decode_hex(public_key.trim()).map_err(|e| format!("Line {}: {}", index + 1, e))?;
+            keys.insert(key_id, &public_key)
+                .map_err(|e| format!("Line {}: {}", index + 1, e))?;
+        }
+        Ok(keys)
+    }
+
+    /// Reads a trusted key file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read trusted keys {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("Invalid trusted keys {}: {}", path.display(), e))
+    }
+
+    /// Loads the keys named by `BRIDGE_TRUSTED_KEYS_FILE` (None when unset).
+    ///
+    /// A configured file that is unreadable or empty is an error, so a typo cannot
+    /// silently turn verification off.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Ok(path) = std::env::var("BRIDGE_TRUSTED_KEYS_FILE") else {
+            return Ok(None);
+        };
+        let keys = Self::load(Path::new(&path))?;
+        if keys.is_empty() {
+            return Err(format!("Trusted keys file {} lists no keys", path));
+        }
+        Ok(Some(keys))
+    }
+
+    /// Checks that `signature` over `payload` was made by the trusted key `key_id`.
+    pub fn verify(&self, key_id: &str, payload: &[u8], signature: &[u8]) -> Result<(), String> {
+        let key = self
+            .keys
+            .get(key_id)
+            .ok_or_else(|| format!("Unknown signing key '{}'", key_id))?;
+        let signature = Signature::from_slice(signature)
+            .map_err(|_| format!("Malformed signature for key '{}'", key_id))?;
+        key.verify(payload, &signature)
+            .map_err(|_| format!("Signature does not match key '{}'", key_id))
+    }
+
+    /// Returns the trusted key ids, sorted.
+    pub fn key_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.keys.keys().map(String::as_str).collect();
+        ids.sort();
+        ids
+    }
+
+    /// Returns the number of trusted keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true when no key is trusted.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// The sequence number of an accepted signed payload, claimed for its signing key.
+///
+/// Each key's sequence numbers must strictly increase. The `Bridge` records a claim in
+/// the rule store together with the write the payload makes, so a payload cannot be
+/// replayed after a restart or against another replica.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceClaim {
+    pub key_id: String,
+    pub sequence: u64,
+}
+
+impl SequenceClaim {
+    /// Claims `sequence` for a payload signed by `key_id` if the payload has not expired.
+    pub fn new(
+        key_id: &str,
+        sequence: u64,
+        expires_at_ms: i64,
+        now_ms: u64,
+    ) -> Result<Self, String> {
+        let expires_at_ms = u64::try_from(expires_at_ms).unwrap_or(0);
+        if expires_at_ms == 0 {
+            return Err("Signed payload must set expires_at_ms".to_string());
+        }
+        if expires_at_ms <= now_ms {
+            return Err(format!("Signed payload expired at {}", expires_at_ms));
+        }
+        if expires_at_ms - now_ms > MAX_SIGNATURE_LIFETIME_MS {
+            return Err(format!(
+                "Signed payload expires more than {} ms ahead",
+                MAX_SIGNATURE_LIFETIME_MS
+            ));
+        }
+        // Stores keep sequences as signed 64-bit integers.
+        if sequence > i64::MAX as u64 {
+            return Err(format!("Signed payload sequence {} is too large", sequence));
+        }
+        Ok(Self {
+            key_id: key_id.to_string(),
+            sequence,
+        })
+    }
+
+    /// Fails unless the claim comes after `last`, the last sequence accepted from its key.
+    pub fn check_after(&self, last: u64) -> Result<(), String> {
+        if self.sequence <= last {
+            return Err(format!(
+                "Signed payload sequence {} is not after {} from key '{}'",
+                self.sequence, last, self.key_id
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Signs `payload` with `key`, for producers of signed batches and commands.
+pub fn sign(key: &SigningKey, payload: &[u8]) -> Vec<u8> {
+    key.sign(payload).to_bytes().to_vec()
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return Err("public key is not valid hex".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&text[i..i + 2], 16)
+                .map_err(|_| "public key is not valid hex".to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_verify_accepts_only_trusted_signatures() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        let file = format!(
+            "# release signers\n\nci {}\n",
+            hex(key.verifying_key().as_bytes())
+        );
+        let keys = TrustedKeys::parse(&file).unwrap();
+        assert_eq!(keys.key_ids(), vec!["ci"]);
+
+        let signature = sign(&key, b"payload");
+        assert!(keys.verify("ci", b"payload", &signature).is_ok());
+        assert!(keys.verify("ci", b"tampered", &signature).is_err());
+        assert!(keys
+            .verify("ci", b"payload", &sign(&other, b"payload"))
+            .is_err());
+        assert!(keys.verify("ci", b"payload", &signature[..10]).is_err());
+        assert!(keys.verify("unknown", b"payload", &signature).is_err());
+    }
+
+    #[test]
+    fn test_sequence_claims_require_fresh_increasing_payloads() {
+        let now = 1_000_000;
+        let expires = (now + 1_000) as i64;
+
+        assert!(SequenceClaim::new("ci", 1, 0, now).is_err());
+        assert!(SequenceClaim::new("ci", 1, (now - 1) as i64, now).is_err());
+        let far = (now + MAX_SIGNATURE_LIFETIME_MS + 1) as i64;
+        assert!(SequenceClaim::new("ci", 1, far, now).is_err());
+        assert!(SequenceClaim::new("ci", u64::MAX, expires, now).is_err());
+
+        let claim = SequenceClaim::new("ci", 5, expires, now).unwrap();
+        assert!(claim.check_after(4).is_ok());
+        assert!(claim.check_after(5).is_err());
+        assert!(claim.check_after(6).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        assert!(TrustedKeys::parse("ci").is_err());
+        assert!(TrustedKeys::parse("ci zz").is_err());
+        assert!(TrustedKeys::parse("ci abcd").is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! # Hot Cache
+//!
+//! Capacity-bounded LRU tier for rule anchors.
+//!
+//! A `RuleVector` is about 8 KB, so only the recently evaluated ones are kept in
+//! memory; the bridge reloads the rest from cold storage on a miss. When an insert
+//! would exceed capacity, the least recently used ~10% of entries are evicted in one
+//! batch so eviction cost is amortised across inserts.
+
+use crate::rule_vector::RuleVector;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default number of cached anchor sets (~80 MB).
+pub const DEFAULT_HOT_CACHE_CAPACITY: usize = 10_000;
+
+#[derive(Debug)]
+struct CacheEntry {
+    vector: RuleVector,
+    /// Logical clock value of the last insert or marked access
+    last_used: AtomicU64,
+}
+
+/// Hot cache statistics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HotCacheStats {
+    pub entries: usize,
+    pub capacity: usize,
+    /// Number of eviction batches
+    pub total_evictions: u64,
+    /// Number of entries evicted across all batches
+    pub total_evicted: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Thread-safe LRU cache of rule anchors keyed by rule_id.
+#[derive(Debug)]
+pub struct HotCache {
+    entries: RwLock<HashMap<String, CacheEntry>>,
+    capacity: usize,
+    clock: AtomicU64,
+    total_evictions: AtomicU64,
+    total_evicted: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl Default for HotCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HotCache {
+    /// Creates a cache with the default capacity.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_HOT_CACHE_CAPACITY)
+    }
+
+    /// Creates a cache holding at most `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::with_capacity(
+                capacity.min(DEFAULT_HOT_CACHE_CAPACITY),
+            )),
+            capacity,
+            clock: AtomicU64::new(0),
+            total_evictions: AtomicU64::new(0),
+            total_evicted: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Creates a cache sized by `BRIDGE_HOT_CACHE_CAPACITY` (default 10,000 entries).
+    pub fn from_env() -> Self {
+        let capacity = std::env::var("BRIDGE_HOT_CACHE_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_HOT_CACHE_CAPACITY);
+        Self::with_capacity(capacity)
+    }
+
+    /// Inserts or replaces anchors, evicting the least recently used batch when full.
+    pub fn insert(&self, rule_id: String, vector: RuleVector) -> Result<(), String> {
+        if self.capacity == 0 {
+            return Err("Hot cache has zero capacity".to_string());
+        }
+
+        let mut entries = self.entries.write();
+        if !entries.contains_key(&rule_id) && entries.len() >= self.capacity {
+            self.evict_batch(&mut entries);
+        }
+        entries.insert(
+            rule_id,
+            CacheEntry {
+                vector,
+                last_used: AtomicU64::new(self.tick()),
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns cached anchors without refreshing their recency.
+    pub fn get(&self, rule_id: &str) -> Option<RuleVector> {
+        let found = self.peek(rule_id);
+        self.record_lookup(found.is_some());
+        found
+    }
+
+    /// Returns cached anchors and marks them as most recently used.
+    pub fn get_and_mark(&self, rule_id: &str) -> Option<RuleVector> {
+        let found = self.entries.read().get(rule_id).map(|entry| {
+            entry.last_used.store(self.tick(), Ordering::Relaxed);
+            entry.vector.clone()
+        });
+        self.record_lookup(found.is_some());
+        found
+    }
+
+    /// Returns cached anchors without refreshing recency or counting a lookup.
+    pub fn peek(&self, rule_id: &str) -> Option<RuleVector> {
+        self.entries
+            .read()
+            .get(rule_id)
+            .map(|entry| entry.vector.clone())
+    }
+
+    /// Returns true when anchors for `rule_id` are cached.
+    pub fn contains(&self, rule_id: &str) -> bool {
+        self.entries.read().contains_key(rule_id)
+    }
+
+    /// Drops the anchors for `rule_id`, if cached.
+    pub fn remove(&self, rule_id: &str) {
+        self.entries.write().remove(rule_id);
+    }
+
+    /// Drops every cached entry. Statistics are kept.
+    pub fn clear(&self) {
+        self.entries.write().clear();
+    }
+
+    /// Returns the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    /// Returns true when nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+
+    /// Returns the maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns current statistics.
+    pub fn stats(&self) -> HotCacheStats {
+        HotCacheStats {
+            entries: self.len(),
+            capacity: self.capacity,
+            total_evictions: self.total_evictions.load(Ordering::Relaxed),
+            total_evicted: self.total_evicted.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn record_lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Evicts the least recently used ~10% of entries (at least one).
+    fn evict_batch(&self, entries: &mut HashMap<String, CacheEntry>) {
+        let batch = (self.capacity / 10).max(1).min(entries.len());
+        if batch == 0 {
+            return;
+        }
+
+        let mut by_age: Vec<(u64, String)> = entries
+            .iter()
+            .map(|(rule_id, entry)| (entry.last_used.load(Ordering::Relaxed), rule_id.clone()))
+            .collect();
+        by_age.select_nth_unstable_by_key(batch - 1, |(last_used, _)| *last_used);
+        for (_, rule_id) in by_age.into_iter().take(batch) {
+            entries.remove(&rule_id);
+        }
+
+        self.total_evictions.fetch_add(1, Ordering::Relaxed);
+        self.total_evicted
+            .fetch_add(batch as u64, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,403 @@
+//! # In-Memory Rule Store
+//!
+//! Rule store with no filesystem access, for tests and embedded users.
+//!
+//! Transactions work on a copy of the state and swap it in on commit, so an aborted
+//! write leaves nothing behind. Nothing outlives the store, and it cannot be shared
+//! between processes.
+
+use super::rule_store::{
+    AgentLabels, PersistedRule, QuarantinedRule, RuleRow, RuleSetVersion, RuleStatus, RuleStore,
+    StoreRead, StoreTransaction, StoredRule,
+};
+use crate::types::{now_ms, DEFAULT_TENANT};
+use std::collections::{BTreeMap, HashMap};
+
+/// A history entry and the tenant owning the rule.
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    tenant_id: String,
+    persisted: PersistedRule,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MemoryState {
+    rules: BTreeMap<String, StoredRule>,
+    versions: BTreeMap<u64, RuleSetVersion>,
+    history: BTreeMap<(u64, String), HistoryEntry>,
+    quarantine: BTreeMap<String, QuarantinedRule>,
+    agent_labels: BTreeMap<(String, String), AgentLabels>,
+    signing_sequences: BTreeMap<String, u64>,
+}
+
+impl StoreRead for MemoryState {
+    fn get_rule(&self, rule_id: &str) -> Result<Option<StoredRule>, String> {
+        Ok(self.rules.get(rule_id).cloned())
+    }
+
+    fn list_rules(&self, status: Option<RuleStatus>) -> Result<Vec<StoredRule>, String> {
+        Ok(self
+            .rules
+            .values()
+            .filter(|stored| status.is_none_or(|status| stored.status == status))
+            .cloned()
+            .collect())
+    }
+
+    fn latest_version(&self) -> Result<u64, String> {
+        Ok(self.versions.keys().next_back().copied().unwrap_or(0))
+    }
+
+    fn has_version(&self, version: u64) -> Result<bool, String> {
+        Ok(self.versions.contains_key(&version))
+    }
+
+    fn list_versions(&self, limit: usize) -> Result<Vec<RuleSetVersion>, String> {
+        Ok(self.versions.values().rev().take(limit).cloned().collect())
+    }
+
+    fn list_tenant_versions(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+    ) -> Result<Vec<RuleSetVersion>, String> {
+        // Replay the history in order, tracking which of the tenant's rules are present.
+        let mut present = BTreeMap::new();
+        let mut touched = Vec::new();
+        for ((version, rule_id), entry) in &self.history {
+            if entry.tenant_id != tenant_id {
+                present.remove(rule_id);
+                continue;
+            }
+            present.insert(rule_id, entry.persisted.is_some());
+            let rule_count = present.values().filter(|active| **active).count();
+            match touched.last_mut() {
+                Some((last, count)) if *last == *version => *count = rule_count,
+                _ => touched.push((*version, rule_count)),
+            }
+        }
+        Ok(touched
+            .into_iter()
+            .rev()
+            .filter_map(|(version, rule_count)| {
+                let recorded = self.versions.get(&version)?;
+                Some(RuleSetVersion {
+                    rule_count,
+                    ..recorded.clone()
+                })
+            })
+            .take(limit)
+            .collect())
+    }
+
+    fn history_between(
+        &self,
+        after: u64,
+        upto: u64,
+    ) -> Result<Vec<(String, PersistedRule)>, String> {
+        // Later versions overwrite earlier ones, leaving each rule's latest entry.
+        let mut latest = BTreeMap::new();
+        for ((version, rule_id), entry) in &self.history {
+            if *version > after && *version <= upto {
+                latest.insert(rule_id.clone(), entry.persisted.clone());
+            }
+        }
+        Ok(latest.into_iter().collect())
+    }
+
+    fn list_quarantined(&self) -> Result<Vec<QuarantinedRule>, String> {
+        Ok(self.quarantine.values().cloned().collect())
+    }
+
+    fn list_agent_labels(&self) -> Result<Vec<AgentLabels>, String> {
+        Ok(self.agent_labels.values().cloned().collect())
+    }
+
+    fn signing_sequence(&self, key_id: &str) -> Result<u64, String> {
+        Ok(self.signing_sequences.get(key_id).copied().unwrap_or(0))
+    }
+}
+
+/// Rule store held entirely in memory.
+#[derive(Debug, Default)]
+pub struct MemoryRuleStore {
+    state: MemoryState,
+}
+
+impl MemoryRuleStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl StoreRead for MemoryRuleStore {
+    fn get_rule(&self, rule_id: &str) -> Result<Option<StoredRule>, String> {
+        self.state.get_rule(rule_id)
+    }
+
+    fn list_rules(&self, status: Option<RuleStatus>) -> Result<Vec<StoredRule>, String> {
+        self.state.list_rules(status)
+    }
+
+    fn latest_version(&self) -> Result<u64, String> {
+        self.state.latest_version()
+    }
+
+    fn has_version(&self, version: u64) -> Result<bool, String> {
+        self.state.has_version(version)
+    }
+
+    fn list_versions(&self, limit: usize) -> Result<Vec<RuleSetVersion>, String> {
+        self.state.list_versions(limit)
+    }
+
+    fn list_tenant_versions(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+    ) -> Result<Vec<RuleSetVersion>, String> {
+        self.state.list_tenant_versions(tenant_id, limit)
+    }
+
+    fn history_between(
+        &self,
+        after: u64,
+        upto: u64,
+    ) -> Result<Vec<(String, PersistedRule)>, String> {
+        self.state.history_between(after, upto)
+    }
+
+    fn list_quarantined(&self) -> Result<Vec<QuarantinedRule>, String> {
+        self.state.list_quarantined()
+    }
+
+    fn list_agent_labels(&self) -> Result<Vec<AgentLabels>, String> {
+        self.state.list_agent_labels()
+    }
+
+    fn signing_sequence(&self, key_id: &str) -> Result<u64, String> {
+        self.state.signing_sequence(key_id)
+    }
+}
+
+impl RuleStore for MemoryRuleStore {
+    fn begin(&mut self) -> Result<Box<dyn StoreTransaction + '_>, String> {
+        let working = self.state.clone();
+        Ok(Box::new(MemoryTransaction {
+            committed: &mut self.state,
+            working,
+        }))
+    }
+
+    fn change_token(&self) -> Result<Option<i64>, String> {
+        Ok(None)
+    }
+}
+
+/// Copy of the store state that replaces it on commit.
+struct MemoryTransaction<'s> {
+    committed: &'s mut MemoryState,
+    working: MemoryState,
+}
+
+impl StoreRead for MemoryTransaction<'_> {
+    fn get_rule(&self, rule_id: &str) -> Result<Option<StoredRule>, String> {
+        self.working.get_rule(rule_id)
+    }
+
+    fn list_rules(&self, status: Option<RuleStatus>) -> Result<Vec<StoredRule>, String> {
+        self.working.list_rules(status)
+    }
+
+    fn latest_version(&self) -> Result<u64, String> {
+        self.working.latest_version()
+    }
+
+    fn has_version(&self, version: u64) -> Result<bool, String> {
+        self.working.has_version(version)
+    }
+
+    fn list_versions(&self, limit: usize) -> Result<Vec<RuleSetVersion>, String> {
+        self.working.list_versions(limit)
+    }
+
+    fn list_tenant_versions(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+    ) -> Result<Vec<RuleSetVersion>, String> {
+        self.working.list_tenant_versions(tenant_id, limit)
+    }
+
+    fn history_between(
+        &self,
+        after: u64,
+        upto: u64,
+    ) -> Result<Vec<(String, PersistedRule)>, String> {
+        self.working.history_between(after, upto)
+    }
+
+    fn list_quarantined(&self) -> Result<Vec<QuarantinedRule>, String> {
+        self.working.list_quarantined()
+    }
+
+    fn list_agent_labels(&self) -> Result<Vec<AgentLabels>, String> {
+        self.working.list_agent_labels()
+    }
+
+    fn signing_sequence(&self, key_id: &str) -> Result<u64, String> {
+        self.working.signing_sequence(key_id)
+    }
+}
+
+impl StoreTransaction for MemoryTransaction<'_> {
+    fn upsert(&mut self, row: &RuleRow) -> Result<(), String> {
+        self.working.rules.insert(
+            row.rule_id.clone(),
+            StoredRule {
+                row: row.clone(),
+                status: RuleStatus::Active,
+                updated_at_ms: now_ms(),
+            },
+        );
+        Ok(())
+    }
+
+    fn set_status(&mut self, rule_id: &str, status: RuleStatus) -> Result<(), String> {
+        let stored = self
+            .working
+            .rules
+            .get_mut(rule_id)
+            .ok_or_else(|| format!("Rule {} not found", rule_id))?;
+        stored.status = status;
+        stored.updated_at_ms = now_ms();
+        Ok(())
+    }
+
+    fn delete(&mut self, rule_id: &str) -> Result<bool, String> {
+        Ok(self.working.rules.remove(rule_id).is_some())
+    }
+
+    fn delete_all(&mut self, status: Option<RuleStatus>) -> Result<Vec<String>, String> {
+        let mut deleted = Vec::new();
+        self.working.rules.retain(|rule_id, stored| {
+            let matches = status.is_none_or(|status| stored.status == status);
+            if matches {
+                deleted.push(rule_id.clone());
+            }
+            !matches
+        });
+        Ok(deleted)
+    }
+
+    fn quarantine(&mut self, rule_id: &str, reason: &str) -> Result<bool, String> {
+        let Some(stored) = self.working.rules.remove(rule_id) else {
+            return Ok(false);
+        };
+        self.working.quarantine.insert(
+            rule_id.to_string(),
+            QuarantinedRule {
+                row: stored.row,
+                reason: reason.to_string(),
+                quarantined_at_ms: now_ms(),
+            },
+        );
+        Ok(true)
+    }
+
+    fn set_agent_labels(
+        &mut self,
+        tenant_id: &str,
+        agent_id: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<(), String> {
+        let key = (tenant_id.to_string(), agent_id.to_string());
+        if labels.is_empty() {
+            self.working.agent_labels.remove(&key);
+        } else {
+            self.working.agent_labels.insert(
+                key,
+                AgentLabels {
+                    tenant_id: tenant_id.to_string(),
+                    agent_id: agent_id.to_string(),
+                    labels: labels.clone(),
+                    updated_at_ms: now_ms(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn set_signing_sequence(&mut self, key_id: &str, sequence: u64) -> Result<(), String> {
+        self.working
+            .signing_sequences
+            .insert(key_id.to_string(), sequence);
+        Ok(())
+    }
+
+    fn record_history(&mut self, version: u64, row: &RuleRow) -> Result<(), String> {
+        self.working.history.insert(
+            (version, row.rule_id.clone()),
+            HistoryEntry {
+                tenant_id: row.tenant_id.clone(),
+                persisted: Some((row.rule_json.clone(), row.anchors_bin.clone())),
+            },
+        );
+        Ok(())
+    }
+
+    fn record_removal(&mut self, version: u64, rule_id: &str) -> Result<(), String> {
+        let tenant_id = self
+            .working
+            .history
+            .iter()
+            .rev()
+            .find(|((earlier, id), _)| *earlier < version && id == rule_id)
+            .map_or_else(
+                || DEFAULT_TENANT.to_string(),
+                |(_, entry)| entry.tenant_id.clone(),
+            );
+        self.working.history.insert(
+            (version, rule_id.to_string()),
+            HistoryEntry {
+                tenant_id,
+                persisted: None,
+            },
+        );
+        Ok(())
+    }
+
+    fn record_version(
+        &mut self,
+        version: u64,
+        kind: &str,
+        description: &str,
+    ) -> Result<(), String> {
+        if self.working.versions.contains_key(&version) {
+            return Err(format!("Version {} is already recorded", version));
+        }
+        let rule_count = self
+            .working
+            .rules
+            .values()
+            .filter(|stored| stored.status == RuleStatus::Active)
+            .count();
+        self.working.versions.insert(
+            version,
+            RuleSetVersion {
+                version,
+                kind: kind.to_string(),
+                description: description.to_string(),
+                rule_count,
+                created_at_ms: now_ms(),
+            },
+        );
+        Ok(())
+    }
+
+    fn commit(self: Box<Self>) -> Result<(), String> {
+        *self.committed = self.working;
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+//! # Schema Migrations
+//!
+//! Numbered, idempotent schema migrations for the SQLite databases owned by the data
+//! plane (cold storage and hitlogs).
+//!
+//! Each database records the migrations it has applied in `schema_version`, keyed by
+//! component so several components can share one file. Pending migrations are applied
+//! in order at startup, each in its own IMMEDIATE transaction, so replicas starting
+//! together never apply the same step twice. A database whose recorded version is
+//! newer than the running binary knows about is refused rather than silently misread.
+
+use crate::types::now_ms;
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+
+const SCHEMA_VERSION_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_version (
+    component     TEXT NOT NULL,
+    version       INTEGER NOT NULL,
+    description   TEXT NOT NULL,
+    applied_at_ms INTEGER NOT NULL,
+    PRIMARY KEY (component, version)
+);
+";
+
+/// Data migration step run inside the migration's transaction.
+pub type MigrationFn = fn(&Connection) -> Result<(), String>;
+
+/// A single schema step.
+///
+/// `sql` runs first (it may be empty), then `apply` if set. Both must be safe to run
+/// against databases created before migrations were tracked.
+#[derive(Clone, Copy)]
+pub struct Migration {
+    /// Sequential version number, starting at 1
+    pub version: u32,
+    pub description: &'static str,
+    pub sql: &'static str,
+    /// Data migration that cannot be expressed as plain SQL
+    pub apply: Option<MigrationFn>,
+}
+
+impl std::fmt::Debug for Migration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Migration")
+            .field("version", &self.version)
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+/// Returns the latest schema version recorded for `component` (0 if none).
+pub fn current_version(conn: &Connection, component: &str) -> Result<u32, String> {
+    conn.execute_batch(SCHEMA_VERSION_TABLE)
+        .map_err(|e| format!("Failed to create schema_version table: {}", e))?;
+    let version: Option<i64> = conn
+        .query_row(
+            "SELECT MAX(version) FROM schema_version WHERE component = ?1",
+            params![component],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read {} schema version: {}", component, e))?
+        .flatten();
+    Ok(version.unwrap_or(0) as u32)
+}
+
+/// Applies every migration newer than the recorded version for `component`.
+///
+/// Returns the resulting schema version. Fails without touching the database if it was
+/// written by a newer binary.
+pub fn migrate(
+    conn: &mut Connection,
+    component: &str,
+    migrations: &[Migration],
+) -> Result<u32, String> {
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+    debug_assert!(
+        migrations
+            .iter()
+            .zip(1..)
+            .all(|(m, expected)| m.version == expected),
+        "{} migrations must be numbered 1..N",
+        component
+    );
+
+    let found = current_version(conn, component)?;
+    if found > latest {
+        return Err(format!(
+            "{} database schema version {} is newer than this binary supports ({}); refusing to start",
+            component, found, latest
+        ));
+    }
+
+    for migration in migrations.iter().filter(|m| m.version > found) {
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| format!("Failed to begin {} migration: {}", component, e))?;
+
+        // Another process may have applied it while we waited for the write lock.
+        if current_version(&tx, component)? >= migration.version {
+            continue;
+        }
+
+        tx.execute_batch(migration.sql).map_err(|e| {
+            format!(
+                "{} migration {} ({}) failed: {}",
+                component, migration.version, migration.description, e
+            )
+        })?;
+        if let Some(apply) = migration.apply {
+            apply(&tx).map_err(|e| {
+                format!(
+                    "{} migration {} ({}) failed: {}",
+                    component, migration.version, migration.description, e
+                )
+            })?;
+        }
+        tx.execute(
+            "INSERT INTO schema_version (component, version, description, applied_at_ms)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                component,
+                migration.version,
+                migration.description,
+                now_ms() as i64
+            ],
+        )
+        .map_err(|e| format!("Failed to record {} schema version: {}", component, e))?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit {} migration: {}", component, e))?;
+
+        log::info!(
+            "Applied {} schema migration {}: {}",
+            component,
+            migration.version,
+            migration.description
+        );
+    }
+
+    current_version(conn, component)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_note(conn: &Connection) -> Result<(), String> {
+        conn.execute("INSERT INTO notes (body) VALUES ('seeded')", [])
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            description: "notes table",
+            sql: "CREATE TABLE IF NOT EXISTS notes (body TEXT NOT NULL);",
+            apply: None,
+        },
+        Migration {
+            version: 2,
+            description: "seed note",
+            sql: "",
+            apply: Some(add_note),
+        },
+    ];
+
+    fn note_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_migrate_applies_pending_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn, "test", &MIGRATIONS[..1]).unwrap(), 1);
+        assert_eq!(migrate(&mut conn, "test", MIGRATIONS).unwrap(), 2);
+        assert_eq!(migrate(&mut conn, "test", MIGRATIONS).unwrap(), 2);
+        assert_eq!(note_count(&conn), 1);
+
+        // Components are tracked independently.
+        assert_eq!(current_version(&conn, "other").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migrate_refuses_newer_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn, "test", MIGRATIONS).unwrap();
+
+        let err = migrate(&mut conn, "test", &MIGRATIONS[..1]).unwrap_err();
+        assert!(err.contains("newer than this binary supports"), "{}", err);
+    }
+}
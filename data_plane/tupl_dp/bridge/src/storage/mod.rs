@@ -1 +1,21 @@
-//! Storage module (legacy tiers removed).
+//! Storage module.
+//!
+//! Rule persistence backends behind the `RuleStore` trait, the hot anchor cache, the
+//! memory-mapped warm anchor file, and schema migrations for the SQLite databases.
+
+pub mod hot_cache;
+pub mod memory;
+pub mod migrations;
+pub mod rule_store;
+pub mod sqlite;
+pub mod warm;
+
+pub use hot_cache::{HotCache, HotCacheStats};
+pub use memory::MemoryRuleStore;
+pub use migrations::{migrate, Migration};
+pub use rule_store::{
+    AgentLabels, PersistedRule, QuarantinedRule, RuleRow, RuleSetVersion, RuleStatus, RuleStore,
+    StoreRead, StoreTransaction, StoredRule,
+};
+pub use sqlite::SqliteRuleStore;
+pub use warm::WarmStore;
@@ -0,0 +1,205 @@
+//! # Rule Store
+//!
+//! Persistence interface behind the `Bridge`.
+//!
+//! A store keeps the current rule rows (with their lifecycle status) plus the
+//! append-only version history. Rules are handled in their persisted form
+//! (metadata JSON + anchor bytes); encoding and decoding stays in the bridge, so a
+//! backend only moves rows around. All writes go through a `StoreTransaction`, and
+//! nothing a transaction does is visible until it commits.
+//!
+//! Active rows the bridge cannot decode are moved to a separate quarantine, so they
+//! stay inspectable without being loaded again.
+//!
+//! The store also keeps the labels registered for each agent, which rule selector tags
+//! are matched against. Labels are not part of the rule-set history.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Lifecycle status of a stored rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuleStatus {
+    /// Loaded and enforced
+    Active,
+    /// Paused; kept in storage and skipped by enforcement
+    Disabled,
+    /// Retired; kept in storage and skipped by enforcement
+    Archived,
+}
+
+impl RuleStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleStatus::Active => "active",
+            RuleStatus::Disabled => "disabled",
+            RuleStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "active" => Ok(RuleStatus::Active),
+            "disabled" => Ok(RuleStatus::Disabled),
+            "archived" => Ok(RuleStatus::Archived),
+            other => Err(format!("Unknown rule status '{}'", other)),
+        }
+    }
+}
+
+/// A rule in its persisted form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleRow {
+    pub rule_id: String,
+    pub tenant_id: String,
+    pub layer: Option<String>,
+    pub priority: i64,
+    /// Serialized `RuleMetadata`
+    pub rule_json: String,
+    /// Serialized `RuleVector`
+    pub anchors_bin: Vec<u8>,
+}
+
+/// A stored rule row with its lifecycle state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredRule {
+    pub row: RuleRow,
+    pub status: RuleStatus,
+    pub updated_at_ms: u64,
+}
+
+/// A rule row that could not be loaded, moved out of the rule set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedRule {
+    pub row: RuleRow,
+    /// Why the row could not be decoded
+    pub reason: String,
+    pub quarantined_at_ms: u64,
+}
+
+/// Labels registered for an agent of a tenant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentLabels {
+    pub tenant_id: String,
+    pub agent_id: String,
+    pub labels: HashMap<String, String>,
+    pub updated_at_ms: u64,
+}
+
+/// A recorded rule-set version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSetVersion {
+    pub version: u64,
+    /// "baseline"|"install"|"remove"|"clear"|"promote"|"rollback"|"enable"|"disable"|
+    /// "archive"|"expire"|"import"|"quarantine"
+    pub kind: String,
+    pub description: String,
+    /// Number of active rules after this version was committed
+    pub rule_count: usize,
+    pub created_at_ms: u64,
+}
+
+/// A rule's persisted (rule_json, anchors_bin), or None if it was removed.
+pub type PersistedRule = Option<(String, Vec<u8>)>;
+
+/// Read access to a store, either committed state or a transaction's view of it.
+pub trait StoreRead {
+    /// Returns a stored rule in any status.
+    fn get_rule(&self, rule_id: &str) -> Result<Option<StoredRule>, String>;
+
+    /// Lists stored rules ordered by rule_id, optionally only those in `status`.
+    fn list_rules(&self, status: Option<RuleStatus>) -> Result<Vec<StoredRule>, String>;
+
+    /// Returns the latest recorded version (0 when no history exists).
+    fn latest_version(&self) -> Result<u64, String>;
+
+    /// Returns true when `version` has been recorded.
+    fn has_version(&self, version: u64) -> Result<bool, String>;
+
+    /// Lists recorded versions, newest first.
+    fn list_versions(&self, limit: usize) -> Result<Vec<RuleSetVersion>, String>;
+
+    /// Lists the versions that changed a rule of `tenant_id`, newest first, each counting
+    /// only that tenant's active rules.
+    fn list_tenant_versions(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+    ) -> Result<Vec<RuleSetVersion>, String>;
+
+    /// Returns the latest history entry of every rule changed in versions `(after, upto]`.
+    fn history_between(
+        &self,
+        after: u64,
+        upto: u64,
+    ) -> Result<Vec<(String, PersistedRule)>, String>;
+
+    /// Lists quarantined rows ordered by rule_id.
+    fn list_quarantined(&self) -> Result<Vec<QuarantinedRule>, String>;
+
+    /// Lists registered agent labels ordered by (tenant_id, agent_id).
+    fn list_agent_labels(&self) -> Result<Vec<AgentLabels>, String>;
+
+    /// Returns the last sequence accepted from signing key `key_id` (0 when none).
+    fn signing_sequence(&self, key_id: &str) -> Result<u64, String>;
+}
+
+/// An open write transaction. Dropping it without `commit` discards every change.
+pub trait StoreTransaction: StoreRead {
+    /// Inserts or replaces a rule row with status active.
+    fn upsert(&mut self, row: &RuleRow) -> Result<(), String>;
+
+    /// Changes the status of an existing rule.
+    fn set_status(&mut self, rule_id: &str, status: RuleStatus) -> Result<(), String>;
+
+    /// Deletes a rule in any status. Returns false if it did not exist.
+    fn delete(&mut self, rule_id: &str) -> Result<bool, String>;
+
+    /// Deletes every rule (or only those in `status`). Returns the deleted IDs.
+    fn delete_all(&mut self, status: Option<RuleStatus>) -> Result<Vec<String>, String>;
+
+    /// Moves a rule row into quarantine with `reason`, replacing any earlier entry for it.
+    /// Returns false if the rule did not exist.
+    fn quarantine(&mut self, rule_id: &str, reason: &str) -> Result<bool, String>;
+
+    /// Replaces the labels registered for an agent; empty `labels` removes them.
+    fn set_agent_labels(
+        &mut self,
+        tenant_id: &str,
+        agent_id: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<(), String>;
+
+    /// Records `sequence` as the last one accepted from signing key `key_id`.
+    fn set_signing_sequence(&mut self, key_id: &str, sequence: u64) -> Result<(), String>;
+
+    /// Records a rule's persisted content in the history of `version`.
+    fn record_history(&mut self, version: u64, row: &RuleRow) -> Result<(), String>;
+
+    /// Records a rule removal in the history of `version`, under the tenant of the
+    /// rule's previous entry.
+    fn record_removal(&mut self, version: u64, rule_id: &str) -> Result<(), String>;
+
+    /// Records `version` itself with the current active rule count.
+    ///
+    /// Must run after the version's row changes so the count is current.
+    fn record_version(&mut self, version: u64, kind: &str, description: &str)
+        -> Result<(), String>;
+
+    /// Makes every change visible atomically.
+    fn commit(self: Box<Self>) -> Result<(), String>;
+}
+
+/// A rule persistence backend.
+pub trait RuleStore: StoreRead + Debug + Send {
+    /// Starts a write transaction.
+    ///
+    /// Backends shared between processes must lock out other writers until the
+    /// transaction ends, so the version read inside it stays current.
+    fn begin(&mut self) -> Result<Box<dyn StoreTransaction + '_>, String>;
+
+    /// Token that changes whenever another process commits to the store.
+    ///
+    /// None for stores that cannot be shared, which never need re-syncing.
+    fn change_token(&self) -> Result<Option<i64>, String>;
+}
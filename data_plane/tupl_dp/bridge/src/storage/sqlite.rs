@@ -0,0 +1,925 @@
+//! # SQLite Rule Store
+//!
+//! Cold storage backend: a single SQLite file, shareable between processes.
+//!
+//! Write transactions are IMMEDIATE, so other processes cannot commit between the
+//! bridge catching up on history and recording its own version. The schema is owned
+//! here and evolved through numbered migrations.
+
+use super::migrations::{migrate, Migration};
+use super::rule_store::{
+    AgentLabels, PersistedRule, QuarantinedRule, RuleRow, RuleSetVersion, RuleStatus, RuleStore,
+    StoreRead, StoreTransaction, StoredRule,
+};
+use crate::rule_vector::{AnchorPrecision, RuleVector};
+use crate::types::{now_ms, PolicyType, RuleMetadata};
+use rusqlite::{params, Connection, Row, Transaction, TransactionBehavior};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// Component name for cold storage in `schema_version`.
+const SCHEMA_COMPONENT: &str = "cold_storage";
+
+/// Cold storage migrations, applied in order at startup. Never edit a shipped step;
+/// append a new one instead.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "rules table",
+        sql: "
+CREATE TABLE IF NOT EXISTS rules (
+    id          TEXT PRIMARY KEY,
+    tenant_id   TEXT NOT NULL,
+    layer       TEXT,
+    priority    INTEGER NOT NULL DEFAULT 0,
+    rule_json   TEXT NOT NULL,
+    anchors_bin BLOB NOT NULL,
+    status      TEXT NOT NULL DEFAULT 'active',
+    updated_at  REAL NOT NULL
+);
+",
+        apply: None,
+    },
+    Migration {
+        version: 2,
+        description: "rule-set version history",
+        sql: "
+CREATE TABLE IF NOT EXISTS rule_versions (
+    version       INTEGER PRIMARY KEY,
+    kind          TEXT NOT NULL,
+    description   TEXT NOT NULL,
+    rule_count    INTEGER NOT NULL,
+    created_at_ms INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS rule_history (
+    version     INTEGER NOT NULL,
+    id          TEXT NOT NULL,
+    rule_json   TEXT,
+    anchors_bin BLOB,
+    PRIMARY KEY (version, id)
+);
+",
+        apply: None,
+    },
+    Migration {
+        version: 3,
+        description: "persist AARM policy fields in legacy rule_json",
+        sql: "",
+        apply: Some(upgrade_legacy_rule_json),
+    },
+    Migration {
+        version: 4,
+        description: "seed history from rules present before it was recorded",
+        sql: "",
+        apply: Some(bootstrap_history),
+    },
+    Migration {
+        version: 5,
+        description: "quarantine for undecodable rule rows",
+        sql: "
+CREATE TABLE IF NOT EXISTS rule_quarantine (
+    id                TEXT PRIMARY KEY,
+    tenant_id         TEXT NOT NULL,
+    layer             TEXT,
+    priority          INTEGER NOT NULL DEFAULT 0,
+    rule_json         TEXT NOT NULL,
+    anchors_bin       BLOB NOT NULL,
+    reason            TEXT NOT NULL,
+    quarantined_at_ms INTEGER NOT NULL
+);
+",
+        apply: None,
+    },
+    Migration {
+        version: 6,
+        description: "compact versioned anchor encoding",
+        sql: "",
+        apply: Some(compact_legacy_anchors),
+    },
+    Migration {
+        version: 7,
+        description: "tenant in the rule scope, carried over from the tenant_id column",
+        sql: "
+-- Rows written before scopes carried a tenant kept it only in the tenant_id column.
+UPDATE rules SET tenant_id = COALESCE(NULLIF(tenant_id, ''), 'default');
+
+UPDATE rule_quarantine SET tenant_id = COALESCE(NULLIF(tenant_id, ''), 'default');
+
+UPDATE rule_history SET rule_json = json_set(rule_json, '$.scope.tenant_id', COALESCE(
+    (SELECT tenant_id FROM rules WHERE rules.id = rule_history.id),
+    (SELECT tenant_id FROM rule_quarantine WHERE rule_quarantine.id = rule_history.id),
+    'default'))
+WHERE json_valid(rule_json) AND json_type(rule_json, '$.scope') = 'object';
+
+UPDATE rules SET rule_json = json_set(rule_json, '$.scope.tenant_id', tenant_id)
+WHERE json_valid(rule_json) AND json_type(rule_json, '$.scope') = 'object';
+
+UPDATE rule_quarantine SET rule_json = json_set(rule_json, '$.scope.tenant_id', tenant_id)
+WHERE json_valid(rule_json) AND json_type(rule_json, '$.scope') = 'object';
+
+CREATE INDEX IF NOT EXISTS idx_rules_tenant ON rules (tenant_id);
+",
+        apply: None,
+    },
+    Migration {
+        version: 8,
+        description: "agent labels for rule selector tags",
+        sql: "
+CREATE TABLE IF NOT EXISTS agent_labels (
+    tenant_id TEXT NOT NULL,
+    agent_id TEXT NOT NULL,
+    labels_json TEXT NOT NULL,
+    updated_at_ms INTEGER NOT NULL,
+    PRIMARY KEY (tenant_id, agent_id)
+);
+",
+        apply: None,
+    },
+    Migration {
+        version: 9,
+        description: "tenant of each history entry",
+        sql: "",
+        apply: Some(add_history_tenant),
+    },
+    Migration {
+        version: 10,
+        description: "last accepted sequence of each signing key",
+        sql: "
+CREATE TABLE IF NOT EXISTS signing_sequences (
+    key_id TEXT PRIMARY KEY,
+    sequence INTEGER NOT NULL,
+    updated_at_ms INTEGER NOT NULL
+);
+",
+        apply: None,
+    },
+];
+
+const RULE_COLUMNS: &str =
+    "id, tenant_id, layer, priority, rule_json, anchors_bin, status, updated_at";
+
+/// SQLite-backed rule store.
+#[derive(Debug)]
+pub struct SqliteRuleStore {
+    conn: Connection,
+}
+
+impl SqliteRuleStore {
+    /// Opens (creating if needed) the database at `path` and applies pending migrations.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        // Ensure parent directory exists
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+            }
+        }
+
+        let mut conn =
+            Connection::open(path).map_err(|e| format!("Failed to open SQLite database: {}", e))?;
+
+        // Other replicas may hold the write lock briefly; wait instead of failing.
+        conn.busy_timeout(Duration::from_secs(5))
+            .map_err(|e| format!("Failed to set SQLite busy timeout: {}", e))?;
+
+        migrate(&mut conn, SCHEMA_COMPONENT, MIGRATIONS)?;
+
+        Ok(Self { conn })
+    }
+}
+
+impl StoreRead for SqliteRuleStore {
+    fn get_rule(&self, rule_id: &str) -> Result<Option<StoredRule>, String> {
+        get_rule(&self.conn, rule_id)
+    }
+
+    fn list_rules(&self, status: Option<RuleStatus>) -> Result<Vec<StoredRule>, String> {
+        list_rules(&self.conn, status)
+    }
+
+    fn latest_version(&self) -> Result<u64, String> {
+        latest_version(&self.conn)
+    }
+
+    fn has_version(&self, version: u64) -> Result<bool, String> {
+        has_version(&self.conn, version)
+    }
+
+    fn list_versions(&self, limit: usize) -> Result<Vec<RuleSetVersion>, String> {
+        list_versions(&self.conn, limit)
+    }
+
+    fn list_tenant_versions(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+    ) -> Result<Vec<RuleSetVersion>, String> {
+        list_tenant_versions(&self.conn, tenant_id, limit)
+    }
+
+    fn history_between(
+        &self,
+        after: u64,
+        upto: u64,
+    ) -> Result<Vec<(String, PersistedRule)>, String> {
+        history_between(&self.conn, after, upto)
+    }
+
+    fn list_quarantined(&self) -> Result<Vec<QuarantinedRule>, String> {
+        list_quarantined(&self.conn)
+    }
+
+    fn list_agent_labels(&self) -> Result<Vec<AgentLabels>, String> {
+        list_agent_labels(&self.conn)
+    }
+
+    fn signing_sequence(&self, key_id: &str) -> Result<u64, String> {
+        signing_sequence(&self.conn, key_id)
+    }
+}
+
+impl RuleStore for SqliteRuleStore {
+    fn begin(&mut self) -> Result<Box<dyn StoreTransaction + '_>, String> {
+        let tx = self
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| format!("Failed to begin SQLite transaction: {}", e))?;
+        Ok(Box::new(SqliteTransaction { tx }))
+    }
+
+    /// `PRAGMA data_version` only changes on commits made through other connections,
+    /// so an idle check costs a single pragma read.
+    fn change_token(&self) -> Result<Option<i64>, String> {
+        self.conn
+            .query_row("PRAGMA data_version", [], |row| row.get(0))
+            .map(Some)
+            .map_err(|e| format!("Failed to read SQLite data_version: {}", e))
+    }
+}
+
+/// An IMMEDIATE SQLite transaction.
+struct SqliteTransaction<'c> {
+    tx: Transaction<'c>,
+}
+
+impl StoreRead for SqliteTransaction<'_> {
+    fn get_rule(&self, rule_id: &str) -> Result<Option<StoredRule>, String> {
+        get_rule(&self.tx, rule_id)
+    }
+
+    fn list_rules(&self, status: Option<RuleStatus>) -> Result<Vec<StoredRule>, String> {
+        list_rules(&self.tx, status)
+    }
+
+    fn latest_version(&self) -> Result<u64, String> {
+        latest_version(&self.tx)
+    }
+
+    fn has_version(&self, version: u64) -> Result<bool, String> {
+        has_version(&self.tx, version)
+    }
+
+    fn list_versions(&self, limit: usize) -> Result<Vec<RuleSetVersion>, String> {
+        list_versions(&self.tx, limit)
+    }
+
+    fn list_tenant_versions(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+    ) -> Result<Vec<RuleSetVersion>, String> {
+        list_tenant_versions(&self.tx, tenant_id, limit)
+    }
+
+    fn history_between(
+        &self,
+        after: u64,
+        upto: u64,
+    ) -> Result<Vec<(String, PersistedRule)>, String> {
+        history_between(&self.tx, after, upto)
+    }
+
+    fn list_quarantined(&self) -> Result<Vec<QuarantinedRule>, String> {
+        list_quarantined(&self.tx)
+    }
+
+    fn list_agent_labels(&self) -> Result<Vec<AgentLabels>, String> {
+        list_agent_labels(&self.tx)
+    }
+
+    fn signing_sequence(&self, key_id: &str) -> Result<u64, String> {
+        signing_sequence(&self.tx, key_id)
+    }
+}
+
+impl StoreTransaction for SqliteTransaction<'_> {
+    fn upsert(&mut self, row: &RuleRow) -> Result<(), String> {
+        let updated_at = (now_ms() as f64) / 1000.0;
+        self.tx
+            .execute(
+                "INSERT OR REPLACE INTO rules (id, tenant_id, layer, priority, rule_json, anchors_bin, status, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'active', ?7)",
+                params![
+                    row.rule_id,
+                    row.tenant_id,
+                    row.layer,
+                    row.priority,
+                    row.rule_json,
+                    row.anchors_bin,
+                    updated_at,
+                ],
+            )
+            .map_err(|e| format!("SQLite upsert failed for rule {}: {}", row.rule_id, e))?;
+        Ok(())
+    }
+
+    fn set_status(&mut self, rule_id: &str, status: RuleStatus) -> Result<(), String> {
+        let updated_at = (now_ms() as f64) / 1000.0;
+        self.tx
+            .execute(
+                "UPDATE rules SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                params![status.as_str(), updated_at, rule_id],
+            )
+            .map_err(|e| format!("SQLite status update failed for rule {}: {}", rule_id, e))?;
+        Ok(())
+    }
+
+    fn delete(&mut self, rule_id: &str) -> Result<bool, String> {
+        let deleted = self
+            .tx
+            .execute("DELETE FROM rules WHERE id = ?1", params![rule_id])
+            .map_err(|e| format!("SQLite delete failed: {}", e))?;
+        Ok(deleted > 0)
+    }
+
+    fn delete_all(&mut self, status: Option<RuleStatus>) -> Result<Vec<String>, String> {
+        let ids: Vec<String> = {
+            let mut stmt = self
+                .tx
+                .prepare("SELECT id FROM rules WHERE ?1 IS NULL OR status = ?1")
+                .map_err(|e| format!("Prepare failed during delete: {}", e))?;
+            let collected: Result<Vec<_>, _> = stmt
+                .query_map(params![status.map(|s| s.as_str())], |row| row.get(0))
+                .map_err(|e| format!("Query failed during delete: {}", e))?
+                .collect();
+            collected.map_err(|e| format!("Row collection failed during delete: {}", e))?
+        };
+        self.tx
+            .execute(
+                "DELETE FROM rules WHERE ?1 IS NULL OR status = ?1",
+                params![status.map(|s| s.as_str())],
+            )
+            .map_err(|e| format!("SQLite delete failed: {}", e))?;
+        Ok(ids)
+    }
+
+    fn quarantine(&mut self, rule_id: &str, reason: &str) -> Result<bool, String> {
+        let moved = self
+            .tx
+            .execute(
+                "INSERT OR REPLACE INTO rule_quarantine
+                     (id, tenant_id, layer, priority, rule_json, anchors_bin, reason, quarantined_at_ms)
+                 SELECT id, tenant_id, layer, priority, rule_json, anchors_bin, ?2, ?3
+                 FROM rules WHERE id = ?1",
+                params![rule_id, reason, now_ms() as i64],
+            )
+            .map_err(|e| format!("SQLite quarantine failed for rule {}: {}", rule_id, e))?;
+        if moved == 0 {
+            return Ok(false);
+        }
+        self.tx
+            .execute("DELETE FROM rules WHERE id = ?1", params![rule_id])
+            .map_err(|e| format!("SQLite delete failed: {}", e))?;
+        Ok(true)
+    }
+
+    fn set_agent_labels(
+        &mut self,
+        tenant_id: &str,
+        agent_id: &str,
+        labels: &HashMap<String, String>,
+    ) -> Result<(), String> {
+        if labels.is_empty() {
+            self.tx
+                .execute(
+                    "DELETE FROM agent_labels WHERE tenant_id = ?1 AND agent_id = ?2",
+                    params![tenant_id, agent_id],
+                )
+                .map_err(|e| format!("SQLite delete failed for agent {}: {}", agent_id, e))?;
+            return Ok(());
+        }
+        let labels_json = serde_json::to_string(labels)
+            .map_err(|e| format!("Failed to serialize labels for agent {}: {}", agent_id, e))?;
+        self.tx
+            .execute(
+                "INSERT OR REPLACE INTO agent_labels (tenant_id, agent_id, labels_json, updated_at_ms)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![tenant_id, agent_id, labels_json, now_ms() as i64],
+            )
+            .map_err(|e| format!("SQLite upsert failed for agent {}: {}", agent_id, e))?;
+        Ok(())
+    }
+
+    fn set_signing_sequence(&mut self, key_id: &str, sequence: u64) -> Result<(), String> {
+        self.tx
+            .execute(
+                "INSERT OR REPLACE INTO signing_sequences (key_id, sequence, updated_at_ms)
+                 VALUES (?1, ?2, ?3)",
+                params![key_id, sequence as i64, now_ms() as i64],
+            )
+            .map_err(|e| format!("SQLite upsert failed for signing key {}: {}", key_id, e))?;
+        Ok(())
+    }
+
+    fn record_history(&mut self, version: u64, row: &RuleRow) -> Result<(), String> {
+        self.tx
+            .execute(
+                "INSERT OR REPLACE INTO rule_history (version, id, rule_json, anchors_bin, tenant_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    version as i64,
+                    row.rule_id,
+                    row.rule_json,
+                    row.anchors_bin,
+                    row.tenant_id,
+                ],
+            )
+            .map_err(|e| {
+                format!(
+                    "SQLite history insert failed for rule {}: {}",
+                    row.rule_id, e
+                )
+            })?;
+        Ok(())
+    }
+
+    fn record_removal(&mut self, version: u64, rule_id: &str) -> Result<(), String> {
+        self.tx
+            .execute(
+                "INSERT OR REPLACE INTO rule_history (version, id, rule_json, anchors_bin, tenant_id)
+                 VALUES (?1, ?2, NULL, NULL, COALESCE(
+                     (SELECT tenant_id FROM rule_history
+                      WHERE id = ?2 AND version < ?1 ORDER BY version DESC LIMIT 1),
+                     'default'))",
+                params![version as i64, rule_id],
+            )
+            .map_err(|e| format!("SQLite history insert failed for rule {}: {}", rule_id, e))?;
+        Ok(())
+    }
+
+    fn record_version(
+        &mut self,
+        version: u64,
+        kind: &str,
+        description: &str,
+    ) -> Result<(), String> {
+        record_version(&self.tx, version, kind, description)
+    }
+
+    fn commit(self: Box<Self>) -> Result<(), String> {
+        self.tx
+            .commit()
+            .map_err(|e| format!("SQLite commit failed: {}", e))
+    }
+}
+
+// ================================================================================================
+// QUERIES
+// ================================================================================================
+
+fn stored_rule(row: &Row<'_>) -> rusqlite::Result<(StoredRule, String)> {
+    let status: String = row.get(6)?;
+    let updated_at: f64 = row.get(7)?;
+    let stored = StoredRule {
+        row: RuleRow {
+            rule_id: row.get(0)?,
+            tenant_id: row.get(1)?,
+            layer: row.get(2)?,
+            priority: row.get(3)?,
+            rule_json: row.get(4)?,
+            anchors_bin: row.get(5)?,
+        },
+        status: RuleStatus::Active,
+        updated_at_ms: (updated_at * 1000.0) as u64,
+    };
+    Ok((stored, status))
+}
+
+fn with_status((mut stored, status): (StoredRule, String)) -> Result<StoredRule, String> {
+    stored.status = RuleStatus::parse(&status)?;
+    Ok(stored)
+}
+
+fn get_rule(conn: &Connection, rule_id: &str) -> Result<Option<StoredRule>, String> {
+    let row = conn.query_row(
+        &format!("SELECT {} FROM rules WHERE id = ?1", RULE_COLUMNS),
+        params![rule_id],
+        stored_rule,
+    );
+    match row {
+        Ok(row) => with_status(row).map(Some),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(format!("Failed to read rule {}: {}", rule_id, e)),
+    }
+}
+
+fn list_rules(conn: &Connection, status: Option<RuleStatus>) -> Result<Vec<StoredRule>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM rules WHERE ?1 IS NULL OR status = ?1 ORDER BY id",
+            RULE_COLUMNS
+        ))
+        .map_err(|e| format!("Prepare failed listing rules: {}", e))?;
+
+    let rows: Result<Vec<_>, _> = stmt
+        .query_map(params![status.map(|s| s.as_str())], stored_rule)
+        .map_err(|e| format!("Query failed listing rules: {}", e))?
+        .collect();
+    rows.map_err(|e| format!("Row collection failed listing rules: {}", e))?
+        .into_iter()
+        .map(with_status)
+        .collect()
+}
+
+/// Records `version` itself. Must run after the version's row changes so the rule count is current.
+fn record_version(
+    conn: &Connection,
+    version: u64,
+    kind: &str,
+    description: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO rule_versions (version, kind, description, rule_count, created_at_ms)
+         VALUES (?1, ?2, ?3, (SELECT COUNT(*) FROM rules WHERE status = 'active'), ?4)",
+        params![version as i64, kind, description, now_ms() as i64],
+    )
+    .map_err(|e| {
+        format!(
+            "SQLite version insert failed for version {}: {}",
+            version, e
+        )
+    })?;
+    Ok(())
+}
+
+fn latest_version(conn: &Connection) -> Result<u64, String> {
+    let latest: Option<i64> = conn
+        .query_row("SELECT MAX(version) FROM rule_versions", [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| format!("Failed to read latest rule-set version: {}", e))?;
+    Ok(latest.unwrap_or(0) as u64)
+}
+
+fn has_version(conn: &Connection, version: u64) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM rule_versions WHERE version = ?1)",
+        params![version as i64],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to look up version {}: {}", version, e))
+}
+
+fn list_versions(conn: &Connection, limit: usize) -> Result<Vec<RuleSetVersion>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT version, kind, description, rule_count, created_at_ms
+             FROM rule_versions ORDER BY version DESC LIMIT ?1",
+        )
+        .map_err(|e| format!("Prepare failed listing versions: {}", e))?;
+
+    let collected: Result<Vec<_>, _> = stmt
+        .query_map(params![limit as i64], |row| {
+            Ok(RuleSetVersion {
+                version: row.get::<_, i64>(0)? as u64,
+                kind: row.get(1)?,
+                description: row.get(2)?,
+                rule_count: row.get::<_, i64>(3)? as usize,
+                created_at_ms: row.get::<_, i64>(4)? as u64,
+            })
+        })
+        .map_err(|e| format!("Query failed listing versions: {}", e))?
+        .collect();
+
+    collected.map_err(|e| format!("Row collection failed listing versions: {}", e))
+}
+
+fn list_tenant_versions(
+    conn: &Connection,
+    tenant_id: &str,
+    limit: usize,
+) -> Result<Vec<RuleSetVersion>, String> {
+    // A rule counts at a version when its latest entry up to it is a live one of the tenant.
+    let mut stmt = conn
+        .prepare(
+            "SELECT v.version, v.kind, v.description,
+                    (SELECT COUNT(*) FROM rule_history h
+                     WHERE h.tenant_id = ?1 AND h.rule_json IS NOT NULL
+                       AND h.version = (SELECT MAX(version) FROM rule_history
+                                        WHERE id = h.id AND version <= v.version)),
+                    v.created_at_ms
+             FROM rule_versions v
+             WHERE EXISTS (SELECT 1 FROM rule_history
+                           WHERE version = v.version AND tenant_id = ?1)
+             ORDER BY v.version DESC LIMIT ?2",
+        )
+        .map_err(|e| format!("Prepare failed listing versions: {}", e))?;
+
+    let collected: Result<Vec<_>, _> = stmt
+        .query_map(params![tenant_id, limit as i64], |row| {
+            Ok(RuleSetVersion {
+                version: row.get::<_, i64>(0)? as u64,
+                kind: row.get(1)?,
+                description: row.get(2)?,
+                rule_count: row.get::<_, i64>(3)? as usize,
+                created_at_ms: row.get::<_, i64>(4)? as u64,
+            })
+        })
+        .map_err(|e| format!("Query failed listing versions: {}", e))?
+        .collect();
+
+    collected.map_err(|e| format!("Row collection failed listing versions: {}", e))
+}
+
+fn history_between(
+    conn: &Connection,
+    after: u64,
+    upto: u64,
+) -> Result<Vec<(String, PersistedRule)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT h.id, h.rule_json, h.anchors_bin
+             FROM rule_history h
+             JOIN (SELECT id, MAX(version) AS version FROM rule_history
+                   WHERE version > ?1 AND version <= ?2 GROUP BY id) latest
+               ON h.id = latest.id AND h.version = latest.version",
+        )
+        .map_err(|e| format!("Prepare failed reading history: {}", e))?;
+
+    let collected: Result<Vec<_>, _> = stmt
+        .query_map(params![after as i64, upto as i64], |row| {
+            let id: String = row.get(0)?;
+            let rule_json: Option<String> = row.get(1)?;
+            let anchors_bin: Option<Vec<u8>> = row.get(2)?;
+            Ok((id, rule_json.zip(anchors_bin)))
+        })
+        .map_err(|e| format!("Query failed reading history: {}", e))?
+        .collect();
+
+    collected.map_err(|e| format!("Row collection failed reading history: {}", e))
+}
+
+fn list_quarantined(conn: &Connection) -> Result<Vec<QuarantinedRule>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, tenant_id, layer, priority, rule_json, anchors_bin, reason, quarantined_at_ms
+             FROM rule_quarantine ORDER BY id",
+        )
+        .map_err(|e| format!("Prepare failed listing quarantine: {}", e))?;
+
+    let collected: Result<Vec<_>, _> = stmt
+        .query_map([], |row| {
+            Ok(QuarantinedRule {
+                row: RuleRow {
+                    rule_id: row.get(0)?,
+                    tenant_id: row.get(1)?,
+                    layer: row.get(2)?,
+                    priority: row.get(3)?,
+                    rule_json: row.get(4)?,
+                    anchors_bin: row.get(5)?,
+                },
+                reason: row.get(6)?,
+                quarantined_at_ms: row.get::<_, i64>(7)? as u64,
+            })
+        })
+        .map_err(|e| format!("Query failed listing quarantine: {}", e))?
+        .collect();
+
+    collected.map_err(|e| format!("Row collection failed listing quarantine: {}", e))
+}
+
+fn list_agent_labels(conn: &Connection) -> Result<Vec<AgentLabels>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT tenant_id, agent_id, labels_json, updated_at_ms
+             FROM agent_labels ORDER BY tenant_id, agent_id",
+        )
+        .map_err(|e| format!("Prepare failed listing agent labels: {}", e))?;
+
+    let collected: Result<Vec<_>, _> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)? as u64,
+            ))
+        })
+        .map_err(|e| format!("Query failed listing agent labels: {}", e))?
+        .collect();
+
+    collected
+        .map_err(|e| format!("Row collection failed listing agent labels: {}", e))?
+        .into_iter()
+        .map(|(tenant_id, agent_id, labels_json, updated_at_ms)| {
+            let labels = serde_json::from_str(&labels_json).map_err(|e| {
+                format!("Invalid labels for agent {}/{}: {}", tenant_id, agent_id, e)
+            })?;
+            Ok(AgentLabels {
+                tenant_id,
+                agent_id,
+                labels,
+                updated_at_ms,
+            })
+        })
+        .collect()
+}
+
+fn signing_sequence(conn: &Connection, key_id: &str) -> Result<u64, String> {
+    let sequence: Option<i64> = conn
+        .query_row(
+            "SELECT MAX(sequence) FROM signing_sequences WHERE key_id = ?1",
+            params![key_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to read sequence of signing key {}: {}", key_id, e))?;
+    Ok(sequence.unwrap_or(0) as u64)
+}
+
+// ================================================================================================
+// DATA MIGRATIONS
+// ================================================================================================
+
+/// Seeds the history from existing rows if it is empty.
+///
+/// Databases written before history was kept get their current rules recorded as version 1.
+fn bootstrap_history(conn: &Connection) -> Result<(), String> {
+    if latest_version(conn)? > 0 {
+        return Ok(());
+    }
+
+    let existing: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM rules WHERE status = 'active'",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to count rules: {}", e))?;
+    if existing == 0 {
+        return Ok(());
+    }
+
+    conn.execute(
+        "INSERT INTO rule_history (version, id, rule_json, anchors_bin)
+         SELECT 1, id, rule_json, anchors_bin FROM rules WHERE status = 'active'",
+        [],
+    )
+    .map_err(|e| format!("Failed to seed rule history: {}", e))?;
+    record_version(
+        conn,
+        1,
+        "baseline",
+        "Rules present before history was recorded",
+    )
+}
+
+/// Rewrites rows persisted before the AARM policy fields were part of `rule_json`.
+///
+/// `policy_type` and `weights` are recovered from the Management Plane params when
+/// present; `drift_threshold` and `modification_spec` were never stored and fall back
+/// to their defaults. Rows that cannot be parsed are left untouched for rebuild to skip.
+fn upgrade_legacy_rule_json(conn: &Connection) -> Result<(), String> {
+    let rows: Vec<(String, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT id, rule_json FROM rules")
+            .map_err(|e| format!("Prepare failed during legacy upgrade: {}", e))?;
+        let collected: Result<Vec<_>, _> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Query failed during legacy upgrade: {}", e))?
+            .collect();
+        collected.map_err(|e| format!("Row collection failed during legacy upgrade: {}", e))?
+    };
+
+    for (id, rule_json) in rows {
+        let Ok(raw) = serde_json::from_str::<Value>(&rule_json) else {
+            continue;
+        };
+        if raw.get("policy_type").is_some() {
+            continue;
+        }
+        let Ok(mut metadata) = serde_json::from_value::<RuleMetadata>(raw) else {
+            continue;
+        };
+
+        if let Some(policy_type) = metadata.params.get("policy_type").and_then(Value::as_str) {
+            metadata.policy_type = PolicyType::from(policy_type);
+        }
+        if let Some(weights) = legacy_slice_weights(&metadata.params) {
+            metadata.slice_weights = weights;
+        }
+
+        let upgraded = serde_json::to_string(&metadata)
+            .map_err(|e| format!("Failed to serialize upgraded rule {}: {}", id, e))?;
+        conn.execute(
+            "UPDATE rules SET rule_json = ?1 WHERE id = ?2",
+            params![upgraded, id],
+        )
+        .map_err(|e| format!("SQLite legacy upgrade failed for rule {}: {}", id, e))?;
+    }
+
+    Ok(())
+}
+
+/// Re-encodes fixed-size anchor rows (current and history) in the compact format.
+///
+/// Anchors are kept at full precision. Rows that do not decode are left untouched for
+/// rebuild to quarantine.
+fn compact_legacy_anchors(conn: &Connection) -> Result<(), String> {
+    for table in ["rules", "rule_history"] {
+        let rows: Vec<(i64, Vec<u8>)> = {
+            let mut stmt = conn
+                .prepare(&format!(
+                    "SELECT rowid, anchors_bin FROM {} WHERE length(anchors_bin) = ?1",
+                    table
+                ))
+                .map_err(|e| format!("Prepare failed compacting {} anchors: {}", table, e))?;
+            let collected: Result<Vec<_>, _> = stmt
+                .query_map(params![RuleVector::ENCODED_LEN as i64], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .map_err(|e| format!("Query failed compacting {} anchors: {}", table, e))?
+                .collect();
+            collected
+                .map_err(|e| format!("Row collection failed compacting {} anchors: {}", table, e))?
+        };
+
+        for (rowid, anchors_bin) in rows {
+            let Ok(vector) = RuleVector::decode(&anchors_bin) else {
+                continue;
+            };
+            conn.execute(
+                &format!("UPDATE {} SET anchors_bin = ?1 WHERE rowid = ?2", table),
+                params![vector.encode(AnchorPrecision::F32), rowid],
+            )
+            .map_err(|e| {
+                format!(
+                    "SQLite anchor compaction failed in {} row {}: {}",
+                    table, rowid, e
+                )
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds the owning tenant to every history entry, taken from its rule JSON.
+///
+/// Removals have no JSON and belong to the tenant of the entry they removed.
+fn add_history_tenant(conn: &Connection) -> Result<(), String> {
+    let has_column: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM pragma_table_info('rule_history') WHERE name = 'tenant_id')",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to inspect rule_history: {}", e))?;
+    if !has_column {
+        conn.execute("ALTER TABLE rule_history ADD COLUMN tenant_id TEXT", [])
+            .map_err(|e| format!("Failed to add rule_history.tenant_id: {}", e))?;
+    }
+
+    conn.execute_batch(
+        "
+UPDATE rule_history SET tenant_id = json_extract(rule_json, '$.scope.tenant_id')
+WHERE tenant_id IS NULL AND json_valid(rule_json);
+
+UPDATE rule_history SET tenant_id = COALESCE(
+    (SELECT earlier.tenant_id FROM rule_history earlier
+     WHERE earlier.id = rule_history.id AND earlier.version < rule_history.version
+       AND earlier.tenant_id IS NOT NULL
+     ORDER BY earlier.version DESC LIMIT 1),
+    'default')
+WHERE tenant_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_rule_history_tenant ON rule_history (tenant_id, version);
+CREATE INDEX IF NOT EXISTS idx_rule_history_id ON rule_history (id, version);
+",
+    )
+    .map_err(|e| format!("Failed to backfill history tenants: {}", e))
+}
+
+/// Parses the Management Plane `weights` param (a JSON-encoded object) into slice order.
+fn legacy_slice_weights(params: &Value) -> Option<[f32; 4]> {
+    let weights: Value = serde_json::from_str(params.get("weights")?.as_str()?).ok()?;
+    let slot = |name: &str| weights.get(name).and_then(Value::as_f64).map(|w| w as f32);
+    Some([
+        slot("action")?,
+        slot("resource")?,
+        slot("data")?,
+        slot("risk")?,
+    ])
+}
@@ -0,0 +1,402 @@
+//! # Warm Storage
+//!
+//! Memory-mapped, fixed-record file of active rule anchors.
+//!
+//! Sits between the hot cache and cold storage: a hot-cache miss reads the anchors
+//! straight out of the mapping instead of querying SQLite, and the OS decides which
+//! pages stay resident, so a large rule set does not need every anchor on the heap.
+//!
+//! The file is a snapshot of the active rules at one bridge version and is never
+//! edited in place. Rules changed after the snapshot are marked stale and fall through
+//! to cold storage until the file is rebuilt. Rebuilds write a temporary file and
+//! rename it over the old one, so readers (including other processes) only ever map a
+//! complete file.
+//!
+//! Layout (all integers little-endian):
+//!
+//! ```text
+//! header (64 bytes): magic "TUPLWARM" | format u32 | record_len u32 | record_count u64 |
+//!                    version u64 | checksum u64 | built_at_ms u64 | reserved
+//! record:            id_len u16 | rule_id (254 bytes, zero padded) | RuleVector::to_le_bytes
+//! ```
+//!
+//! Anchors are stored in the fixed-size layout whatever encoding cold storage uses, so
+//! every record has the same length. The checksum is FNV-1a 64 over every record and
+//! is verified when the file is opened.
+
+use crate::rule_vector::RuleVector;
+use crate::types::now_ms;
+use memmap2::Mmap;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"TUPLWARM";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 64;
+
+/// Longest rule_id a record can hold. Rules with longer IDs are left out of the file.
+pub const MAX_WARM_RULE_ID_LEN: usize = 254;
+
+const ID_FIELD_LEN: usize = 2 + MAX_WARM_RULE_ID_LEN;
+const RECORD_LEN: usize = ID_FIELD_LEN + RuleVector::ENCODED_LEN;
+
+/// Parsed file header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Header {
+    record_count: u64,
+    version: u64,
+    checksum: u64,
+    built_at_ms: u64,
+}
+
+impl Header {
+    fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut out = [0u8; HEADER_LEN];
+        out[0..8].copy_from_slice(MAGIC);
+        out[8..12].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out[12..16].copy_from_slice(&(RECORD_LEN as u32).to_le_bytes());
+        out[16..24].copy_from_slice(&self.record_count.to_le_bytes());
+        out[24..32].copy_from_slice(&self.version.to_le_bytes());
+        out[32..40].copy_from_slice(&self.checksum.to_le_bytes());
+        out[40..48].copy_from_slice(&self.built_at_ms.to_le_bytes());
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < HEADER_LEN {
+            return Err(format!(
+                "file is {} bytes, shorter than the header",
+                bytes.len()
+            ));
+        }
+        if &bytes[0..8] != MAGIC {
+            return Err("bad magic".to_string());
+        }
+        let format = read_u32(&bytes[8..12]);
+        if format != FORMAT_VERSION {
+            return Err(format!(
+                "format version {} is not supported (expected {})",
+                format, FORMAT_VERSION
+            ));
+        }
+        let record_len = read_u32(&bytes[12..16]) as usize;
+        if record_len != RECORD_LEN {
+            return Err(format!(
+                "record length {} does not match {}",
+                record_len, RECORD_LEN
+            ));
+        }
+        Ok(Header {
+            record_count: read_u64(&bytes[16..24]),
+            version: read_u64(&bytes[24..32]),
+            checksum: read_u64(&bytes[32..40]),
+            built_at_ms: read_u64(&bytes[40..48]),
+        })
+    }
+}
+
+/// Read-only view of a warm storage file.
+#[derive(Debug)]
+pub struct WarmStore {
+    path: PathBuf,
+    mmap: Mmap,
+    header: Header,
+    /// rule_id → record index
+    slots: HashMap<String, usize>,
+    /// Rules changed since the snapshot; their records must not be served
+    stale: HashSet<String>,
+}
+
+impl WarmStore {
+    /// Writes a snapshot of `rows` (rule_id, encoded anchors) taken at `version` and opens it.
+    ///
+    /// Rows whose ID is too long or whose anchors are not a valid encoding are skipped.
+    pub fn build<'a>(
+        path: &Path,
+        version: u64,
+        rows: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+    ) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                format!(
+                    "Failed to create warm storage directory {}: {}",
+                    parent.display(),
+                    e
+                )
+            })?;
+        }
+
+        let tmp_path = PathBuf::from(format!("{}.{}.tmp", path.display(), std::process::id()));
+        let written = write_snapshot(&tmp_path, version, rows).and_then(|count| {
+            fs::rename(&tmp_path, path).map_err(|e| {
+                format!("Failed to move warm storage into {}: {}", path.display(), e)
+            })?;
+            Ok(count)
+        });
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        let count = written?;
+
+        log::info!(
+            "Built warm storage {} with {} rules at version {}",
+            path.display(),
+            count,
+            version
+        );
+        Self::open(path)
+    }
+
+    /// Maps an existing file, verifying its header and checksum.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Failed to open warm storage {}: {}", path.display(), e))?;
+        // SAFETY: the file is only ever replaced by rename, never modified in place, so
+        // the mapped contents cannot change underneath us.
+        let mmap = unsafe { Mmap::map(&file) }
+            .map_err(|e| format!("Failed to map warm storage {}: {}", path.display(), e))?;
+
+        let corrupt =
+            |reason: String| format!("Warm storage {} is corrupt: {}", path.display(), reason);
+        let header = Header::decode(&mmap).map_err(corrupt)?;
+
+        let expected_len = (header.record_count as usize)
+            .checked_mul(RECORD_LEN)
+            .and_then(|len| len.checked_add(HEADER_LEN));
+        if expected_len != Some(mmap.len()) {
+            return Err(corrupt(format!(
+                "{} bytes for {} records",
+                mmap.len(),
+                header.record_count
+            )));
+        }
+        if fnv1a(&mmap[HEADER_LEN..]) != header.checksum {
+            return Err(corrupt("checksum mismatch".to_string()));
+        }
+
+        let mut slots = HashMap::with_capacity(header.record_count as usize);
+        for (slot, record) in mmap[HEADER_LEN..].chunks_exact(RECORD_LEN).enumerate() {
+            let id_len = u16::from_le_bytes([record[0], record[1]]) as usize;
+            if id_len > MAX_WARM_RULE_ID_LEN {
+                return Err(corrupt(format!("record {} has id length {}", slot, id_len)));
+            }
+            let rule_id = std::str::from_utf8(&record[2..2 + id_len])
+                .map_err(|_| corrupt(format!("record {} has a non-UTF-8 id", slot)))?;
+            slots.insert(rule_id.to_string(), slot);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            mmap,
+            header,
+            slots,
+            stale: HashSet::new(),
+        })
+    }
+
+    /// Returns the anchors recorded for `rule_id`, unless missing or stale.
+    pub fn get(&self, rule_id: &str) -> Option<RuleVector> {
+        if self.stale.contains(rule_id) {
+            return None;
+        }
+        let slot = *self.slots.get(rule_id)?;
+        let start = HEADER_LEN + slot * RECORD_LEN + ID_FIELD_LEN;
+        match RuleVector::from_le_bytes(&self.mmap[start..start + RuleVector::ENCODED_LEN]) {
+            Ok(anchors) => Some(anchors),
+            Err(e) => {
+                log::warn!("Invalid warm anchors for rule {}: {}", rule_id, e);
+                None
+            }
+        }
+    }
+
+    /// Stops serving `rule_id` from this snapshot.
+    pub fn invalidate(&mut self, rule_id: &str) {
+        if self.slots.contains_key(rule_id) {
+            self.stale.insert(rule_id.to_string());
+        }
+    }
+
+    /// Bridge version the snapshot was taken at.
+    pub fn version(&self) -> u64 {
+        self.header.version
+    }
+
+    /// When the snapshot was written.
+    pub fn built_at_ms(&self) -> u64 {
+        self.header.built_at_ms
+    }
+
+    /// Number of records in the file.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true when the file holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Number of records invalidated since the snapshot.
+    pub fn stale_count(&self) -> usize {
+        self.stale.len()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Writes a complete snapshot to `path`. Returns the number of records written.
+fn write_snapshot<'a>(
+    path: &Path,
+    version: u64,
+    rows: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> Result<u64, String> {
+    let io_err =
+        |e: std::io::Error| format!("Failed to write warm storage {}: {}", path.display(), e);
+
+    let file = File::create(path).map_err(io_err)?;
+    let mut out = BufWriter::new(file);
+    out.write_all(&[0u8; HEADER_LEN]).map_err(io_err)?;
+
+    let mut checksum = Fnv1a::new();
+    let mut record_count = 0u64;
+    for (rule_id, anchors_bin) in rows {
+        if rule_id.len() > MAX_WARM_RULE_ID_LEN {
+            log::warn!(
+                "Rule {} has too long an id for warm storage; skipping",
+                rule_id
+            );
+            continue;
+        }
+        let Ok(vector) = RuleVector::decode(anchors_bin) else {
+            log::warn!(
+                "Rule {} has invalid anchors; skipping warm storage",
+                rule_id
+            );
+            continue;
+        };
+
+        let mut id_field = [0u8; ID_FIELD_LEN];
+        id_field[0..2].copy_from_slice(&(rule_id.len() as u16).to_le_bytes());
+        id_field[2..2 + rule_id.len()].copy_from_slice(rule_id.as_bytes());
+
+        for part in [&id_field[..], &vector.to_le_bytes()] {
+            checksum.update(part);
+            out.write_all(part).map_err(io_err)?;
+        }
+        record_count += 1;
+    }
+
+    let header = Header {
+        record_count,
+        version,
+        checksum: checksum.finish(),
+        built_at_ms: now_ms(),
+    };
+    out.seek(SeekFrom::Start(0)).map_err(io_err)?;
+    out.write_all(&header.encode()).map_err(io_err)?;
+    let file = out.into_inner().map_err(|e| io_err(e.into_error()))?;
+    file.sync_all().map_err(io_err)?;
+    Ok(record_count)
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().expect("4-byte field"))
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().expect("8-byte field"))
+}
+
+/// Incremental FNV-1a 64-bit hash.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = Fnv1a::new();
+    hash.update(bytes);
+    hash.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchors(count: usize) -> Vec<u8> {
+        let mut vector = RuleVector {
+            action_count: count,
+            ..Default::default()
+        };
+        vector.action_anchors[0][0] = 0.5;
+        vector.to_le_bytes()
+    }
+
+    #[test]
+    fn test_build_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("warm.bin");
+        let (a, b) = (anchors(1), anchors(2));
+        let too_long = "x".repeat(MAX_WARM_RULE_ID_LEN + 1);
+
+        let rows = [
+            ("a", a.as_slice()),
+            ("b", b.as_slice()),
+            (too_long.as_str(), a.as_slice()),
+        ];
+        let mut warm = WarmStore::build(&path, 7, rows).unwrap();
+        assert_eq!(warm.version(), 7);
+        assert_eq!(warm.len(), 2);
+        assert_eq!(warm.get("b").unwrap().action_count, 2);
+        assert!(warm.get(&too_long).is_none());
+
+        warm.invalidate("b");
+        assert!(warm.get("b").is_none());
+        assert_eq!(warm.stale_count(), 1);
+
+        // Staleness is not persisted; a reopened file serves its snapshot again.
+        let reopened = WarmStore::open(&path).unwrap();
+        assert_eq!(reopened.get("a").unwrap().action_anchors[0][0], 0.5);
+        assert_eq!(reopened.get("b").unwrap().action_count, 2);
+    }
+
+    #[test]
+    fn test_open_rejects_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("warm.bin");
+        let a = anchors(1);
+        WarmStore::build(&path, 1, [("a", a.as_slice())]).unwrap();
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        let err = WarmStore::open(&path).unwrap_err();
+        assert!(err.contains("checksum mismatch"), "{}", err);
+
+        fs::write(&path, &bytes[..bytes.len() - 10]).unwrap();
+        assert!(WarmStore::open(&path).is_err());
+
+        fs::write(&path, b"not a warm file").unwrap();
+        assert!(WarmStore::open(&path).is_err());
+    }
+}
@@ -3,6 +3,7 @@
 //! Query and analyze enforcement sessions from hitlog files.
 
 use super::session::EnforcementSession;
+use crate::types::normalize_tenant;
 use rusqlite::{params_from_iter, Connection};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
@@ -20,7 +21,7 @@ pub struct QueryFilter {
     /// Filter by agent ID
     pub agent_id: Option<String>,
 
-    /// Filter by tenant ID
+    /// Filter by tenant ID (empty = the default tenant)
     pub tenant_id: Option<String>,
 
     /// Filter by decision (0 = BLOCK, 1 = ALLOW)
@@ -159,7 +160,7 @@ impl HitlogQuery {
         }
         if let Some(ref tenant_id) = filter.tenant_id {
             conditions.push("tenant_id = ?".into());
-            params_vec.push(Box::new(tenant_id.clone()));
+            params_vec.push(Box::new(normalize_tenant(tenant_id).to_string()));
         }
         if let Some(decision) = filter.decision {
             conditions.push("final_decision = ?".into());
@@ -299,7 +300,8 @@ impl HitlogQuery {
         }
 
         if let Some(ref tenant_id) = filter.tenant_id {
-            if session.tenant_id.as_ref() != Some(tenant_id) {
+            let session_tenant = normalize_tenant(session.tenant_id.as_deref().unwrap_or_default());
+            if session_tenant != normalize_tenant(tenant_id) {
                 return false;
             }
         }
@@ -82,12 +82,18 @@ pub enum SessionEvent {
     RulesQueried {
         timestamp_us: u64,
         layer: String,
+        /// Agent identity the rules were selected for (None = global rules only)
+        agent_id: Option<String>,
         rule_count: usize,
         query_duration_us: u64,
     },
 
     /// No rules found (fail-closed)
-    NoRulesFound { timestamp_us: u64, layer: String },
+    NoRulesFound {
+        timestamp_us: u64,
+        layer: String,
+        agent_id: Option<String>,
+    },
 
     /// Rule evaluation started
     RuleEvaluationStarted {
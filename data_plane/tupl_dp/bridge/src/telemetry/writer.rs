@@ -5,6 +5,8 @@
 
 use super::recorder::TelemetryConfig;
 use super::session::EnforcementSession;
+use crate::storage::{migrate, Migration};
+use crate::types::normalize_tenant;
 use parking_lot::Mutex;
 use rusqlite::{params, Connection};
 use std::fs::{self, File, OpenOptions};
@@ -12,6 +14,40 @@ use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Component name for the hitlog database in `schema_version`.
+const HITLOG_SCHEMA_COMPONENT: &str = "hitlogs";
+
+/// Hitlog database migrations, applied in order when the writer starts.
+const HITLOG_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "hitlogs table",
+        sql: "
+CREATE TABLE IF NOT EXISTS hitlogs (
+    session_id     TEXT PRIMARY KEY,
+    tenant_id      TEXT,
+    agent_id       TEXT,
+    layer          TEXT,
+    timestamp_ms   INTEGER,
+    final_decision INTEGER,
+    duration_us    INTEGER,
+    session_json   TEXT NOT NULL
+);
+",
+        apply: None,
+    },
+    Migration {
+        version: 2,
+        description: "sessions without a tenant belong to the default tenant",
+        sql: "
+UPDATE hitlogs SET tenant_id = 'default' WHERE tenant_id IS NULL OR tenant_id = '';
+
+CREATE INDEX IF NOT EXISTS idx_hitlogs_tenant ON hitlogs (tenant_id, timestamp_ms);
+",
+        apply: None,
+    },
+];
+
 /// Hitlog writer configuration
 #[derive(Debug, Clone)]
 pub struct HitlogConfig {
@@ -116,27 +152,10 @@ impl HitlogWriter {
                     }
                 }
 
-                match Connection::open(&p) {
-                    Ok(conn) => {
-                        if let Err(e) = conn.execute(
-                            "CREATE TABLE IF NOT EXISTS hitlogs (
-                                session_id TEXT PRIMARY KEY,
-                                tenant_id TEXT,
-                                agent_id TEXT,
-                                layer TEXT,
-                                timestamp_ms INTEGER,
-                                final_decision INTEGER,
-                                duration_us INTEGER,
-                                session_json TEXT NOT NULL
-                            )",
-                            [],
-                        ) {
-                            return Err(format!("Failed to create hitlogs table: {}", e));
-                        }
-                        Ok(Mutex::new(conn))
-                    }
-                    Err(e) => Err(format!("Failed to open sqlite db: {}", e)),
-                }
+                let mut conn =
+                    Connection::open(&p).map_err(|e| format!("Failed to open sqlite db: {}", e))?;
+                migrate(&mut conn, HITLOG_SCHEMA_COMPONENT, HITLOG_MIGRATIONS)?;
+                Ok(Mutex::new(conn))
             })
             .transpose()?;
 
@@ -197,7 +216,7 @@ impl HitlogWriter {
         // Also persist to SQLite when configured
         if let Some(ref sqlite_mutex) = self.sqlite {
             let conn_guard = sqlite_mutex.lock();
-            let tenant = session.tenant_id.clone().unwrap_or_default();
+            let tenant = normalize_tenant(session.tenant_id.as_deref().unwrap_or_default());
             let agent = session.agent_id.clone().unwrap_or_default();
             let layer = session.layer.clone();
             let ts = session.timestamp_ms as i64;
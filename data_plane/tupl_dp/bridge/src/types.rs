@@ -11,7 +11,10 @@ use std::collections::HashMap;
 use std::fmt;
 
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::Value;
+
+use crate::api_types::IntentEvent;
+use crate::families::EvaluationContext;
 
 // ================================================================================================
 // AARM POLICY TYPE
@@ -50,6 +53,18 @@ impl From<&str> for PolicyType {
     }
 }
 
+impl PolicyType {
+    /// Wire name, the inverse of `From<&str>`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyType::Forbidden => "forbidden",
+            PolicyType::ContextDeny => "context_deny",
+            PolicyType::ContextAllow => "context_allow",
+            PolicyType::ContextDefer => "context_defer",
+        }
+    }
+}
+
 // ================================================================================================
 // AARM DECISION
 // ================================================================================================
@@ -108,58 +123,119 @@ pub struct EnforcementDecision {
 ///
 /// This trait provides a unified interface for accessing rule metadata.
 pub trait RuleInstance: Send + Sync {
+    /// Metadata the rule was built from and is persisted as
+    fn metadata(&self) -> &RuleMetadata;
+
+    /// Registered family this rule belongs to (its `rule_type`)
+    fn family_id(&self) -> &str;
+
     /// Unique identifier for this rule instance
-    fn rule_id(&self) -> &str;
+    fn rule_id(&self) -> &str {
+        &self.metadata().rule_id
+    }
 
     /// Priority value (higher = evaluated first)
-    fn priority(&self) -> u32;
+    fn priority(&self) -> u32 {
+        self.metadata().priority
+    }
 
     /// Scope definition for this rule
-    fn scope(&self) -> &RuleScope;
+    fn scope(&self) -> &RuleScope {
+        &self.metadata().scope
+    }
 
     /// Optional layer metadata hint (None = no layer metadata)
     fn layer(&self) -> Option<&str> {
-        None
+        self.metadata().layer.as_deref()
     }
 
     /// Timestamp when rule was created
-    fn created_at(&self) -> u64;
+    fn created_at(&self) -> u64 {
+        self.metadata().created_at_ms
+    }
 
     /// Optional description for this rule
     fn description(&self) -> Option<&str> {
-        None
+        self.metadata().description.as_deref()
     }
 
     /// Whether this rule is currently enabled
     fn is_enabled(&self) -> bool {
-        true
+        self.metadata().enabled
     }
 
-    /// Returns a Management Plane payload for encoding APIs (default empty for unsupported families)
+    /// Returns a Management Plane payload for encoding APIs (the rule's params)
     fn management_plane_payload(&self) -> Value {
-        json!({})
+        self.metadata().params.clone()
     }
 
     /// AARM policy classification for this rule.
-    /// Defaults to ContextAllow (preserves existing behaviour for rules that do not set it).
     fn policy_type(&self) -> PolicyType {
-        PolicyType::default()
+        self.metadata().policy_type.clone()
     }
 
     /// Drift threshold for this rule; 0.0 means drift enforcement is disabled.
     fn drift_threshold(&self) -> f32 {
-        0.0
+        self.metadata().drift_threshold
     }
 
     /// Optional JSON patch applied when the decision is MODIFY.
     fn modification_spec(&self) -> Option<&serde_json::Value> {
-        None
+        self.metadata().modification_spec.as_ref()
     }
 
     /// Per-slice weights [action, resource, data, risk] used for weighted-average scoring.
     fn slice_weights(&self) -> [f32; 4] {
-        [0.25, 0.25, 0.25, 0.25]
+        self.metadata().slice_weights
+    }
+
+    /// Start of the validity window in epoch ms (None = valid from creation).
+    fn not_before(&self) -> Option<u64> {
+        self.metadata().not_before
+    }
+
+    /// End of the validity window in epoch ms, exclusive (None = never expires).
+    fn expires_at(&self) -> Option<u64> {
+        self.metadata().expires_at
     }
+
+    /// Id of the trusted key whose signature covered this rule's install (None = unsigned).
+    fn signed_by(&self) -> Option<&str> {
+        self.metadata().signed_by.as_deref()
+    }
+
+    /// Whether `now_ms` falls inside this rule's validity window.
+    fn is_effective_at(&self, now_ms: u64) -> bool {
+        self.not_before().is_none_or(|start| now_ms >= start)
+            && self.expires_at().is_none_or(|end| now_ms < end)
+    }
+
+    /// Hostnames `evaluate` needs looked up for this intent; the engine resolves them
+    /// into `EvaluationContext::dns` first.
+    fn dns_lookups(&self, _intent: &IntentEvent) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Evaluates the rule against an intent without anchors.
+    ///
+    /// None for families matched semantically against their anchors.
+    fn evaluate(&self, _intent: &IntentEvent, _context: &EvaluationContext) -> Option<RuleMatch> {
+        None
+    }
+
+    /// Decision a match yields in the FORBIDDEN and CONTEXT_DENY passes.
+    fn deny_decision(&self) -> Decision {
+        Decision::Deny
+    }
+}
+
+/// Outcome of evaluating a deterministic rule against an intent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// Whether the rule matches, in the sense its policy type acts on
+    pub matched: bool,
+    /// Why it matched or not (e.g. the violated constraint)
+    pub detail: String,
 }
 
 impl fmt::Debug for dyn RuleInstance {
@@ -178,16 +254,37 @@ impl fmt::Debug for dyn RuleInstance {
 // SCOPE DEFINITION
 // ================================================================================================
 
+/// Tenant assigned to rules and requests that do not name one.
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Maps an empty tenant id to `DEFAULT_TENANT`.
+pub fn normalize_tenant(tenant_id: &str) -> &str {
+    if tenant_id.is_empty() {
+        DEFAULT_TENANT
+    } else {
+        tenant_id
+    }
+}
+
+fn default_tenant() -> String {
+    DEFAULT_TENANT.to_string()
+}
+
 /// Defines the scope/applicability of a rule
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RuleScope {
+    /// Tenant that owns this rule; it is never applied to another tenant's requests
+    #[serde(default = "default_tenant")]
+    pub tenant_id: String,
+
     /// Agent IDs this rule applies to (empty = all agents)
     pub agent_ids: Vec<String>,
 
-    /// Tags for additional scoping
+    /// Selector tags; every one must match a label of the request (empty = no selector)
+    #[serde(default)]
     pub tags: HashMap<String, String>,
 
-    /// Whether this is a global rule (applies to all)
+    /// Whether this is a global rule (applies to all agents of the tenant)
     pub is_global: bool,
 }
 
@@ -195,6 +292,7 @@ impl RuleScope {
     /// Creates a new global scope
     pub fn global() -> Self {
         RuleScope {
+            tenant_id: default_tenant(),
             agent_ids: vec![],
             tags: HashMap::new(),
             is_global: true,
@@ -204,6 +302,7 @@ impl RuleScope {
     /// Creates a scope for specific agents
     pub fn for_agents(agent_ids: Vec<String>) -> Self {
         RuleScope {
+            tenant_id: default_tenant(),
             agent_ids,
             tags: HashMap::new(),
             is_global: false,
@@ -213,22 +312,57 @@ impl RuleScope {
     /// Creates a scope for a single agent
     pub fn for_agent(agent_id: String) -> Self {
         RuleScope {
+            tenant_id: default_tenant(),
             agent_ids: vec![agent_id],
             tags: HashMap::new(),
             is_global: false,
         }
     }
 
+    /// Moves this scope to a tenant (empty = `DEFAULT_TENANT`)
+    pub fn with_tenant(mut self, tenant_id: &str) -> Self {
+        self.tenant_id = normalize_tenant(tenant_id).to_string();
+        self
+    }
+
+    /// Returns the owning tenant
+    pub fn tenant(&self) -> &str {
+        normalize_tenant(&self.tenant_id)
+    }
+
+    /// Checks if this scope belongs to a given tenant (empty = `DEFAULT_TENANT`)
+    pub fn belongs_to(&self, tenant_id: &str) -> bool {
+        self.tenant() == normalize_tenant(tenant_id)
+    }
+
     /// Checks if this scope applies to a given agent
     pub fn applies_to(&self, agent_id: &str) -> bool {
         self.is_global || self.agent_ids.iter().any(|id| id == agent_id)
     }
 
+    /// Checks if this scope names the agent explicitly (global scopes do not)
+    pub fn is_scoped_to(&self, agent_id: &str) -> bool {
+        !self.is_global && self.agent_ids.iter().any(|id| id == agent_id)
+    }
+
     /// Adds a tag to this scope
     pub fn with_tag(mut self, key: String, value: String) -> Self {
         self.tags.insert(key, value);
         self
     }
+
+    /// Adds selector tags to this scope
+    pub fn with_tags(mut self, tags: HashMap<String, String>) -> Self {
+        self.tags.extend(tags);
+        self
+    }
+
+    /// Checks if every selector tag matches a label (true when there are no tags)
+    pub fn selects(&self, labels: &HashMap<String, String>) -> bool {
+        self.tags
+            .iter()
+            .all(|(key, value)| labels.get(key) == Some(value))
+    }
 }
 
 impl Default for RuleScope {
@@ -242,6 +376,9 @@ impl Default for RuleScope {
 // ================================================================================================
 
 /// Serializable rule metadata for cold storage persistence.
+///
+/// The AARM policy fields default when absent so that rows written before they
+/// were persisted still deserialize; see `Bridge::new` for the legacy upgrade.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleMetadata {
     pub rule_id: String,
@@ -252,23 +389,24 @@ pub struct RuleMetadata {
     pub enabled: bool,
     pub description: Option<String>,
     pub params: Value,
+    #[serde(default)]
+    pub policy_type: PolicyType,
+    #[serde(default)]
+    pub drift_threshold: f32,
+    #[serde(default)]
+    pub modification_spec: Option<Value>,
+    #[serde(default = "default_slice_weights")]
+    pub slice_weights: [f32; 4],
+    #[serde(default)]
+    pub not_before: Option<u64>,
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_by: Option<String>,
 }
 
-impl RuleMetadata {
-    pub fn from_rule(rule: &dyn RuleInstance) -> Self {
-        Self {
-            rule_id: rule.rule_id().to_string(),
-            priority: rule.priority(),
-            scope: rule.scope().clone(),
-            layer: rule.layer().map(|layer| layer.to_string()),
-            created_at_ms: rule.created_at(),
-            enabled: rule.is_enabled(),
-            description: rule
-                .description()
-                .map(|description| description.to_string()),
-            params: rule.management_plane_payload(),
-        }
-    }
+fn default_slice_weights() -> [f32; 4] {
+    [0.25, 0.25, 0.25, 0.25]
 }
 
 // ================================================================================================
@@ -333,8 +471,41 @@ impl Default for NetworkProtocol {
     }
 }
 
+impl NetworkProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NetworkProtocol::TCP => "tcp",
+            NetworkProtocol::UDP => "udp",
+            NetworkProtocol::HTTP => "http",
+            NetworkProtocol::HTTPS => "https",
+        }
+    }
+
+    /// Parses a protocol name as written in rules (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "tcp" => Some(NetworkProtocol::TCP),
+            "udp" => Some(NetworkProtocol::UDP),
+            "http" => Some(NetworkProtocol::HTTP),
+            "https" => Some(NetworkProtocol::HTTPS),
+            _ => None,
+        }
+    }
+
+    /// Protocol a URL scheme runs over; schemes other than web ones and `udp` are TCP.
+    pub fn from_scheme(scheme: &str) -> Self {
+        match scheme.to_ascii_lowercase().as_str() {
+            "http" | "ws" => NetworkProtocol::HTTP,
+            "https" | "wss" => NetworkProtocol::HTTPS,
+            "udp" => NetworkProtocol::UDP,
+            _ => NetworkProtocol::TCP,
+        }
+    }
+}
+
 /// Parameter types for tool constraint validation
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ParamType {
     String,
     Int,
@@ -342,6 +513,27 @@ pub enum ParamType {
     Bool,
 }
 
+impl ParamType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParamType::String => "string",
+            ParamType::Int => "int",
+            ParamType::Float => "float",
+            ParamType::Bool => "bool",
+        }
+    }
+
+    /// Checks if a JSON value has this type (any number is a float).
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            ParamType::String => value.is_string(),
+            ParamType::Int => value.is_i64() || value.is_u64(),
+            ParamType::Float => value.is_number(),
+            ParamType::Bool => value.is_boolean(),
+        }
+    }
+}
+
 // ================================================================================================
 // UTILITY FUNCTIONS
 // ================================================================================================
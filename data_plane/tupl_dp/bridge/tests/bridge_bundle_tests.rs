@@ -0,0 +1,144 @@
+//! Integration tests for rule bundle export and import.
+//!
+//! Tests verify:
+//! - An exported bundle reproduces rules and anchors in another bridge
+//! - Merge keeps unrelated rules, replace drops them
+//! - Dry runs and bundles with invalid rules change nothing
+
+mod common;
+
+use bridge::bridge::{Bridge, RuleSetDiff, RuleStatus};
+use bridge::bundle::{ImportMode, RuleBundle};
+use bridge::types::{RuleInstance, RuleScope, DEFAULT_TENANT};
+use common::{design_rule, marked_anchors};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str, priority: u32) -> Arc<dyn RuleInstance> {
+    design_rule(rule_id, priority, RuleScope::global())
+}
+
+fn bridge_with(rules: &[(&str, u32)]) -> Bridge {
+    let bridge = Bridge::in_memory().unwrap();
+    let batch = rules
+        .iter()
+        .map(|(rule_id, priority)| (rule(rule_id, *priority), marked_anchors(*priority as usize)))
+        .collect();
+    bridge.add_rules_batch(batch).unwrap();
+    bridge
+}
+
+fn ids(bridge: &Bridge) -> Vec<String> {
+    let mut ids: Vec<String> = bridge
+        .all_rules()
+        .iter()
+        .map(|rule| rule.rule_id().to_string())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn test_export_then_replace_reproduces_rule_set() {
+    let source = bridge_with(&[("a", 1), ("b", 2)]);
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("rules.bundle");
+    source
+        .export_bundle(DEFAULT_TENANT)
+        .unwrap()
+        .write_to_file(&path)
+        .unwrap();
+
+    let target = bridge_with(&[("a", 9), ("c", 3)]);
+    let import = target
+        .import_bundle(
+            DEFAULT_TENANT,
+            RuleBundle::read_from_file(&path).unwrap(),
+            ImportMode::Replace,
+            false,
+        )
+        .unwrap();
+
+    assert!(import.failures.is_empty());
+    assert_eq!(import.version, Some(target.version()));
+    assert_eq!(
+        import.diff,
+        RuleSetDiff {
+            added: vec!["b".to_string()],
+            removed: vec!["c".to_string()],
+            changed: vec!["a".to_string()],
+            unchanged: 0,
+        }
+    );
+    assert_eq!(ids(&target), vec!["a", "b"]);
+    assert_eq!(target.get_rule("a").unwrap().priority(), 1);
+    assert_eq!(
+        target.get_rule_anchors("a").unwrap().action_anchors[0][0],
+        1.0
+    );
+    assert_eq!(target.list_versions(1).unwrap()[0].kind, "import");
+}
+
+#[test]
+fn test_merge_keeps_other_rules_and_reactivates() {
+    let source = bridge_with(&[("a", 1), ("b", 2)]);
+    let target = bridge_with(&[("b", 2), ("c", 3)]);
+    target
+        .set_rule_status(&["b".to_string()], RuleStatus::Disabled)
+        .unwrap();
+
+    let import = target
+        .import_bundle(
+            DEFAULT_TENANT,
+            source.export_bundle(DEFAULT_TENANT).unwrap(),
+            ImportMode::Merge,
+            false,
+        )
+        .unwrap();
+
+    assert_eq!(import.diff.added, vec!["a", "b"]);
+    assert!(import.diff.removed.is_empty());
+    assert_eq!(import.diff.unchanged, 1);
+    assert_eq!(ids(&target), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_dry_run_and_invalid_bundles_change_nothing() {
+    let source = bridge_with(&[("a", 1), ("b", 2)]);
+    let target = bridge_with(&[("c", 3)]);
+    let version = target.version();
+
+    let dry_run = target
+        .import_bundle(
+            DEFAULT_TENANT,
+            source.export_bundle(DEFAULT_TENANT).unwrap(),
+            ImportMode::Replace,
+            true,
+        )
+        .unwrap();
+    assert_eq!(dry_run.version, None);
+    assert_eq!(dry_run.diff.added, vec!["a", "b"]);
+    assert_eq!(dry_run.diff.removed, vec!["c"]);
+    assert_eq!(target.version(), version);
+    assert_eq!(ids(&target), vec!["c"]);
+
+    // Exported rules are ordered by rule_id: corrupt "b" and repeat "a".
+    let mut bundle = source.export_bundle(DEFAULT_TENANT).unwrap();
+    bundle.rules[1].anchors.truncate(10);
+    let duplicate = bundle.rules[0].clone();
+    bundle.rules.push(duplicate);
+
+    let import = target
+        .import_bundle(DEFAULT_TENANT, bundle, ImportMode::Merge, false)
+        .unwrap();
+    let mut failures: Vec<(&str, &str)> = import
+        .failures
+        .iter()
+        .map(|failure| (failure.rule_id.as_str(), failure.stage))
+        .collect();
+    failures.sort();
+    assert_eq!(failures, vec![("a", "metadata"), ("b", "anchors")]);
+    assert_eq!(import.version, None);
+    assert_eq!(target.version(), version);
+    assert_eq!(ids(&target), vec!["c"]);
+}
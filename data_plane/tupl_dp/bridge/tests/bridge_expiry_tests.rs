@@ -0,0 +1,109 @@
+//! Integration tests for rule validity windows (not_before / expires_at).
+//!
+//! Tests verify:
+//! - Rules report whether they are effective at a given time
+//! - Validity windows survive a restart
+//! - Expired rules are archived in a single "expire" version and stay listable
+
+mod common;
+
+use bridge::bridge::{Bridge, RuleStatus};
+use bridge::families::DesignBoundaryRule;
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope};
+use common::open_bridge;
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+const NOW: u64 = 1_700_000_000_000;
+
+fn windowed_rule(
+    rule_id: &str,
+    not_before: Option<u64>,
+    expires_at: Option<u64>,
+) -> Arc<dyn RuleInstance> {
+    Arc::new(
+        DesignBoundaryRule::new(
+            rule_id.to_string(),
+            10,
+            RuleScope::for_agent("agent-1".to_string()),
+            Some("L4".to_string()),
+            NOW - 60_000,
+            true,
+            None,
+            json!({"rule_type": "design_boundary"}),
+        )
+        .with_validity(not_before, expires_at),
+    )
+}
+
+fn install(bridge: &Bridge, rule: Arc<dyn RuleInstance>) {
+    let mut anchors = RuleVector::default();
+    anchors.action_anchors[0][0] = 0.5;
+    anchors.action_count = 1;
+    bridge.add_rule_with_anchors(rule, anchors).unwrap();
+}
+
+#[test]
+fn test_effective_window_bounds() {
+    let open = windowed_rule("open", None, None);
+    assert!(open.is_effective_at(0));
+    assert!(open.is_effective_at(u64::MAX));
+
+    let windowed = windowed_rule("windowed", Some(NOW), Some(NOW + 1_000));
+    assert!(!windowed.is_effective_at(NOW - 1));
+    assert!(windowed.is_effective_at(NOW));
+    assert!(windowed.is_effective_at(NOW + 999));
+    assert!(!windowed.is_effective_at(NOW + 1_000));
+}
+
+#[test]
+fn test_validity_window_survives_restart() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        install(
+            &bridge,
+            windowed_rule("rule-1", Some(NOW), Some(NOW + 1_000)),
+        );
+    }
+
+    let bridge = open_bridge(&dir);
+    let rule = bridge.get_rule("rule-1").expect("rule should reload");
+    assert_eq!(rule.not_before(), Some(NOW));
+    assert_eq!(rule.expires_at(), Some(NOW + 1_000));
+}
+
+#[test]
+fn test_archive_expired_records_version() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, windowed_rule("expired-1", None, Some(NOW - 1)));
+    install(&bridge, windowed_rule("expired-2", None, Some(NOW)));
+    install(&bridge, windowed_rule("future", None, Some(NOW + 1)));
+    install(&bridge, windowed_rule("forever", None, None));
+    let before = bridge.version();
+
+    let archived = bridge.archive_expired(NOW).unwrap();
+    assert_eq!(
+        archived,
+        vec!["expired-1".to_string(), "expired-2".to_string()]
+    );
+    assert_eq!(bridge.version(), before + 1);
+    assert!(bridge.get_rule("expired-1").is_none());
+    assert_eq!(bridge.rule_count(), 2);
+
+    let latest = &bridge.list_versions(1).unwrap()[0];
+    assert_eq!(latest.kind, "expire");
+    assert_eq!(latest.description, "Archived 2 expired rules");
+
+    let listings = bridge.list_rules(None, None).unwrap();
+    let status = |id: &str| listings.iter().find(|l| l.rule_id == id).unwrap().status;
+    assert_eq!(status("expired-1"), RuleStatus::Archived);
+    assert_eq!(status("future"), RuleStatus::Active);
+
+    // Nothing left to expire: no new version.
+    assert!(bridge.archive_expired(NOW).unwrap().is_empty());
+    assert_eq!(bridge.version(), before + 1);
+}
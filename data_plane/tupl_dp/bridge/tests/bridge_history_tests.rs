@@ -0,0 +1,185 @@
+//! Integration tests for rule-set version history.
+//!
+//! Tests verify:
+//! - Every mutation records a version that survives restart
+//! - Diffs between recorded versions
+//! - Whole-bridge and per-agent rollback, committed as new versions
+//! - Rows a per-agent rollback cannot decode are quarantined in the same write
+//! - Rows written before history existed are recorded as a baseline
+
+mod common;
+
+use bridge::bridge::Bridge;
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope, DEFAULT_TENANT};
+use common::{design_rule, open_bridge};
+use rusqlite::Connection;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str, agent_id: &str, priority: u32) -> Arc<dyn RuleInstance> {
+    design_rule(
+        rule_id,
+        priority,
+        RuleScope::for_agent(agent_id.to_string()),
+    )
+}
+
+fn install(bridge: &Bridge, rule_id: &str, agent_id: &str, priority: u32) {
+    bridge
+        .add_rule_with_anchors(rule(rule_id, agent_id, priority), RuleVector::default())
+        .unwrap();
+}
+
+#[test]
+fn test_versions_are_recorded_and_persist() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        install(&bridge, "rule-1", "agent-1", 10);
+        install(&bridge, "rule-2", "agent-1", 10);
+        bridge.remove_rule("rule-1").unwrap();
+        assert_eq!(bridge.version(), 3);
+    }
+
+    let bridge = open_bridge(&dir);
+    assert_eq!(bridge.version(), 3);
+
+    let versions = bridge.list_versions(10).unwrap();
+    let summary: Vec<_> = versions
+        .iter()
+        .map(|v| (v.version, v.kind.as_str(), v.rule_count))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![(3, "remove", 1), (2, "install", 2), (1, "install", 1)]
+    );
+    assert_eq!(bridge.list_versions(1).unwrap().len(), 1);
+}
+
+#[test]
+fn test_diff_between_versions() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "rule-1", "agent-1", 10);
+    install(&bridge, "rule-2", "agent-1", 10);
+    install(&bridge, "rule-2", "agent-1", 20);
+    bridge.remove_rule("rule-1").unwrap();
+
+    let diff = bridge.diff_versions(2, 4).unwrap();
+    assert!(diff.added.is_empty());
+    assert_eq!(diff.removed, vec!["rule-1".to_string()]);
+    assert_eq!(diff.changed, vec!["rule-2".to_string()]);
+
+    let from_empty = bridge.diff_versions(0, 2).unwrap();
+    assert_eq!(from_empty.added.len(), 2);
+
+    assert!(bridge.diff_versions(0, 99).is_err());
+    assert!(!bridge.has_version(99).unwrap());
+}
+
+#[test]
+fn test_rollback_whole_bridge() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        install(&bridge, "rule-1", "agent-1", 10);
+        install(&bridge, "rule-1", "agent-1", 99);
+        install(&bridge, "rule-2", "agent-2", 10);
+
+        let diff = bridge.rollback_to(1, DEFAULT_TENANT, None).unwrap();
+        assert_eq!(diff.removed, vec!["rule-2".to_string()]);
+        assert_eq!(diff.changed, vec!["rule-1".to_string()]);
+        assert_eq!(bridge.version(), 4);
+        assert_eq!(bridge.get_rule("rule-1").unwrap().priority(), 10);
+        assert!(bridge.get_rule("rule-2").is_none());
+
+        // The rollback is itself a version and can be undone.
+        assert!(bridge.diff_versions(1, 4).unwrap().is_empty());
+        bridge.rollback_to(3, DEFAULT_TENANT, None).unwrap();
+        assert_eq!(bridge.rule_count(), 2);
+        bridge.rollback_to(4, DEFAULT_TENANT, None).unwrap();
+    }
+
+    let bridge = open_bridge(&dir);
+    assert_eq!(bridge.rule_count(), 1);
+    assert_eq!(bridge.get_rule("rule-1").unwrap().priority(), 10);
+}
+
+#[test]
+fn test_rollback_single_agent() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "a-1", "agent-a", 10);
+    install(&bridge, "b-1", "agent-b", 10);
+    let checkpoint = bridge.version();
+
+    install(&bridge, "a-2", "agent-a", 10);
+    install(&bridge, "b-2", "agent-b", 10);
+
+    let diff = bridge
+        .rollback_to(checkpoint, DEFAULT_TENANT, Some("agent-a"))
+        .unwrap();
+    assert_eq!(diff.removed, vec!["a-2".to_string()]);
+    assert!(bridge.get_rule("a-1").is_some());
+    assert!(bridge.get_rule("a-2").is_none());
+    assert!(bridge.get_rule("b-2").is_some());
+}
+
+#[test]
+fn test_agent_rollback_quarantines_in_same_write() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "a-1", "agent-a", 10);
+    install(&bridge, "b-1", "agent-b", 10);
+    let checkpoint = bridge.version();
+    install(&bridge, "a-2", "agent-a", 10);
+
+    let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+    conn.execute(
+        "UPDATE rules SET rule_json = '{not json' WHERE id = 'b-1'",
+        [],
+    )
+    .unwrap();
+
+    bridge
+        .rollback_to(checkpoint, DEFAULT_TENANT, Some("agent-a"))
+        .unwrap();
+    assert!(bridge.get_rule("a-2").is_none());
+    assert!(bridge.get_rule("b-1").is_none());
+    assert_eq!(bridge.list_quarantined().unwrap().len(), 1);
+
+    let versions = bridge.list_versions(2).unwrap();
+    assert_eq!(versions[0].kind, "rollback");
+    assert_eq!(versions[0].version, bridge.version());
+    assert_eq!(versions[1].kind, "quarantine");
+    assert_eq!(versions[1].version, bridge.version() - 1);
+}
+
+#[test]
+fn test_existing_rows_become_baseline_version() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        install(&bridge, "rule-1", "agent-1", 10);
+    }
+
+    // Simulate a database written before history was recorded.
+    {
+        let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+        conn.execute_batch(
+            "DELETE FROM rule_history; DELETE FROM rule_versions; DROP TABLE schema_version;",
+        )
+        .unwrap();
+    }
+
+    let bridge = open_bridge(&dir);
+    assert_eq!(bridge.version(), 1);
+    let versions = bridge.list_versions(10).unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].kind, "baseline");
+
+    bridge.remove_rule("rule-1").unwrap();
+    bridge.rollback_to(1, DEFAULT_TENANT, None).unwrap();
+    assert!(bridge.get_rule("rule-1").is_some());
+}
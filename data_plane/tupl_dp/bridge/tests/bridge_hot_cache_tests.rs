@@ -0,0 +1,137 @@
+//! Integration tests for the bridge's anchor hot cache.
+//!
+//! Tests verify:
+//! - Anchors beyond the cache capacity are reloaded from the store on a miss
+//! - Hits, misses and evictions are reported in bridge stats
+//! - Removed and disabled rules are dropped from the cache
+//! - Loading and rebuilding the rule set do not decode anchors into the cache
+
+mod common;
+
+use bridge::bridge::{Bridge, RuleStatus, StorageConfig};
+use bridge::storage::{HotCache, MemoryRuleStore};
+use bridge::types::{RuleInstance, RuleScope};
+use common::{design_rule, marked_anchors};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str) -> Arc<dyn RuleInstance> {
+    design_rule(rule_id, 10, RuleScope::for_agent("agent-1".to_string()))
+}
+
+fn small_bridge(capacity: usize) -> Bridge {
+    Bridge::with_store_and_cache(
+        Box::new(MemoryRuleStore::new()),
+        HotCache::with_capacity(capacity),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_misses_load_anchors_from_store() {
+    let bridge = small_bridge(2);
+    for i in 0..5 {
+        bridge
+            .add_rule_with_anchors(rule(&format!("rule-{}", i)), marked_anchors(i))
+            .unwrap();
+    }
+    assert_eq!(bridge.stats().hot_cache.entries, 2);
+
+    for i in 0..5 {
+        let loaded = bridge.get_rule_anchors(&format!("rule-{}", i)).unwrap();
+        assert_eq!(loaded.action_anchors[0][0], i as f32);
+    }
+
+    let stats = bridge.stats().hot_cache;
+    assert_eq!(stats.entries, 2);
+    assert_eq!(stats.hits, 2);
+    assert_eq!(stats.misses, 3);
+    assert!(stats.total_evictions > 0);
+
+    // Recently loaded anchors are now served from the cache.
+    bridge.get_rule_anchors("rule-4").unwrap();
+    assert_eq!(bridge.stats().hot_cache.hits, 3);
+}
+
+#[test]
+fn test_inactive_rules_leave_the_cache() {
+    let bridge = small_bridge(10);
+    bridge
+        .add_rule_with_anchors(rule("rule-1"), marked_anchors(1))
+        .unwrap();
+    bridge
+        .add_rule_with_anchors(rule("rule-2"), marked_anchors(2))
+        .unwrap();
+
+    bridge
+        .set_rule_status(&["rule-1".to_string()], RuleStatus::Disabled)
+        .unwrap();
+    bridge.remove_rule("rule-2").unwrap();
+    assert_eq!(bridge.stats().hot_cache.entries, 0);
+    assert!(bridge.get_rule_anchors("rule-1").is_none());
+    assert!(bridge.get_rule_anchors("rule-2").is_none());
+
+    bridge
+        .set_rule_status(&["rule-1".to_string()], RuleStatus::Active)
+        .unwrap();
+    assert_eq!(
+        bridge.get_rule_anchors("rule-1").unwrap().action_anchors[0][0],
+        1.0
+    );
+}
+
+#[test]
+fn test_anchors_survive_restart_with_cold_cache() {
+    let dir = TempDir::new().unwrap();
+    let config = StorageConfig {
+        warm_storage_path: dir.path().join("warm_storage.bin"),
+        cold_storage_path: dir.path().join("cold_storage.db"),
+    };
+    {
+        let bridge = Bridge::new(config.clone()).unwrap();
+        bridge
+            .add_rule_with_anchors(rule("rule-1"), marked_anchors(7))
+            .unwrap();
+    }
+
+    let store = bridge::storage::SqliteRuleStore::open(&config.cold_storage_path).unwrap();
+    let bridge = Bridge::with_store_and_cache(Box::new(store), HotCache::with_capacity(0)).unwrap();
+    assert_eq!(
+        bridge.get_rule_anchors("rule-1").unwrap().action_anchors[0][0],
+        7.0
+    );
+    assert_eq!(bridge.stats().hot_cache.entries, 0);
+}
+
+#[test]
+fn test_rebuild_keeps_only_cached_anchors() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("cold_storage.db");
+    {
+        let store = bridge::storage::SqliteRuleStore::open(&path).unwrap();
+        let bridge = Bridge::with_store(Box::new(store)).unwrap();
+        for i in 0..3 {
+            bridge
+                .add_rule_with_anchors(rule(&format!("rule-{}", i)), marked_anchors(i))
+                .unwrap();
+        }
+    }
+
+    let store = bridge::storage::SqliteRuleStore::open(&path).unwrap();
+    let bridge =
+        Bridge::with_store_and_cache(Box::new(store), HotCache::with_capacity(10)).unwrap();
+    assert_eq!(bridge.rule_count(), 3);
+    assert_eq!(bridge.stats().hot_cache.entries, 0);
+
+    assert_eq!(
+        bridge.get_rule_anchors("rule-1").unwrap().action_anchors[0][0],
+        1.0
+    );
+    bridge.rebuild_from_db_public().unwrap();
+    assert_eq!(bridge.stats().hot_cache.entries, 1);
+    assert_eq!(
+        bridge.get_rule_anchors("rule-1").unwrap().action_anchors[0][0],
+        1.0
+    );
+    assert_eq!(bridge.stats().hot_cache.hits, 1);
+}
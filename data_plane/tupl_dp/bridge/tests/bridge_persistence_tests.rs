@@ -0,0 +1,373 @@
+//! Integration tests for Bridge cold-storage persistence.
+//!
+//! Tests verify:
+//! - AARM policy fields survive a restart and an explicit rebuild
+//! - Rows written by the legacy metadata format are upgraded on startup
+//! - Fixed-size anchor rows are re-encoded in the compact format on startup
+//! - Rows written before tenants existed keep the tenant stored alongside them
+//! - Batch installs are persisted together with a single version bump
+//! - Schema migrations are recorded, and newer databases are refused
+
+mod common;
+
+use bridge::bridge::{Bridge, StorageConfig};
+use bridge::families::DesignBoundaryRule;
+use bridge::rule_vector::{AnchorPrecision, RuleVector};
+use bridge::types::{PolicyType, RuleInstance, RuleScope, DEFAULT_TENANT};
+use common::open_bridge;
+use rusqlite::{params, Connection};
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn forbidden_rule(rule_id: &str) -> Arc<dyn RuleInstance> {
+    Arc::new(DesignBoundaryRule::new_with_policy(
+        rule_id.to_string(),
+        10,
+        RuleScope::for_agent("agent-1".to_string()),
+        Some("L4".to_string()),
+        1_700_000_000_000,
+        true,
+        Some("block prod writes".to_string()),
+        json!({"rule_type": "design_boundary", "rule_decision": "min"}),
+        PolicyType::Forbidden,
+        0.35,
+        Some(json!({"dry_run": true})),
+        [0.4, 0.3, 0.2, 0.1],
+    ))
+}
+
+fn assert_policy_fields(rule: &Arc<dyn RuleInstance>) {
+    assert_eq!(rule.policy_type(), PolicyType::Forbidden);
+    assert_eq!(rule.drift_threshold(), 0.35);
+    assert_eq!(rule.modification_spec(), Some(&json!({"dry_run": true})));
+    assert_eq!(rule.slice_weights(), [0.4, 0.3, 0.2, 0.1]);
+}
+
+#[test]
+fn test_policy_fields_survive_restart() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        bridge
+            .add_rule_with_anchors(forbidden_rule("rule-1"), RuleVector::default())
+            .unwrap();
+    }
+
+    let bridge = open_bridge(&dir);
+    let rule = bridge.get_rule("rule-1").expect("rule should be reloaded");
+    assert_policy_fields(&rule);
+}
+
+#[test]
+fn test_policy_fields_survive_rebuild() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    bridge
+        .add_rule_with_anchors(forbidden_rule("rule-1"), RuleVector::default())
+        .unwrap();
+
+    bridge.rebuild_from_db_public().unwrap();
+
+    let rule = bridge.get_rule("rule-1").expect("rule should be reloaded");
+    assert_policy_fields(&rule);
+}
+
+#[test]
+fn test_legacy_rows_are_upgraded() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        bridge
+            .add_rule_with_anchors(forbidden_rule("rule-1"), RuleVector::default())
+            .unwrap();
+    }
+
+    // Rewrite the row in the pre-AARM metadata format, in a database that predates
+    // migration tracking.
+    let legacy_json = json!({
+        "rule_id": "rule-1",
+        "priority": 10,
+        "scope": {"agent_ids": ["agent-1"], "tags": {}, "is_global": false},
+        "layer": "L4",
+        "created_at_ms": 1_700_000_000_000u64,
+        "enabled": true,
+        "description": null,
+        "params": {
+            "rule_type": "design_boundary",
+            "policy_type": "forbidden",
+            "rule_decision": "weighted-avg",
+            "weights": "{\"action\": 0.4, \"resource\": 0.3, \"data\": 0.2, \"risk\": 0.1}"
+        }
+    });
+    {
+        let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+        conn.execute(
+            "UPDATE rules SET rule_json = ?1 WHERE id = 'rule-1'",
+            params![legacy_json.to_string()],
+        )
+        .unwrap();
+        conn.execute("DROP TABLE schema_version", []).unwrap();
+    }
+
+    let bridge = open_bridge(&dir);
+    let rule = bridge.get_rule("rule-1").expect("legacy rule should load");
+    assert_eq!(rule.policy_type(), PolicyType::Forbidden);
+    assert_eq!(rule.slice_weights(), [0.4, 0.3, 0.2, 0.1]);
+    assert_eq!(rule.drift_threshold(), 0.0);
+    assert!(rule.modification_spec().is_none());
+
+    let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+    let stored: String = conn
+        .query_row(
+            "SELECT rule_json FROM rules WHERE id = 'rule-1'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert!(stored.contains("\"policy_type\""));
+}
+
+#[test]
+fn test_legacy_anchor_rows_are_compacted() {
+    let dir = TempDir::new().unwrap();
+    let mut anchors = RuleVector {
+        action_count: 1,
+        ..Default::default()
+    };
+    anchors.action_anchors[0][3] = 0.75;
+    {
+        let bridge = open_bridge(&dir);
+        bridge
+            .add_rule_with_anchors(forbidden_rule("rule-1"), anchors.clone())
+            .unwrap();
+    }
+
+    // Rewrite the row and its history in the fixed-size layout, as written before the
+    // compact encoding existed.
+    {
+        let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+        for table in ["rules", "rule_history"] {
+            conn.execute(
+                &format!("UPDATE {} SET anchors_bin = ?1 WHERE id = 'rule-1'", table),
+                params![anchors.to_le_bytes()],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "DELETE FROM schema_version WHERE component = 'cold_storage' AND version >= 6",
+            [],
+        )
+        .unwrap();
+    }
+
+    let bridge = open_bridge(&dir);
+    assert_eq!(
+        bridge.get_rule_anchors("rule-1").unwrap().action_anchors[0][3],
+        0.75
+    );
+
+    let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+    for table in ["rules", "rule_history"] {
+        let len: i64 = conn
+            .query_row(
+                &format!(
+                    "SELECT length(anchors_bin) FROM {} WHERE id = 'rule-1'",
+                    table
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(
+            (len as usize) < RuleVector::ENCODED_LEN,
+            "{} row not compacted",
+            table
+        );
+    }
+}
+
+#[test]
+fn test_f16_anchors_are_persisted_at_half_size() {
+    let dir = TempDir::new().unwrap();
+    let mut anchors = RuleVector {
+        data_count: 2,
+        ..Default::default()
+    };
+    anchors.data_anchors[1][0] = 0.1;
+    {
+        let bridge = open_bridge(&dir).with_anchor_precision(AnchorPrecision::F16);
+        bridge
+            .add_rule_with_anchors(forbidden_rule("rule-1"), anchors.clone())
+            .unwrap();
+    }
+
+    let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+    let stored: Vec<u8> = conn
+        .query_row(
+            "SELECT anchors_bin FROM rules WHERE id = 'rule-1'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(stored, anchors.encode(AnchorPrecision::F16));
+
+    let bridge = open_bridge(&dir);
+    let reloaded = bridge.get_rule_anchors("rule-1").unwrap();
+    assert_eq!(reloaded.data_count, 2);
+    assert!((reloaded.data_anchors[1][0] - 0.1).abs() < 1e-3);
+}
+
+#[test]
+fn test_pre_tenant_database_keeps_stored_tenants() {
+    let dir = TempDir::new().unwrap();
+    let legacy_rule = |rule_id: &str, agent_ids: Vec<&str>| {
+        json!({
+            "rule_id": rule_id,
+            "priority": 10,
+            "scope": {"agent_ids": agent_ids, "is_global": agent_ids.is_empty()},
+            "layer": "L4",
+            "created_at_ms": 1_700_000_000_000u64,
+            "enabled": true,
+            "description": null,
+            "params": {"rule_type": "design_boundary", "policy_type": "forbidden"}
+        })
+        .to_string()
+    };
+
+    // A database written before migrations, history or tenants: the tenant lived only in
+    // the tenant_id column.
+    {
+        let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE rules (
+                 id TEXT PRIMARY KEY, tenant_id TEXT NOT NULL, layer TEXT,
+                 priority INTEGER NOT NULL DEFAULT 0, rule_json TEXT NOT NULL,
+                 anchors_bin BLOB NOT NULL, status TEXT NOT NULL DEFAULT 'active',
+                 updated_at REAL NOT NULL
+             );",
+        )
+        .unwrap();
+        for (rule_id, tenant, rule_json) in [
+            (
+                "acme-rule",
+                "acme",
+                legacy_rule("acme-rule", vec!["agent-1"]),
+            ),
+            (
+                "untenanted-rule",
+                "",
+                legacy_rule("untenanted-rule", vec![]),
+            ),
+        ] {
+            conn.execute(
+                "INSERT INTO rules (id, tenant_id, layer, priority, rule_json, anchors_bin, updated_at)
+                 VALUES (?1, ?2, 'L4', 10, ?3, ?4, 1700000000.0)",
+                params![rule_id, tenant, rule_json, RuleVector::default().to_le_bytes()],
+            )
+            .unwrap();
+        }
+    }
+
+    let bridge = open_bridge(&dir);
+    let tenant = |rule_id: &str| {
+        bridge
+            .get_rule(rule_id)
+            .unwrap()
+            .scope()
+            .tenant()
+            .to_string()
+    };
+    assert_eq!(tenant("acme-rule"), "acme");
+    assert_eq!(tenant("untenanted-rule"), DEFAULT_TENANT);
+    assert_eq!(
+        bridge.rules_for(Some("acme"), Some("agent-1"), "L4").len(),
+        1
+    );
+    assert_eq!(
+        bridge.list_rules(Some(DEFAULT_TENANT), None).unwrap().len(),
+        1
+    );
+    let versions = bridge.list_tenant_versions("acme", 10).unwrap();
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].rule_count, 1);
+    drop(bridge);
+
+    // History carries the tenant too, so rolling back or catching up keeps it.
+    let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+    let history_tenant: String = conn
+        .query_row(
+            "SELECT json_extract(rule_json, '$.scope.tenant_id') FROM rule_history
+             WHERE id = 'acme-rule'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(history_tenant, "acme");
+}
+
+#[test]
+fn test_batch_install_persists_with_single_version_bump() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        let version_before = bridge.version();
+        let batch = (0..5)
+            .map(|i| {
+                (
+                    forbidden_rule(&format!("rule-{}", i)),
+                    RuleVector::default(),
+                )
+            })
+            .collect();
+
+        bridge.add_rules_batch(batch).unwrap();
+
+        assert_eq!(bridge.rule_count(), 5);
+        assert_eq!(bridge.version(), version_before + 1);
+    }
+
+    let bridge = open_bridge(&dir);
+    assert_eq!(bridge.rule_count(), 5);
+    assert_policy_fields(&bridge.get_rule("rule-3").unwrap());
+}
+
+#[test]
+fn test_schema_migrations_are_recorded() {
+    let dir = TempDir::new().unwrap();
+    drop(open_bridge(&dir));
+    drop(open_bridge(&dir));
+
+    let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+    let (count, latest): (i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(*), MAX(version) FROM schema_version WHERE component = 'cold_storage'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(count, latest, "each migration is recorded exactly once");
+}
+
+#[test]
+fn test_newer_schema_is_refused() {
+    let dir = TempDir::new().unwrap();
+    drop(open_bridge(&dir));
+
+    {
+        let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (component, version, description, applied_at_ms)
+             VALUES ('cold_storage', 9999, 'from the future', 0)",
+            [],
+        )
+        .unwrap();
+    }
+
+    let err = Bridge::new(StorageConfig {
+        warm_storage_path: dir.path().join("warm_storage.bin"),
+        cold_storage_path: dir.path().join("cold_storage.db"),
+    })
+    .unwrap_err();
+    assert!(err.contains("newer than this binary supports"), "{}", err);
+}
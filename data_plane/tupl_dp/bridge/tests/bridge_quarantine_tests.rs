@@ -0,0 +1,210 @@
+//! Integration tests for quarantining undecodable rule rows.
+//!
+//! Tests verify:
+//! - Active rows with invalid JSON or anchors are moved to quarantine on load
+//! - The move is recorded as a version, so the rest of the rule set still loads
+//! - Quarantined rows are not loaded again on the next start
+//! - Rows another replica wrote are quarantined on refresh and before a write, even
+//!   one that turns out to change nothing
+
+mod common;
+
+use bridge::bridge::{Bridge, StorageConfig};
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope};
+use common::{design_rule, storage_config};
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str) -> Arc<dyn RuleInstance> {
+    design_rule(rule_id, 10, RuleScope::global())
+}
+
+/// Installs three rules, then corrupts two of them behind the bridge's back.
+fn corrupted_store(config: &StorageConfig) {
+    let bridge = Bridge::new(config.clone()).unwrap();
+    for rule_id in ["rule-1", "rule-2", "rule-3"] {
+        bridge
+            .add_rule_with_anchors(rule(rule_id), RuleVector::default())
+            .unwrap();
+    }
+    drop(bridge);
+
+    let conn = Connection::open(&config.cold_storage_path).unwrap();
+    conn.execute(
+        "UPDATE rules SET rule_json = '{not json' WHERE id = ?1",
+        params!["rule-1"],
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE rules SET anchors_bin = ?1 WHERE id = ?2",
+        params![vec![0u8; 10], "rule-2"],
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_undecodable_rows_are_quarantined_on_load() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    corrupted_store(&config);
+
+    let bridge = Bridge::new(config).unwrap();
+    assert_eq!(bridge.quarantined_on_load(), ["rule-1", "rule-2"]);
+    assert_eq!(bridge.rule_count(), 1);
+    assert!(bridge.get_rule("rule-3").is_some());
+    assert!(bridge.get_rule_anchors("rule-3").is_some());
+
+    let quarantined = bridge.list_quarantined().unwrap();
+    assert_eq!(quarantined.len(), 2);
+    assert_eq!(quarantined[0].row.rule_id, "rule-1");
+    assert!(
+        quarantined[0].reason.contains("invalid JSON"),
+        "{}",
+        quarantined[0].reason
+    );
+    assert_eq!(quarantined[1].row.anchors_bin.len(), 10);
+    assert!(
+        quarantined[1].reason.contains("invalid anchors"),
+        "{}",
+        quarantined[1].reason
+    );
+    assert_eq!(bridge.stats().quarantined_rules, 2);
+
+    let latest = &bridge.list_versions(1).unwrap()[0];
+    assert_eq!(latest.kind, "quarantine");
+    assert_eq!(latest.version, bridge.version());
+    assert_eq!(latest.rule_count, 1);
+
+    let stored: Vec<String> = bridge
+        .list_rules(None, None)
+        .unwrap()
+        .into_iter()
+        .map(|listing| listing.rule_id)
+        .collect();
+    assert_eq!(stored, vec!["rule-3"]);
+}
+
+#[test]
+fn test_quarantine_survives_restart() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    corrupted_store(&config);
+    let version = Bridge::new(config.clone()).unwrap().version();
+
+    let bridge = Bridge::new(config).unwrap();
+    assert!(bridge.quarantined_on_load().is_empty());
+    assert_eq!(bridge.version(), version);
+    assert_eq!(bridge.rule_count(), 1);
+    assert_eq!(bridge.list_quarantined().unwrap().len(), 2);
+
+    // A quarantined rule can be installed again from a good copy.
+    bridge
+        .add_rule_with_anchors(rule("rule-1"), RuleVector::default())
+        .unwrap();
+    assert_eq!(bridge.rule_count(), 2);
+}
+
+#[test]
+fn test_clean_store_quarantines_nothing() {
+    let bridge = Bridge::in_memory().unwrap();
+    bridge
+        .add_rule_with_anchors(rule("rule-1"), RuleVector::default())
+        .unwrap();
+
+    assert!(bridge.quarantined_on_load().is_empty());
+    assert!(bridge.list_quarantined().unwrap().is_empty());
+    assert_eq!(bridge.stats().quarantined_rules, 0);
+    assert_eq!(bridge.list_versions(1).unwrap()[0].kind, "install");
+}
+
+/// Opens a writer and a reader on one store, both holding `rule-1`, then has the writer
+/// update it and corrupts the update behind both bridges' backs.
+fn corrupted_update(config: &StorageConfig) -> (Bridge, Bridge) {
+    let writer = Bridge::new(config.clone()).unwrap();
+    writer
+        .add_rule_with_anchors(rule("rule-1"), RuleVector::default())
+        .unwrap();
+    let reader = Bridge::new(config.clone()).unwrap();
+    assert!(reader.get_rule("rule-1").is_some());
+
+    writer
+        .add_rule_with_anchors(rule("rule-1"), RuleVector::default())
+        .unwrap();
+    let conn = Connection::open(&config.cold_storage_path).unwrap();
+    conn.execute(
+        "UPDATE rules SET rule_json = '{not json' WHERE id = ?1",
+        params!["rule-1"],
+    )
+    .unwrap();
+    conn.execute(
+        "UPDATE rule_history SET rule_json = '{not json' WHERE id = ?1 AND version = ?2",
+        params!["rule-1", writer.version() as i64],
+    )
+    .unwrap();
+    (writer, reader)
+}
+
+#[test]
+fn test_refresh_quarantines_rows_written_elsewhere() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    let (_writer, reader) = corrupted_update(&config);
+
+    let delta = reader.refresh_delta().unwrap();
+    assert_eq!(delta.removed, 1);
+    // The previous, decodable version is not kept either.
+    assert!(reader.get_rule("rule-1").is_none());
+    assert_eq!(reader.rule_count(), 0);
+
+    let quarantined = reader.list_quarantined().unwrap();
+    assert_eq!(quarantined.len(), 1);
+    assert!(
+        quarantined[0].reason.contains("invalid JSON"),
+        "{}",
+        quarantined[0].reason
+    );
+
+    let latest = &reader.list_versions(1).unwrap()[0];
+    assert_eq!(latest.kind, "quarantine");
+    assert_eq!(latest.version, reader.version());
+}
+
+#[test]
+fn test_write_quarantines_rows_written_elsewhere() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    let (_writer, reader) = corrupted_update(&config);
+
+    reader
+        .add_rule_with_anchors(rule("rule-2"), RuleVector::default())
+        .unwrap();
+    assert!(reader.get_rule("rule-1").is_none());
+    assert!(reader.get_rule("rule-2").is_some());
+    assert_eq!(reader.list_quarantined().unwrap().len(), 1);
+
+    let versions = reader.list_versions(2).unwrap();
+    assert_eq!(versions[0].kind, "install");
+    assert_eq!(versions[0].version, reader.version());
+    assert_eq!(versions[1].kind, "quarantine");
+    assert_eq!(versions[1].version, reader.version() - 1);
+}
+
+#[test]
+fn test_noop_write_keeps_catch_up_quarantine() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    let (_writer, reader) = corrupted_update(&config);
+
+    assert!(!reader.remove_rule("missing").unwrap());
+    assert!(reader.get_rule("rule-1").is_none());
+    assert_eq!(reader.list_quarantined().unwrap().len(), 1);
+    assert_eq!(reader.list_versions(1).unwrap()[0].kind, "quarantine");
+
+    // A restart sees the same quarantine rather than judging the row again.
+    drop(reader);
+    let reopened = Bridge::new(config).unwrap();
+    assert!(reopened.quarantined_on_load().is_empty());
+    assert_eq!(reopened.list_quarantined().unwrap().len(), 1);
+}
@@ -0,0 +1,154 @@
+//! Integration tests for refreshing the in-memory rule set from cold storage.
+//!
+//! Two bridges opened on the same database stand in for two processes sharing it.
+//!
+//! Tests verify:
+//! - Delta refresh applies only rules changed by the other writer
+//! - Delta refresh is a no-op when nothing changed
+//! - Full rebuild picks up rows edited outside the bridge
+//! - Change detection only refreshes after foreign commits
+//! - A stale writer catches up instead of reusing another replica's version
+
+mod common;
+
+use bridge::bridge::{Bridge, RefreshDelta};
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope};
+use common::{design_rule, open_bridge};
+use rusqlite::Connection;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str, priority: u32) -> Arc<dyn RuleInstance> {
+    design_rule(rule_id, priority, RuleScope::global())
+}
+
+fn install(bridge: &Bridge, rule_id: &str, priority: u32) {
+    bridge
+        .add_rule_with_anchors(rule(rule_id, priority), RuleVector::default())
+        .unwrap();
+}
+
+#[test]
+fn test_delta_refresh_applies_foreign_changes() {
+    let dir = TempDir::new().unwrap();
+    let reader = open_bridge(&dir);
+    let writer = open_bridge(&dir);
+
+    install(&writer, "rule-1", 10);
+    install(&writer, "rule-2", 10);
+    let delta = reader.refresh_delta().unwrap();
+    assert_eq!(
+        delta,
+        RefreshDelta {
+            added: 2,
+            updated: 0,
+            removed: 0
+        }
+    );
+    assert_eq!(reader.version(), writer.version());
+
+    install(&writer, "rule-1", 20);
+    writer.remove_rule("rule-2").unwrap();
+    install(&writer, "rule-3", 10);
+    let delta = reader.refresh_delta().unwrap();
+    assert_eq!(
+        delta,
+        RefreshDelta {
+            added: 1,
+            updated: 1,
+            removed: 1
+        }
+    );
+    assert_eq!(reader.get_rule("rule-1").unwrap().priority(), 20);
+    assert!(reader.get_rule("rule-2").is_none());
+    assert_eq!(reader.rule_count(), 2);
+}
+
+#[test]
+fn test_delta_refresh_without_changes_is_noop() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "rule-1", 10);
+    let version = bridge.version();
+
+    assert_eq!(bridge.refresh_delta().unwrap(), RefreshDelta::default());
+    assert_eq!(bridge.version(), version);
+    assert_eq!(bridge.rule_count(), 1);
+}
+
+#[test]
+fn test_full_rebuild_reports_delta() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "rule-1", 10);
+    install(&bridge, "rule-2", 10);
+
+    // Edit cold storage directly, bypassing the version history.
+    {
+        let conn = Connection::open(dir.path().join("cold_storage.db")).unwrap();
+        conn.execute("DELETE FROM rules WHERE id = 'rule-2'", [])
+            .unwrap();
+    }
+    assert_eq!(bridge.refresh_delta().unwrap(), RefreshDelta::default());
+
+    let delta = bridge.rebuild_from_db_public().unwrap();
+    assert_eq!(
+        delta,
+        RefreshDelta {
+            added: 0,
+            updated: 0,
+            removed: 1
+        }
+    );
+    assert!(bridge.get_rule("rule-2").is_none());
+    assert!(bridge.get_rule("rule-1").is_some());
+}
+
+#[test]
+fn test_sync_if_changed_detects_foreign_commits() {
+    let dir = TempDir::new().unwrap();
+    let reader = open_bridge(&dir);
+    let writer = open_bridge(&dir);
+
+    // First check establishes the baseline.
+    reader.sync_if_changed().unwrap();
+    assert!(reader.sync_if_changed().unwrap().is_none());
+
+    // The reader's own writes do not count as foreign commits.
+    install(&reader, "own", 10);
+    assert!(reader.sync_if_changed().unwrap().is_none());
+
+    install(&writer, "foreign", 10);
+    let delta = reader
+        .sync_if_changed()
+        .unwrap()
+        .expect("foreign commit detected");
+    assert_eq!(delta.added, 1);
+    assert_eq!(reader.version(), writer.version());
+    assert!(reader.get_rule("foreign").is_some());
+    assert!(reader.last_synced_at() > 0);
+}
+
+#[test]
+fn test_stale_writer_catches_up_before_writing() {
+    let dir = TempDir::new().unwrap();
+    let first = open_bridge(&dir);
+    let second = open_bridge(&dir);
+
+    install(&first, "rule-1", 10);
+    install(&second, "rule-2", 10);
+
+    assert_eq!(second.version(), 2);
+    assert!(second.get_rule("rule-1").is_some());
+    assert_eq!(first.refresh_delta().unwrap().added, 1);
+    assert_eq!(first.rule_count(), 2);
+
+    let versions: Vec<u64> = first
+        .list_versions(10)
+        .unwrap()
+        .iter()
+        .map(|v| v.version)
+        .collect();
+    assert_eq!(versions, vec![2, 1]);
+}
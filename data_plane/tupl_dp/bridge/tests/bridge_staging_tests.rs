@@ -0,0 +1,131 @@
+//! Integration tests for staged rule sets.
+//!
+//! Tests verify:
+//! - Staged rules are not visible to the active rule set until promoted
+//! - Diffs report added, removed and changed rules against the active set
+//! - Promotion replaces the active set atomically and persists it
+//! - Discarding drops the staged set without touching active rules
+
+mod common;
+
+use bridge::bridge::{Bridge, RuleSetDiff};
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope, DEFAULT_TENANT};
+use common::{design_rule, open_bridge};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str, priority: u32) -> Arc<dyn RuleInstance> {
+    design_rule(rule_id, priority, RuleScope::global())
+}
+
+fn install(bridge: &Bridge, rules: &[(&str, u32)]) {
+    let batch = rules
+        .iter()
+        .map(|(id, priority)| (rule(id, *priority), RuleVector::default()))
+        .collect();
+    bridge.add_rules_batch(batch).unwrap();
+}
+
+fn stage(bridge: &Bridge, rules: &[(&str, u32)], replace: bool) -> usize {
+    let batch = rules
+        .iter()
+        .map(|(id, priority)| (rule(id, *priority), RuleVector::default()))
+        .collect();
+    bridge.stage_rules(batch, replace)
+}
+
+#[test]
+fn test_staged_rules_are_not_active() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, &[("active-1", 10)]);
+    let version = bridge.version();
+
+    assert_eq!(stage(&bridge, &[("staged-1", 10)], true), 1);
+
+    assert_eq!(bridge.version(), version);
+    assert_eq!(bridge.staged_version(), Some(version));
+    assert!(bridge.get_rule("staged-1").is_none());
+    assert_eq!(bridge.staged_rules().len(), 1);
+    assert!(bridge.get_staged_rule_anchors("staged-1").is_some());
+}
+
+#[test]
+fn test_diff_staged_against_active() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    assert!(bridge.diff_staged(DEFAULT_TENANT).unwrap().is_none());
+
+    install(&bridge, &[("keep", 10), ("change", 10), ("drop", 10)]);
+    stage(&bridge, &[("keep", 10), ("change", 20), ("new", 10)], true);
+
+    let diff = bridge
+        .diff_staged(DEFAULT_TENANT)
+        .unwrap()
+        .expect("staged set exists");
+    assert_eq!(
+        diff,
+        RuleSetDiff {
+            added: vec!["new".to_string()],
+            removed: vec!["drop".to_string()],
+            changed: vec!["change".to_string()],
+            unchanged: 1,
+        }
+    );
+}
+
+#[test]
+fn test_stage_merge_and_replace() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+
+    assert_eq!(stage(&bridge, &[("a", 10)], false), 1);
+    assert_eq!(stage(&bridge, &[("b", 10)], false), 2);
+    assert_eq!(stage(&bridge, &[("c", 10)], true), 1);
+    assert_eq!(bridge.staged_rule_count(), Some(1));
+}
+
+#[test]
+fn test_promote_replaces_active_and_persists() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        install(&bridge, &[("old-1", 10), ("old-2", 10)]);
+        stage(&bridge, &[("new-1", 10), ("old-2", 10)], true);
+        let version = bridge.version();
+
+        let diff = bridge.promote_staged(DEFAULT_TENANT).unwrap();
+        assert_eq!(diff.added, vec!["new-1".to_string()]);
+        assert_eq!(diff.removed, vec!["old-1".to_string()]);
+        assert_eq!(bridge.version(), version + 1);
+        assert_eq!(bridge.staged_rule_count(), None);
+        assert!(bridge.get_rule("old-1").is_none());
+        assert!(bridge.get_rule("new-1").is_some());
+    }
+
+    let bridge = open_bridge(&dir);
+    assert_eq!(bridge.rule_count(), 2);
+    assert!(bridge.get_rule("old-1").is_none());
+    assert!(bridge.get_rule("new-1").is_some());
+}
+
+#[test]
+fn test_promote_without_staged_set_fails() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    assert!(bridge.promote_staged(DEFAULT_TENANT).is_err());
+}
+
+#[test]
+fn test_discard_keeps_active_rules() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, &[("active-1", 10)]);
+    stage(&bridge, &[("staged-1", 10), ("staged-2", 10)], true);
+
+    assert_eq!(bridge.discard_staged(), Some(2));
+    assert_eq!(bridge.discard_staged(), None);
+    assert_eq!(bridge.rule_count(), 1);
+    assert!(bridge.get_rule("active-1").is_some());
+}
@@ -0,0 +1,150 @@
+//! Integration tests for rule status (enable/disable/archive).
+//!
+//! Tests verify:
+//! - Disabled and archived rules leave enforcement but keep their row and anchors
+//! - Re-enabling restores the stored rule, including after restart
+//! - Agent-wide status changes commit a single version
+//! - Listings show rules in every status
+
+mod common;
+
+use bridge::bridge::{Bridge, RuleStatus};
+use bridge::families::DesignBoundaryRule;
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope, DEFAULT_TENANT};
+use common::{open_bridge, rule_ids};
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn install(bridge: &Bridge, rule_id: &str, agent_id: &str) {
+    let rule: Arc<dyn RuleInstance> = Arc::new(DesignBoundaryRule::new(
+        rule_id.to_string(),
+        10,
+        RuleScope::for_agent(agent_id.to_string()),
+        Some("L4".to_string()),
+        1_700_000_000_000,
+        true,
+        None,
+        json!({"rule_type": "design_boundary"}),
+    ));
+    let mut anchors = RuleVector::default();
+    anchors.action_anchors[0][0] = 0.5;
+    anchors.action_count = 1;
+    bridge.add_rule_with_anchors(rule, anchors).unwrap();
+}
+
+#[test]
+fn test_disabled_rules_are_skipped_and_restorable() {
+    let dir = TempDir::new().unwrap();
+    {
+        let bridge = open_bridge(&dir);
+        install(&bridge, "rule-1", "agent-1");
+        install(&bridge, "rule-2", "agent-1");
+
+        let changed = bridge
+            .set_rule_status(&["rule-1".to_string()], RuleStatus::Disabled)
+            .unwrap();
+        assert_eq!(changed, vec!["rule-1".to_string()]);
+        assert!(bridge.get_rule("rule-1").is_none());
+        assert_eq!(
+            rule_ids(&bridge.rules_for(None, None, "L4")),
+            vec!["rule-2"]
+        );
+
+        // Setting the same status again is a no-op.
+        let version = bridge.version();
+        assert!(bridge
+            .set_rule_status(&["rule-1".to_string()], RuleStatus::Disabled)
+            .unwrap()
+            .is_empty());
+        assert_eq!(bridge.version(), version);
+    }
+
+    let bridge = open_bridge(&dir);
+    assert!(bridge.get_rule("rule-1").is_none());
+
+    bridge
+        .set_rule_status(&["rule-1".to_string()], RuleStatus::Active)
+        .unwrap();
+    assert!(bridge.get_rule("rule-1").is_some());
+    assert_eq!(
+        bridge.get_rule_anchors("rule-1").unwrap().action_anchors[0][0],
+        0.5
+    );
+}
+
+#[test]
+fn test_archive_agent_commits_one_version() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "a-1", "agent-a");
+    install(&bridge, "a-2", "agent-a");
+    install(&bridge, "b-1", "agent-b");
+    let version = bridge.version();
+
+    let changed = bridge
+        .set_agent_status(DEFAULT_TENANT, "agent-a", RuleStatus::Archived)
+        .unwrap();
+    assert_eq!(changed.len(), 2);
+    assert_eq!(bridge.version(), version + 1);
+    assert_eq!(bridge.rule_count(), 1);
+
+    let latest = &bridge.list_versions(1).unwrap()[0];
+    assert_eq!(latest.kind, "archive");
+    assert_eq!(latest.rule_count, 1);
+
+    // Rolling back to before the archive reactivates the rules.
+    bridge.rollback_to(version, DEFAULT_TENANT, None).unwrap();
+    assert_eq!(bridge.rule_count(), 3);
+}
+
+#[test]
+fn test_listing_includes_every_status() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "a-1", "agent-a");
+    install(&bridge, "a-2", "agent-a");
+    install(&bridge, "b-1", "agent-b");
+    bridge
+        .set_rule_status(&["a-1".to_string()], RuleStatus::Archived)
+        .unwrap();
+    bridge
+        .set_rule_status(&["a-2".to_string()], RuleStatus::Disabled)
+        .unwrap();
+
+    let listed: Vec<_> = bridge
+        .list_rules(None, Some("agent-a"))
+        .unwrap()
+        .into_iter()
+        .map(|l| (l.rule_id, l.status))
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("a-1".to_string(), RuleStatus::Archived),
+            ("a-2".to_string(), RuleStatus::Disabled),
+        ]
+    );
+    assert_eq!(bridge.list_rules(None, None).unwrap().len(), 3);
+}
+
+#[test]
+fn test_promotion_keeps_archived_rows() {
+    let dir = TempDir::new().unwrap();
+    let bridge = open_bridge(&dir);
+    install(&bridge, "archived", "agent-a");
+    bridge
+        .set_rule_status(&["archived".to_string()], RuleStatus::Archived)
+        .unwrap();
+
+    bridge.stage_rules(Vec::new(), true);
+    bridge.promote_staged(DEFAULT_TENANT).unwrap();
+
+    let listed = bridge.list_rules(None, None).unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].status, RuleStatus::Archived);
+
+    assert!(bridge.remove_rule("archived").unwrap());
+    assert!(bridge.list_rules(None, None).unwrap().is_empty());
+}
@@ -0,0 +1,367 @@
+//! Integration tests for tenant namespaces.
+//!
+//! Tests verify:
+//! - Rules, including global ones, only match requests of their own tenant
+//! - A rule_id owned by one tenant cannot be taken over by another
+//! - Listings, status changes, rollbacks and removal RPCs stay within one tenant
+//! - Promotions and bundle imports only replace the tenant's own rules
+//! - Version listings and diffs only cover one tenant
+//! - Enforcement only evaluates the requesting tenant's rules
+
+mod common;
+
+use bridge::bridge::{Bridge, RuleStatus};
+use bridge::bundle::ImportMode;
+use bridge::enforcement_engine::EnforcementEngine;
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    ListRulesRequest, RemoveAgentRulesRequest, RemovePolicyRequest, RollbackRulesRequest,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope, DEFAULT_TENANT};
+use common::{design_rule, open_bridge, rule_ids};
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::Request;
+
+fn agent_rule(rule_id: &str, tenant: &str, agent_id: &str) -> Arc<dyn RuleInstance> {
+    design_rule(
+        rule_id,
+        10,
+        RuleScope::for_agent(agent_id.to_string()).with_tenant(tenant),
+    )
+}
+
+/// Two tenants whose rules target the same agent id.
+fn shared_agent_bridge() -> Bridge {
+    with_shared_agents(Bridge::in_memory().unwrap())
+}
+
+/// Installs the rules of `shared_agent_bridge` into `bridge`.
+fn with_shared_agents(bridge: Bridge) -> Bridge {
+    bridge
+        .add_rules_batch(vec![
+            (
+                agent_rule("acme-1", "acme", "agent-1"),
+                RuleVector::default(),
+            ),
+            (
+                design_rule("acme-global", 10, RuleScope::global().with_tenant("acme")),
+                RuleVector::default(),
+            ),
+            (
+                agent_rule("globex-1", "globex", "agent-1"),
+                RuleVector::default(),
+            ),
+        ])
+        .unwrap();
+    bridge
+}
+
+fn listed(bridge: &Bridge, tenant: &str) -> Vec<String> {
+    bridge
+        .list_rules(Some(tenant), None)
+        .unwrap()
+        .into_iter()
+        .map(|listing| listing.rule_id)
+        .collect()
+}
+
+#[test]
+fn test_rules_only_match_their_tenant() {
+    let bridge = shared_agent_bridge();
+
+    let acme = bridge.rules_for(Some("acme"), Some("agent-1"), "L4");
+    assert_eq!(rule_ids(&acme), vec!["acme-1", "acme-global"]);
+
+    let globex = bridge.rules_for(Some("globex"), Some("agent-2"), "L4");
+    assert!(globex.is_empty(), "acme's global rule leaked into globex");
+    assert!(bridge
+        .rules_for(Some(DEFAULT_TENANT), Some("agent-1"), "L4")
+        .is_empty());
+
+    assert!(bridge.get_tenant_rule("globex", "acme-1").is_none());
+    assert_eq!(rule_ids(&bridge.tenant_rules("globex")), vec!["globex-1"]);
+}
+
+#[test]
+fn test_rule_id_cannot_change_tenant() {
+    let bridge = shared_agent_bridge();
+    let version = bridge.version();
+
+    let err = bridge
+        .add_rule_with_anchors(
+            agent_rule("acme-1", "globex", "agent-1"),
+            RuleVector::default(),
+        )
+        .unwrap_err();
+    assert!(err.contains("belongs to tenant acme"), "{}", err);
+
+    let err = bridge
+        .add_rules_batch(vec![
+            (
+                agent_rule("globex-2", "globex", "agent-1"),
+                RuleVector::default(),
+            ),
+            (
+                agent_rule("acme-global", "globex", "agent-1"),
+                RuleVector::default(),
+            ),
+        ])
+        .unwrap_err();
+    assert!(err.contains("acme-global"), "{}", err);
+
+    assert_eq!(bridge.version(), version);
+    assert_eq!(listed(&bridge, "globex"), vec!["globex-1"]);
+    assert!(bridge.get_tenant_rule("acme", "acme-1").is_some());
+}
+
+#[test]
+fn test_listing_status_and_rollback_stay_in_tenant() {
+    let bridge = shared_agent_bridge();
+    let checkpoint = bridge.version();
+    assert_eq!(listed(&bridge, "acme"), vec!["acme-1", "acme-global"]);
+    assert_eq!(bridge.list_rules(None, None).unwrap().len(), 3);
+
+    let changed = bridge
+        .set_agent_status("globex", "agent-1", RuleStatus::Disabled)
+        .unwrap();
+    assert_eq!(changed, vec!["globex-1"]);
+
+    // Ids owned by another tenant are ignored.
+    let changed = bridge
+        .set_tenant_rule_status("globex", &["acme-1".to_string()], RuleStatus::Archived)
+        .unwrap();
+    assert!(changed.is_empty());
+    assert!(bridge.get_rule("acme-1").is_some());
+
+    bridge.remove_rule("acme-1").unwrap();
+    bridge.rollback_to(checkpoint, "globex", None).unwrap();
+    assert!(bridge.get_rule("globex-1").is_some());
+    assert!(bridge.get_rule("acme-1").is_none());
+}
+
+#[test]
+fn test_replacements_stay_in_tenant() {
+    let bridge = shared_agent_bridge();
+
+    bridge.stage_rules(
+        vec![(
+            agent_rule("globex-2", "globex", "agent-1"),
+            RuleVector::default(),
+        )],
+        true,
+    );
+    let diff = bridge.promote_staged("globex").unwrap();
+    assert_eq!(diff.added, vec!["globex-2"]);
+    assert_eq!(diff.removed, vec!["globex-1"]);
+    assert_eq!(listed(&bridge, "acme"), vec!["acme-1", "acme-global"]);
+    assert_eq!(listed(&bridge, "globex"), vec!["globex-2"]);
+
+    let bundle = bridge.export_bundle("acme").unwrap();
+    assert_eq!(bundle.rules.len(), 2);
+    let import = bridge
+        .import_bundle("globex", bundle, ImportMode::Replace, false)
+        .unwrap();
+    assert_eq!(import.failures.len(), 2);
+    assert!(import.failures[0]
+        .message
+        .contains("belongs to tenant acme"));
+    assert_eq!(listed(&bridge, "globex"), vec!["globex-2"]);
+
+    let import = bridge
+        .import_bundle(
+            "acme",
+            bridge.export_bundle("acme").unwrap(),
+            ImportMode::Replace,
+            false,
+        )
+        .unwrap();
+    assert!(import.diff.is_empty());
+    assert_eq!(listed(&bridge, "globex"), vec!["globex-2"]);
+}
+
+#[test]
+fn test_promotion_and_import_cannot_take_over_rule_ids() {
+    let bridge = shared_agent_bridge();
+    let version = bridge.version();
+
+    bridge.stage_rules(
+        vec![(
+            agent_rule("acme-1", "globex", "agent-1"),
+            RuleVector::default(),
+        )],
+        true,
+    );
+    let err = bridge.promote_staged("globex").unwrap_err();
+    assert!(err.contains("belongs to tenant acme"), "{}", err);
+    assert_eq!(bridge.staged_rule_count(), Some(1));
+
+    // A staged rule of another tenant is refused rather than promoted into this one.
+    let err = bridge.promote_staged("acme").unwrap_err();
+    assert!(err.contains("belongs to tenant globex"), "{}", err);
+
+    let other = Bridge::in_memory().unwrap();
+    other
+        .add_rule_with_anchors(
+            agent_rule("acme-1", "globex", "agent-1"),
+            RuleVector::default(),
+        )
+        .unwrap();
+    let err = bridge
+        .import_bundle(
+            "globex",
+            other.export_bundle("globex").unwrap(),
+            ImportMode::Merge,
+            false,
+        )
+        .unwrap_err();
+    assert!(err.contains("belongs to tenant acme"), "{}", err);
+
+    assert_eq!(bridge.version(), version);
+    assert!(bridge.get_tenant_rule("acme", "acme-1").is_some());
+}
+
+#[test]
+fn test_versions_listed_per_tenant() {
+    let dir = TempDir::new().unwrap();
+    for bridge in [shared_agent_bridge(), with_shared_agents(open_bridge(&dir))] {
+        let installed = bridge.version();
+        bridge.remove_rule("globex-1").unwrap();
+
+        let acme = bridge.list_tenant_versions("acme", 10).unwrap();
+        assert_eq!(acme.len(), 1);
+        assert_eq!(acme[0].version, installed);
+        assert_eq!(acme[0].rule_count, 2);
+
+        let globex = bridge.list_tenant_versions("globex", 10).unwrap();
+        let counts: Vec<usize> = globex.iter().map(|v| v.rule_count).collect();
+        assert_eq!(counts, vec![0, 1]);
+        assert_eq!(globex[0].version, bridge.version());
+
+        let diff = bridge
+            .diff_tenant_versions("acme", 0, bridge.version())
+            .unwrap();
+        assert_eq!(diff.added, vec!["acme-1", "acme-global"]);
+        let diff = bridge
+            .diff_tenant_versions("globex", installed, bridge.version())
+            .unwrap();
+        assert_eq!(diff.removed, vec!["globex-1"]);
+        assert!(diff.added.is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_removal_rpcs_stay_in_tenant() {
+    let dir = TempDir::new().unwrap();
+    std::env::set_var("HITLOG_DIR", dir.path());
+    std::env::set_var("HITLOG_SQLITE_PATH", dir.path().join("hitlogs.db"));
+    let bridge = Arc::new(shared_agent_bridge());
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+
+    let response = service
+        .remove_policy(Request::new(RemovePolicyRequest {
+            agent_id: "agent-1".to_string(),
+            policy_id: "acme-1".to_string(),
+            tenant_id: "globex".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.success);
+    assert!(bridge.get_rule("acme-1").is_some());
+
+    let response = service
+        .remove_agent_rules(Request::new(RemoveAgentRulesRequest {
+            agent_id: "agent-1".to_string(),
+            tenant_id: "globex".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.rules_removed, 1);
+    assert_eq!(bridge.rule_count(), 2);
+
+    let listed = service
+        .list_rules(Request::new(ListRulesRequest {
+            tenant_id: "acme".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.rules.len(), 2);
+    assert!(listed.rules.iter().all(|rule| rule.tenant_id == "acme"));
+}
+
+#[tokio::test]
+async fn test_rollback_rpc_defaults_to_default_tenant() {
+    let bridge = Arc::new(shared_agent_bridge());
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+    let checkpoint = bridge.version() as i64;
+    bridge.remove_rule("acme-1").unwrap();
+    bridge.remove_rule("globex-1").unwrap();
+
+    // An empty tenant rolls back "default", which owns nothing here.
+    let response = service
+        .rollback_rules(Request::new(RollbackRulesRequest {
+            version: checkpoint,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.diff.unwrap().added.is_empty());
+    assert_eq!(rule_ids(&bridge.all_rules()), vec!["acme-global"]);
+
+    service
+        .rollback_rules(Request::new(RollbackRulesRequest {
+            version: checkpoint,
+            tenant_id: "acme".to_string(),
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    assert!(bridge.get_tenant_rule("acme", "acme-1").is_some());
+    assert!(bridge.get_rule("globex-1").is_none());
+}
+
+#[tokio::test]
+async fn test_enforcement_uses_requesting_tenant_rules() {
+    let bridge = Arc::new(shared_agent_bridge());
+    bridge.remove_rule("globex-1").unwrap();
+    let engine = EnforcementEngine::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+
+    let intent = |tenant: &str| {
+        json!({
+            "id": "evt-1",
+            "schemaVersion": "v1.3",
+            "tenantId": tenant,
+            "timestamp": 1699564800.0,
+            "actor": {"id": "agent-1", "type": "agent"},
+            "action": "read",
+            "resource": {"type": "database", "name": "users_db", "location": "cloud"},
+            "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+            "risk": {"authn": "required"},
+            "layer": "L4"
+        })
+        .to_string()
+    };
+
+    let acme = engine
+        .enforce(&intent("acme"), Some([0.0; 128]), "req-acme", 0.0)
+        .await
+        .unwrap();
+    assert_eq!(acme.rules_evaluated, 2);
+
+    let globex = engine
+        .enforce(&intent("globex"), Some([0.0; 128]), "req-globex", 0.0)
+        .await
+        .unwrap();
+    assert_eq!(globex.rules_evaluated, 0);
+    assert_eq!(globex.decision, 0);
+}
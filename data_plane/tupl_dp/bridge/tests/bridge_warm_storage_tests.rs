@@ -0,0 +1,139 @@
+//! Integration tests for the bridge's memory-mapped warm anchor tier.
+//!
+//! Tests verify:
+//! - Hot-cache misses are served from the warm file instead of cold storage
+//! - Rules written after the snapshot bypass it, in-process and across restarts
+//! - A corrupt warm file is rebuilt from cold storage on startup
+//! - A failed rebuild stops serving the old snapshot
+
+mod common;
+
+use bridge::bridge::{Bridge, StorageConfig};
+use bridge::storage::{HotCache, SqliteRuleStore, WarmStore};
+use bridge::types::{RuleInstance, RuleScope};
+use common::{design_rule, marked_anchors, storage_config};
+use rusqlite::{params, Connection};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str) -> Arc<dyn RuleInstance> {
+    design_rule(rule_id, 10, RuleScope::for_agent("agent-1".to_string()))
+}
+
+/// Opens a bridge with no hot cache, so every lookup goes to the warm or cold tier.
+fn uncached_bridge(config: &StorageConfig) -> Bridge {
+    let store = SqliteRuleStore::open(&config.cold_storage_path).unwrap();
+    Bridge::with_store_and_cache(Box::new(store), HotCache::with_capacity(0))
+        .unwrap()
+        .with_warm_storage(&config.warm_storage_path)
+        .unwrap()
+}
+
+/// Overwrites a rule's anchors in cold storage behind the bridge's back.
+fn overwrite_cold_anchors(config: &StorageConfig, rule_id: &str, marker: usize) {
+    let conn = Connection::open(&config.cold_storage_path).unwrap();
+    conn.execute(
+        "UPDATE rules SET anchors_bin = ?1 WHERE id = ?2",
+        params![marked_anchors(marker).to_le_bytes(), rule_id],
+    )
+    .unwrap();
+}
+
+fn marker_of(bridge: &Bridge, rule_id: &str) -> f32 {
+    bridge.get_rule_anchors(rule_id).unwrap().action_anchors[0][0]
+}
+
+#[test]
+fn test_cache_misses_are_served_from_warm_storage() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    let bridge = uncached_bridge(&config);
+
+    bridge
+        .add_rule_with_anchors(rule("rule-1"), marked_anchors(1))
+        .unwrap();
+    assert_eq!(bridge.rebuild_warm_storage().unwrap(), Some(1));
+
+    overwrite_cold_anchors(&config, "rule-1", 99);
+    assert_eq!(marker_of(&bridge, "rule-1"), 1.0);
+
+    // A write through the bridge takes the rule out of the snapshot.
+    bridge
+        .add_rule_with_anchors(rule("rule-1"), marked_anchors(2))
+        .unwrap();
+    assert_eq!(marker_of(&bridge, "rule-1"), 2.0);
+
+    bridge.remove_rule("rule-1").unwrap();
+    assert!(bridge.get_rule_anchors("rule-1").is_none());
+}
+
+#[test]
+fn test_restart_skips_rules_changed_since_snapshot() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    {
+        let bridge = Bridge::new(config.clone()).unwrap();
+        bridge
+            .add_rule_with_anchors(rule("rule-1"), marked_anchors(1))
+            .unwrap();
+        bridge
+            .add_rule_with_anchors(rule("rule-2"), marked_anchors(2))
+            .unwrap();
+        bridge.rebuild_warm_storage().unwrap();
+
+        bridge
+            .add_rule_with_anchors(rule("rule-2"), marked_anchors(20))
+            .unwrap();
+        bridge
+            .add_rule_with_anchors(rule("rule-3"), marked_anchors(3))
+            .unwrap();
+    }
+
+    let bridge = uncached_bridge(&config);
+    overwrite_cold_anchors(&config, "rule-1", 99);
+    assert_eq!(marker_of(&bridge, "rule-1"), 1.0);
+    assert_eq!(marker_of(&bridge, "rule-2"), 20.0);
+    assert_eq!(marker_of(&bridge, "rule-3"), 3.0);
+}
+
+#[test]
+fn test_corrupt_warm_file_is_rebuilt() {
+    let dir = TempDir::new().unwrap();
+    let config = storage_config(&dir);
+    {
+        let bridge = Bridge::new(config.clone()).unwrap();
+        bridge
+            .add_rule_with_anchors(rule("rule-1"), marked_anchors(1))
+            .unwrap();
+    }
+
+    std::fs::write(&config.warm_storage_path, b"garbage").unwrap();
+    let bridge = uncached_bridge(&config);
+
+    let warm = WarmStore::open(&config.warm_storage_path).unwrap();
+    assert_eq!(warm.len(), 1);
+    assert_eq!(warm.version(), bridge.version());
+    assert_eq!(marker_of(&bridge, "rule-1"), 1.0);
+}
+
+#[test]
+fn test_failed_rebuild_stops_serving_snapshot() {
+    let dir = TempDir::new().unwrap();
+    let config = StorageConfig {
+        warm_storage_path: dir.path().join("warm").join("warm_storage.bin"),
+        cold_storage_path: dir.path().join("cold_storage.db"),
+    };
+    let bridge = uncached_bridge(&config);
+    bridge
+        .add_rule_with_anchors(rule("rule-1"), marked_anchors(1))
+        .unwrap();
+    bridge.rebuild_warm_storage().unwrap();
+
+    // A file where the directory was makes the next rebuild fail.
+    std::fs::remove_dir_all(dir.path().join("warm")).unwrap();
+    std::fs::write(dir.path().join("warm"), b"").unwrap();
+    assert!(bridge.rebuild_warm_storage().is_err());
+
+    overwrite_cold_anchors(&config, "rule-1", 99);
+    assert_eq!(marker_of(&bridge, "rule-1"), 99.0);
+}
@@ -0,0 +1,99 @@
+//! Fixtures shared by the integration tests.
+//!
+//! Each test binary compiles this module on its own and uses a part of it.
+#![allow(dead_code)]
+
+use bridge::bridge::{Bridge, StorageConfig};
+use bridge::enforcement_engine::EnforcementResult;
+use bridge::families::DesignBoundaryRule;
+use bridge::grpc_server::rule_installation::{
+    param_value, AnchorVector, ParamValue, RuleAnchorsPayload,
+};
+use bridge::rule_vector::{RuleVector, MAX_ANCHORS_PER_SLOT, SLOT_WIDTH};
+use bridge::types::{Decision, RuleInstance, RuleScope};
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// A design boundary rule on layer L4, scored with `min`.
+pub fn design_rule(rule_id: &str, priority: u32, scope: RuleScope) -> Arc<dyn RuleInstance> {
+    Arc::new(DesignBoundaryRule::new(
+        rule_id.to_string(),
+        priority,
+        scope,
+        Some("L4".to_string()),
+        1_700_000_000_000,
+        true,
+        None,
+        json!({"rule_type": "design_boundary", "rule_decision": "min"}),
+    ))
+}
+
+/// Warm and cold storage paths inside `dir`.
+pub fn storage_config(dir: &TempDir) -> StorageConfig {
+    StorageConfig {
+        warm_storage_path: dir.path().join("warm_storage.bin"),
+        cold_storage_path: dir.path().join("cold_storage.db"),
+    }
+}
+
+/// Opens a bridge on the storage inside `dir`.
+pub fn open_bridge(dir: &TempDir) -> Bridge {
+    Bridge::new(storage_config(dir)).expect("bridge should open")
+}
+
+/// Anchors whose first action value is `marker`, to tell stored copies apart.
+pub fn marked_anchors(marker: usize) -> RuleVector {
+    let mut vector = RuleVector {
+        action_count: 1,
+        ..Default::default()
+    };
+    vector.action_anchors[0][0] = marker as f32;
+    vector
+}
+
+/// A full anchors payload with one zero anchor per slot, as sent over gRPC.
+pub fn anchors_payload() -> RuleAnchorsPayload {
+    let block = || {
+        (0..MAX_ANCHORS_PER_SLOT)
+            .map(|_| AnchorVector {
+                values: vec![0.0; SLOT_WIDTH],
+            })
+            .collect()
+    };
+    RuleAnchorsPayload {
+        action_anchors: block(),
+        action_count: 1,
+        resource_anchors: block(),
+        resource_count: 1,
+        data_anchors: block(),
+        data_count: 1,
+        risk_anchors: block(),
+        risk_count: 1,
+    }
+}
+
+pub fn string_param(value: &str) -> ParamValue {
+    ParamValue {
+        value: Some(param_value::Value::StringValue(value.to_string())),
+    }
+}
+
+/// Sends enforcement hit logs into `dir`.
+pub fn log_to(dir: &TempDir) {
+    std::env::set_var("HITLOG_DIR", dir.path());
+    std::env::set_var("HITLOG_SQLITE_PATH", dir.path().join("hitlogs.db"));
+}
+
+pub fn decision(result: &EnforcementResult) -> Decision {
+    result
+        .enforcement_decision
+        .as_ref()
+        .unwrap()
+        .decision
+        .clone()
+}
+
+pub fn rule_ids(rules: &[Arc<dyn RuleInstance>]) -> Vec<&str> {
+    rules.iter().map(|rule| rule.rule_id()).collect()
+}
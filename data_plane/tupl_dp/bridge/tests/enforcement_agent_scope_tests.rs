@@ -0,0 +1,138 @@
+//! Integration tests for agent-scoped rule selection during enforcement.
+//!
+//! Tests verify:
+//! - An agent is only evaluated against its own rules plus global rules
+//! - The agent is taken from the actor id, or the rate-limit context without one
+//! - Requests without an agent identity only see global rules
+//! - Telemetry records the agent identity rules were selected for
+
+mod common;
+
+use bridge::bridge::Bridge;
+use bridge::enforcement_engine::EnforcementEngine;
+use bridge::rule_vector::RuleVector;
+use bridge::telemetry::query::{HitlogQuery, QueryFilter};
+use bridge::telemetry::{SessionEvent, TelemetryConfig, TelemetryRecorder};
+use bridge::types::{RuleInstance, RuleScope};
+use common::design_rule;
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn rule(rule_id: &str, scope: RuleScope) -> (Arc<dyn RuleInstance>, RuleVector) {
+    (design_rule(rule_id, 10, scope), RuleVector::default())
+}
+
+fn bridge() -> Arc<Bridge> {
+    let bridge = Bridge::in_memory().unwrap();
+    bridge
+        .add_rules_batch(vec![
+            rule("agent-1-rule", RuleScope::for_agent("agent-1".to_string())),
+            rule("agent-2-rule", RuleScope::for_agent("agent-2".to_string())),
+            rule("global-rule", RuleScope::global()),
+        ])
+        .unwrap();
+    Arc::new(bridge)
+}
+
+fn intent(actor_id: &str, rate_limited_agent: Option<&str>) -> String {
+    let mut intent = json!({
+        "id": "evt-1",
+        "schemaVersion": "v1.3",
+        "tenantId": "",
+        "timestamp": 1699564800.0,
+        "actor": {"id": actor_id, "type": "agent"},
+        "action": "read",
+        "resource": {"type": "database", "name": "users_db", "location": "cloud"},
+        "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+        "risk": {"authn": "required"},
+        "layer": "L4"
+    });
+    if let Some(agent_id) = rate_limited_agent {
+        intent["rate_limit_context"] =
+            json!({"agent_id": agent_id, "window_start": 1699564800.0, "call_count": 1});
+    }
+    intent.to_string()
+}
+
+async fn rules_evaluated(engine: &EnforcementEngine, intent: &str) -> usize {
+    engine
+        .enforce(intent, Some([0.0; 128]), "", 0.0)
+        .await
+        .unwrap()
+        .rules_evaluated
+}
+
+#[tokio::test]
+async fn test_agents_only_see_their_own_and_global_rules() {
+    let engine = EnforcementEngine::new(bridge(), "http://localhost:1".to_string());
+
+    assert_eq!(rules_evaluated(&engine, &intent("agent-1", None)).await, 2);
+    assert_eq!(rules_evaluated(&engine, &intent("agent-3", None)).await, 1);
+
+    // The actor id wins over the rate-limit context; the latter is used without one.
+    assert_eq!(
+        rules_evaluated(&engine, &intent("agent-1", Some("agent-2"))).await,
+        2
+    );
+    assert_eq!(
+        rules_evaluated(&engine, &intent("", Some("agent-2"))).await,
+        2
+    );
+
+    // No agent identity: global rules only.
+    assert_eq!(rules_evaluated(&engine, &intent("", None)).await, 1);
+}
+
+#[tokio::test]
+async fn test_telemetry_records_selected_agent() {
+    let dir = TempDir::new().unwrap();
+    std::env::set_var("HITLOG_SQLITE_PATH", dir.path().join("hitlogs.db"));
+    let telemetry = Arc::new(
+        TelemetryRecorder::new(TelemetryConfig {
+            hitlog_dir: dir.path().to_string_lossy().to_string(),
+            ..TelemetryConfig::default()
+        })
+        .unwrap(),
+    );
+    let engine = EnforcementEngine::with_telemetry(
+        bridge(),
+        "http://localhost:1".to_string(),
+        Some(Arc::clone(&telemetry)),
+    )
+    .unwrap();
+
+    engine
+        .enforce(
+            &intent("", Some("agent-2")),
+            Some([0.0; 128]),
+            "session-1",
+            0.0,
+        )
+        .await
+        .unwrap();
+    telemetry.flush().unwrap();
+
+    let result = HitlogQuery::new(dir.path())
+        .query(&QueryFilter {
+            session_id: Some("session-1".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    let session = &result.sessions[0];
+    assert_eq!(session.agent_id.as_deref(), Some("agent-2"));
+
+    let queried = session
+        .events
+        .iter()
+        .find_map(|event| match event {
+            SessionEvent::RulesQueried {
+                agent_id,
+                rule_count,
+                ..
+            } => Some((agent_id.clone(), *rule_count)),
+            _ => None,
+        })
+        .expect("rules_queried event");
+    assert_eq!(queried, (Some("agent-2".to_string()), 2));
+}
@@ -0,0 +1,155 @@
+//! Integration tests for data sensitivity rules.
+//!
+//! Tests verify:
+//! - Data sensitivity rules install over gRPC without anchors
+//! - They run in the same passes as semantic design boundary rules
+//! - A CONTEXT_DENY match can step up instead of denying
+
+mod common;
+
+use bridge::bridge::Bridge;
+use bridge::enforcement_engine::{EnforcementEngine, EnforcementResult};
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    InstallRulesRequest, RuleInstance as ProtoRuleInstance,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::rule_vector::{RuleVector, SLOT_WIDTH};
+use bridge::types::{Decision, RuleInstance, RuleScope};
+use common::{decision, design_rule, log_to, string_param};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::Request;
+
+fn data_rule(rule_id: &str, policy_type: &str, params: &[(&str, &str)]) -> ProtoRuleInstance {
+    let mut rule = ProtoRuleInstance {
+        rule_id: rule_id.to_string(),
+        layer: "L4".to_string(),
+        priority: 10,
+        enabled: true,
+        policy_type: policy_type.to_string(),
+        family_id: "data_sensitivity".to_string(),
+        ..Default::default()
+    };
+    for (key, value) in params {
+        rule.params.insert(key.to_string(), string_param(value));
+    }
+    rule
+}
+
+/// A semantic allow rule whose anchors match the uniform intent vector used below.
+fn semantic_allow_rule() -> (Arc<dyn RuleInstance>, RuleVector) {
+    let rule = design_rule("db-reads", 5, RuleScope::global());
+    let anchor = [1.0 / (SLOT_WIDTH as f32).sqrt(); SLOT_WIDTH];
+    let mut anchors = RuleVector {
+        action_count: 1,
+        resource_count: 1,
+        data_count: 1,
+        risk_count: 1,
+        ..Default::default()
+    };
+    anchors.action_anchors[0] = anchor;
+    anchors.resource_anchors[0] = anchor;
+    anchors.data_anchors[0] = anchor;
+    anchors.risk_anchors[0] = anchor;
+    (rule, anchors)
+}
+
+async fn bridge() -> Arc<Bridge> {
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+    let response = service
+        .install_rules(Request::new(InstallRulesRequest {
+            rules: vec![
+                data_rule(
+                    "restricted-bulk",
+                    "forbidden",
+                    &[("sensitivity", "restricted"), ("volume", "bulk")],
+                ),
+                data_rule(
+                    "pii-step-up",
+                    "context_deny",
+                    &[
+                        ("pii", "true"),
+                        ("authn_not", "strong"),
+                        ("on_match", "step_up"),
+                    ],
+                ),
+            ],
+            atomic: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+
+    let (rule, anchors) = semantic_allow_rule();
+    bridge.add_rule_with_anchors(rule, anchors).unwrap();
+    bridge
+}
+
+async fn enforce(bridge: &Arc<Bridge>, data: Value, authn: &str) -> EnforcementResult {
+    let intent = json!({
+        "id": "evt-1",
+        "schemaVersion": "v1.3",
+        "tenantId": "",
+        "timestamp": 1699564800.0,
+        "actor": {"id": "agent-1", "type": "agent"},
+        "action": "read",
+        "resource": {"type": "database", "name": "users_db", "location": "cloud"},
+        "data": data,
+        "risk": {"authn": authn},
+        "layer": "L4"
+    });
+    let encoded = [1.0 / (SLOT_WIDTH as f32).sqrt(); 128];
+    EnforcementEngine::new(Arc::clone(bridge), "http://localhost:1".to_string())
+        .enforce(&intent.to_string(), Some(encoded), "", 0.0)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_data_rules_run_alongside_semantic_rules() {
+    let dir = TempDir::new().unwrap();
+    log_to(&dir);
+    let bridge = bridge().await;
+
+    // No data condition holds, so the semantic allow rule decides.
+    let internal = enforce(
+        &bridge,
+        json!({"sensitivity": ["internal"], "pii": false, "volume": "single"}),
+        "required",
+    )
+    .await;
+    assert_eq!(decision(&internal), Decision::Allow);
+    let evaluated: Vec<(&str, &str)> = internal
+        .evidence
+        .iter()
+        .map(|evidence| (evidence.rule_id.as_str(), evidence.scoring_mode.as_str()))
+        .collect();
+    assert_eq!(
+        evaluated,
+        vec![
+            ("restricted-bulk", "deterministic"),
+            ("pii-step-up", "deterministic"),
+            ("db-reads", "min"),
+        ]
+    );
+
+    let bulk = enforce(
+        &bridge,
+        json!({"sensitivity": ["restricted"], "pii": false, "volume": "bulk"}),
+        "strong",
+    )
+    .await;
+    assert_eq!(decision(&bulk), Decision::Deny);
+    assert_eq!(bulk.evidence.len(), 1);
+
+    let pii = json!({"sensitivity": ["confidential"], "pii": true, "volume": "single"});
+    let weak = enforce(&bridge, pii.clone(), "required").await;
+    assert_eq!(decision(&weak), Decision::StepUp);
+    let strong = enforce(&bridge, pii, "strong").await;
+    assert_eq!(decision(&strong), Decision::Allow);
+}
@@ -0,0 +1,193 @@
+//! Integration tests for network egress rules.
+//!
+//! Tests verify:
+//! - Egress rules install over gRPC without anchors, with list params
+//! - FORBIDDEN ranges deny before an allowlist is consulted
+//! - Allowlists cover every destination of a call, from the location and tool params
+//! - Hostnames are resolved for rules that ask for it, and a failed lookup denies
+
+mod common;
+
+use bridge::bridge::Bridge;
+use bridge::enforcement_engine::{EnforcementEngine, EnforcementResult};
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    param_value, InstallRulesRequest, ParamValue, RuleInstance as ProtoRuleInstance, StringList,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::types::Decision;
+use common::{decision, log_to, string_param};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::Request;
+
+fn list_param(values: &[&str]) -> ParamValue {
+    ParamValue {
+        value: Some(param_value::Value::StringList(StringList {
+            values: values.iter().map(|value| value.to_string()).collect(),
+        })),
+    }
+}
+
+fn egress_rule(rule_id: &str, policy_type: &str, params: &[(&str, &[&str])]) -> ProtoRuleInstance {
+    let mut rule = ProtoRuleInstance {
+        rule_id: rule_id.to_string(),
+        agent_id: "agent-1".to_string(),
+        layer: "L0".to_string(),
+        priority: 10,
+        enabled: true,
+        policy_type: policy_type.to_string(),
+        family_id: "net_egress".to_string(),
+        ..Default::default()
+    };
+    for (key, values) in params {
+        rule.params.insert(key.to_string(), list_param(values));
+    }
+    rule
+}
+
+async fn bridge() -> Arc<Bridge> {
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+    let response = service
+        .install_rules(Request::new(InstallRulesRequest {
+            agent_id: "agent-1".to_string(),
+            rules: vec![
+                egress_rule(
+                    "no-metadata-endpoint",
+                    "forbidden",
+                    &[("dest_cidrs", &["169.254.0.0/16", "fd00:ec2::254/128"])],
+                ),
+                egress_rule(
+                    "partner-apis",
+                    "context_allow",
+                    &[
+                        ("dest_domains", &["api.example.com", "*.partner.io"]),
+                        ("dest_cidrs", &["169.254.0.0/16"]),
+                        ("ports", &["443"]),
+                        ("protocols", &["https"]),
+                    ],
+                ),
+            ],
+            atomic: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+    bridge
+}
+
+async fn enforce(bridge: &Arc<Bridge>, location: &str, tool_params: Value) -> EnforcementResult {
+    let intent = json!({
+        "id": "evt-1",
+        "schemaVersion": "v1.3",
+        "tenantId": "",
+        "timestamp": 1699564800.0,
+        "actor": {"id": "agent-1", "type": "agent"},
+        "action": "execute",
+        "resource": {"type": "api", "name": "http.fetch", "location": location},
+        "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+        "risk": {"authn": "required"},
+        "layer": "L0",
+        "tool_name": "http.fetch",
+        "tool_params": tool_params
+    });
+    EnforcementEngine::new(Arc::clone(bridge), "http://localhost:1".to_string())
+        .enforce(&intent.to_string(), Some([0.0; 128]), "", 0.0)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_egress_rules_decide_destinations() {
+    let dir = TempDir::new().unwrap();
+    log_to(&dir);
+    let bridge = bridge().await;
+    assert_eq!(
+        bridge.get_rule("partner-apis").unwrap().family_id(),
+        "net_egress"
+    );
+
+    let allowed = enforce(&bridge, "https://api.example.com/v1/orders", json!({})).await;
+    assert_eq!(decision(&allowed), Decision::Allow);
+    let partner = enforce(
+        &bridge,
+        "cloud",
+        json!({"url": "https://eu.partner.io/hook"}),
+    )
+    .await;
+    assert_eq!(decision(&partner), Decision::Allow);
+
+    // Off the allowlist: wrong host, port or protocol, or one destination among several.
+    for (location, tool_params) in [
+        ("https://example.org", json!({})),
+        ("https://api.example.com:8443", json!({})),
+        ("http://api.example.com", json!({})),
+        (
+            "https://api.example.com",
+            json!({"url": "https://paste.example.org"}),
+        ),
+    ] {
+        let result = enforce(&bridge, location, tool_params).await;
+        assert_eq!(decision(&result), Decision::Deny, "{}", location);
+    }
+
+    // The metadata range is on the allowlist too, but FORBIDDEN wins.
+    let metadata = enforce(&bridge, "https://169.254.169.254:443/latest", json!({})).await;
+    assert_eq!(decision(&metadata), Decision::Deny);
+    assert_eq!(metadata.evidence.len(), 1);
+    assert_eq!(metadata.evidence[0].rule_id, "no-metadata-endpoint");
+    assert_eq!(metadata.evidence[0].scoring_mode, "deterministic");
+    assert!(
+        metadata.evidence[0]
+            .anchor_matched
+            .contains("169.254.169.254"),
+        "{}",
+        metadata.evidence[0].anchor_matched
+    );
+}
+
+#[tokio::test]
+async fn test_unresolvable_hosts_are_denied() {
+    let dir = TempDir::new().unwrap();
+    log_to(&dir);
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    let mut internal = egress_rule(
+        "no-internal",
+        "forbidden",
+        &[("dest_cidrs", &["10.0.0.0/8"])],
+    );
+    internal
+        .params
+        .insert("resolve_dns".to_string(), string_param("true"));
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+    let response = service
+        .install_rules(Request::new(InstallRulesRequest {
+            agent_id: "agent-1".to_string(),
+            rules: vec![internal, egress_rule("anywhere", "context_allow", &[])],
+            atomic: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+
+    let loopback = enforce(&bridge, "https://localhost:8443", json!({})).await;
+    assert_eq!(decision(&loopback), Decision::Allow);
+
+    let nested = json!({"request": {"mirrors": ["https://no-such-host.invalid/upload"]}});
+    let unresolved = enforce(&bridge, "cloud", nested).await;
+    assert_eq!(decision(&unresolved), Decision::Deny);
+    assert_eq!(unresolved.evidence[0].rule_id, "no-internal");
+    assert!(
+        unresolved.evidence[0]
+            .anchor_matched
+            .contains("could not be resolved"),
+        "{}",
+        unresolved.evidence[0].anchor_matched
+    );
+}
@@ -0,0 +1,265 @@
+//! Integration tests for rate limit rules.
+//!
+//! Tests verify:
+//! - Calls over a limit are denied, or stepped up, per agent
+//! - Calls are counted even when another rule denies them, but not in staged dry runs
+//! - Client-reported rate limit context does not affect the count
+//! - Counters can be inspected and reset over gRPC
+
+mod common;
+
+use bridge::bridge::Bridge;
+use bridge::enforcement_engine::EnforcementEngine;
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    param_value, GetRateLimitsRequest, InstallRulesRequest, ParamValue, RateLimitSelector,
+    ResetRateLimitsRequest, RuleInstance as ProtoRuleInstance, StageRulesRequest,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::types::Decision;
+use common::{decision, log_to};
+use serde_json::json;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::Request;
+
+fn param(value: param_value::Value) -> ParamValue {
+    ParamValue { value: Some(value) }
+}
+
+fn proto_rule(
+    rule_id: &str,
+    policy_type: &str,
+    params: Vec<(&str, ParamValue)>,
+) -> ProtoRuleInstance {
+    ProtoRuleInstance {
+        rule_id: rule_id.to_string(),
+        layer: "L4".to_string(),
+        priority: 10,
+        enabled: true,
+        policy_type: policy_type.to_string(),
+        params: params
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn service(dir: &TempDir) -> (Arc<Bridge>, DataPlaneService) {
+    log_to(dir);
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+    (bridge, service)
+}
+
+/// Allows any `search` call, with at most two calls an hour per agent.
+fn rules(on_match: &str) -> Vec<ProtoRuleInstance> {
+    let text = |value: &str| param(param_value::Value::StringValue(value.to_string()));
+    let int = |value: i64| param(param_value::Value::IntValue(value));
+    vec![
+        proto_rule(
+            "search-limit",
+            "forbidden",
+            vec![
+                ("rule_type", text("rate_limit")),
+                ("limit", int(2)),
+                ("window_secs", int(3600)),
+                ("per", text("agent")),
+                ("on_match", text(on_match)),
+            ],
+        ),
+        proto_rule(
+            "search",
+            "context_allow",
+            vec![
+                ("rule_type", text("tool_constraint")),
+                ("tool_name", text("search")),
+            ],
+        ),
+    ]
+}
+
+async fn install(service: &DataPlaneService, on_match: &str) {
+    let response = service
+        .install_rules(Request::new(InstallRulesRequest {
+            rules: rules(on_match),
+            atomic: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+}
+
+fn intent(agent_id: &str, tool_name: &str) -> String {
+    json!({
+        "id": "evt-1",
+        "schemaVersion": "v1.3",
+        "tenantId": "",
+        "timestamp": 1699564800.0,
+        "actor": {"id": agent_id, "type": "agent"},
+        "action": "read",
+        "resource": {"type": "api", "name": tool_name, "location": "cloud"},
+        "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+        "risk": {"authn": "required"},
+        "layer": "L4",
+        "tool_name": tool_name,
+        // Forged: the data plane keeps its own count.
+        "rate_limit_context": {"agent_id": agent_id, "window_start": 0.0, "call_count": 0}
+    })
+    .to_string()
+}
+
+fn engine(bridge: &Arc<Bridge>) -> EnforcementEngine {
+    EnforcementEngine::new(Arc::clone(bridge), "http://localhost:1".to_string())
+}
+
+async fn enforce(bridge: &Arc<Bridge>, agent_id: &str) -> Decision {
+    enforce_tool(bridge, agent_id, "search").await
+}
+
+async fn enforce_tool(bridge: &Arc<Bridge>, agent_id: &str, tool_name: &str) -> Decision {
+    let result = engine(bridge)
+        .enforce(&intent(agent_id, tool_name), Some([0.0; 128]), "", 0.0)
+        .await
+        .unwrap();
+    decision(&result)
+}
+
+#[tokio::test]
+async fn test_calls_over_the_limit_are_denied() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    install(&service, "deny").await;
+
+    assert_eq!(enforce(&bridge, "agent-1").await, Decision::Allow);
+    assert_eq!(enforce(&bridge, "agent-1").await, Decision::Allow);
+    assert_eq!(enforce(&bridge, "agent-1").await, Decision::Deny);
+    assert_eq!(enforce(&bridge, "agent-2").await, Decision::Allow);
+}
+
+#[tokio::test]
+async fn test_calls_over_the_limit_can_step_up() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    install(&service, "step_up").await;
+
+    for _ in 0..2 {
+        assert_eq!(enforce(&bridge, "agent-1").await, Decision::Allow);
+    }
+    assert_eq!(enforce(&bridge, "agent-1").await, Decision::StepUp);
+}
+
+#[tokio::test]
+async fn test_calls_denied_by_other_rules_are_counted() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    install(&service, "deny").await;
+
+    // No rule allows `delete`, but the limit counts the call before that is decided.
+    assert_eq!(
+        enforce_tool(&bridge, "agent-1", "delete").await,
+        Decision::Deny
+    );
+    assert_eq!(enforce(&bridge, "agent-1").await, Decision::Allow);
+    assert_eq!(enforce(&bridge, "agent-1").await, Decision::Deny);
+}
+
+#[tokio::test]
+async fn test_staged_dry_runs_are_not_counted() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    let response = service
+        .stage_rules(Request::new(StageRulesRequest {
+            rules: rules("deny"),
+            replace: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+
+    for _ in 0..3 {
+        let result = engine(&bridge)
+            .enforce_staged(&intent("agent-1", "search"), Some([0.0; 128]), "", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(decision(&result), Decision::Allow);
+    }
+    assert!(bridge.rate_limits().states(&Default::default()).is_empty());
+}
+
+#[tokio::test]
+async fn test_counters_are_inspected_and_reset_over_grpc() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    install(&service, "deny").await;
+    for _ in 0..3 {
+        enforce(&bridge, "agent-1").await;
+    }
+    enforce(&bridge, "agent-2").await;
+
+    let selector = |agent_id: &str| {
+        Some(RateLimitSelector {
+            agent_id: agent_id.to_string(),
+            ..Default::default()
+        })
+    };
+    let states = service
+        .get_rate_limits(Request::new(GetRateLimitsRequest {
+            selector: selector(""),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .states;
+    let counters: Vec<(&str, &str, &str, i64, i64)> = states
+        .iter()
+        .map(|state| {
+            (
+                state.rule_id.as_str(),
+                state.agent_id.as_str(),
+                state.tool_name.as_str(),
+                state.capacity,
+                state.remaining,
+            )
+        })
+        .collect();
+    assert_eq!(
+        counters,
+        vec![
+            ("search-limit", "agent-1", "", 2, 0),
+            ("search-limit", "agent-2", "", 2, 1)
+        ]
+    );
+    assert_eq!(states[0].algorithm, "sliding_window");
+    assert!(states[0].retry_after_ms > 0);
+
+    let reset = service
+        .reset_rate_limits(Request::new(ResetRateLimitsRequest {
+            selector: selector("agent-1"),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(reset.success);
+    assert_eq!(reset.reset_count, 1);
+    assert_eq!(enforce(&bridge, "agent-1").await, Decision::Allow);
+
+    // Counters belong to their tenant.
+    let other_tenant = service
+        .get_rate_limits(Request::new(GetRateLimitsRequest {
+            selector: Some(RateLimitSelector {
+                tenant_id: "acme".to_string(),
+                ..Default::default()
+            }),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(other_tenant.states.is_empty());
+}
@@ -0,0 +1,295 @@
+//! Integration tests for rule selector tags.
+//!
+//! Tests verify:
+//! - Tagged rules only apply when every tag matches a label of the request
+//! - Labels registered for an agent take precedence over labels in the request context
+//! - Registered labels are persisted, and an empty set removes them
+//! - The label and listing RPCs expose labels and tags
+//! - Removing an agent's rules leaves tag-selected rules in place
+
+mod common;
+
+use bridge::bridge::{Bridge, StorageConfig};
+use bridge::enforcement_engine::EnforcementEngine;
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    GetAgentLabelsRequest, InstallRulesRequest, ListRulesRequest, RemoveAgentRulesRequest,
+    RuleInstance as ProtoRuleInstance, SetAgentLabelsRequest,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope, DEFAULT_TENANT};
+use common::{design_rule, log_to, string_param};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::Request;
+
+fn rule(rule_id: &str, scope: RuleScope) -> (Arc<dyn RuleInstance>, RuleVector) {
+    (design_rule(rule_id, 10, scope), RuleVector::default())
+}
+
+fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// An untagged global rule, a fleet rule for prod and a rule for agent-1's payments team.
+fn bridge() -> Arc<Bridge> {
+    let bridge = Bridge::in_memory().unwrap();
+    bridge
+        .add_rules_batch(vec![
+            rule("global-rule", RuleScope::global()),
+            rule(
+                "prod-fleet",
+                RuleScope::global().with_tag("env".to_string(), "prod".to_string()),
+            ),
+            rule(
+                "agent-1-payments",
+                RuleScope::for_agent("agent-1".to_string())
+                    .with_tags(labels(&[("team", "payments"), ("env", "prod")])),
+            ),
+        ])
+        .unwrap();
+    Arc::new(bridge)
+}
+
+fn intent(actor_id: &str, context_labels: Value) -> String {
+    json!({
+        "id": "evt-1",
+        "schemaVersion": "v1.3",
+        "tenantId": "",
+        "timestamp": 1699564800.0,
+        "actor": {"id": actor_id, "type": "agent"},
+        "action": "read",
+        "resource": {"type": "database", "name": "users_db", "location": "cloud"},
+        "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+        "risk": {"authn": "required"},
+        "context": {"labels": context_labels},
+        "layer": "L4"
+    })
+    .to_string()
+}
+
+async fn rules_evaluated(engine: &EnforcementEngine, intent: &str) -> usize {
+    engine
+        .enforce(intent, Some([0.0; 128]), "", 0.0)
+        .await
+        .unwrap()
+        .rules_evaluated
+}
+
+#[tokio::test]
+async fn test_tagged_rules_match_context_labels() {
+    let engine = EnforcementEngine::new(bridge(), "http://localhost:1".to_string());
+
+    assert_eq!(
+        rules_evaluated(&engine, &intent("agent-1", json!({}))).await,
+        1
+    );
+    assert_eq!(
+        rules_evaluated(&engine, &intent("agent-2", json!({"env": "prod"}))).await,
+        2
+    );
+    assert_eq!(
+        rules_evaluated(&engine, &intent("agent-1", json!({"env": "staging"}))).await,
+        1
+    );
+
+    // Every tag has to match.
+    assert_eq!(
+        rules_evaluated(&engine, &intent("agent-1", json!({"env": "prod"}))).await,
+        2
+    );
+    assert_eq!(
+        rules_evaluated(
+            &engine,
+            &intent("agent-1", json!({"env": "prod", "team": "payments"}))
+        )
+        .await,
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_registered_labels_take_precedence() {
+    let bridge = bridge();
+    bridge
+        .set_agent_labels("", "agent-1", labels(&[("env", "staging")]))
+        .unwrap();
+    bridge
+        .set_agent_labels(DEFAULT_TENANT, "agent-2", labels(&[("env", "prod")]))
+        .unwrap();
+    let engine = EnforcementEngine::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+
+    // agent-1 is registered as staging, whatever the request claims.
+    assert_eq!(
+        rules_evaluated(&engine, &intent("agent-1", json!({"env": "prod"}))).await,
+        1
+    );
+    // agent-2 gets the prod fleet rule without carrying any labels.
+    assert_eq!(
+        rules_evaluated(&engine, &intent("agent-2", json!({}))).await,
+        2
+    );
+
+    // Labels are confined to their tenant.
+    assert!(bridge.agent_labels("acme", "agent-2").is_empty());
+}
+
+#[test]
+fn test_agent_labels_are_persisted() {
+    let dir = TempDir::new().unwrap();
+    let config = StorageConfig {
+        warm_storage_path: dir.path().join("warm_storage.bin"),
+        cold_storage_path: dir.path().join("cold_storage.db"),
+    };
+
+    let bridge = Bridge::new(config.clone()).unwrap();
+    let version = bridge.version();
+    bridge
+        .set_agent_labels("acme", "agent-1", labels(&[("env", "prod")]))
+        .unwrap();
+    bridge
+        .set_agent_labels("acme", "agent-2", labels(&[("team", "db")]))
+        .unwrap();
+    bridge
+        .set_agent_labels("acme", "agent-2", HashMap::new())
+        .unwrap();
+    assert!(bridge
+        .set_agent_labels("acme", "", labels(&[("env", "prod")]))
+        .is_err());
+    assert!(bridge
+        .set_agent_labels("acme", "agent-3", labels(&[("", "prod")]))
+        .is_err());
+    // Labels are not rule-set changes.
+    assert_eq!(bridge.version(), version);
+    drop(bridge);
+
+    let bridge = Bridge::new(config).unwrap();
+    assert_eq!(
+        bridge.agent_labels("acme", "agent-1"),
+        labels(&[("env", "prod")])
+    );
+    assert!(bridge.agent_labels("acme", "agent-2").is_empty());
+    assert!(bridge.agent_labels("acme", "agent-3").is_empty());
+}
+
+#[tokio::test]
+async fn test_label_and_listing_rpcs() {
+    let dir = TempDir::new().unwrap();
+    log_to(&dir);
+    let bridge = bridge();
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+
+    let response = service
+        .set_agent_labels(Request::new(SetAgentLabelsRequest {
+            tenant_id: String::new(),
+            agent_id: "agent-1".to_string(),
+            labels: labels(&[("env", "prod")]),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+
+    let response = service
+        .set_agent_labels(Request::new(SetAgentLabelsRequest {
+            tenant_id: String::new(),
+            agent_id: String::new(),
+            labels: labels(&[("env", "prod")]),
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.success);
+
+    let registered = service
+        .get_agent_labels(Request::new(GetAgentLabelsRequest {
+            tenant_id: DEFAULT_TENANT.to_string(),
+            agent_id: "agent-1".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(registered.labels, labels(&[("env", "prod")]));
+
+    let listed = service
+        .list_rules(Request::new(ListRulesRequest::default()))
+        .await
+        .unwrap()
+        .into_inner();
+    let fleet = listed
+        .rules
+        .iter()
+        .find(|rule| rule.rule_id == "prod-fleet")
+        .unwrap();
+    assert_eq!(fleet.tags, labels(&[("env", "prod")]));
+}
+
+fn tool_rule(rule_id: &str, agent_id: &str, tags: &[(&str, &str)]) -> ProtoRuleInstance {
+    let params = [("tool_name", "db.*"), ("constraints", "[]")];
+    ProtoRuleInstance {
+        rule_id: rule_id.to_string(),
+        agent_id: agent_id.to_string(),
+        layer: "L4".to_string(),
+        priority: 10,
+        enabled: true,
+        policy_type: "context_allow".to_string(),
+        family_id: "tool_constraint".to_string(),
+        tags: labels(tags),
+        params: params
+            .iter()
+            .map(|(key, value)| (key.to_string(), string_param(value)))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_removing_agent_rules_keeps_tagged_rules() {
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+    let response = service
+        .install_rules(Request::new(InstallRulesRequest {
+            rules: vec![
+                tool_rule("prod-fleet", "", &[("env", "prod")]),
+                tool_rule("agent-1-tools", "agent-1", &[]),
+                tool_rule("untagged", "", &[]),
+            ],
+            atomic: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+
+    // Only the tagged rule becomes global; an untagged one keeps the "" agent.
+    assert!(bridge.get_rule("prod-fleet").unwrap().scope().is_global);
+    assert!(bridge
+        .get_rule("untagged")
+        .unwrap()
+        .scope()
+        .is_scoped_to(""));
+
+    for (agent_id, removed) in [("agent-1", "agent-1-tools"), ("", "untagged")] {
+        let response = service
+            .remove_agent_rules(Request::new(RemoveAgentRulesRequest {
+                agent_id: agent_id.to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.rules_removed, 1);
+        assert!(bridge.get_rule(removed).is_none());
+    }
+    assert!(bridge.get_rule("prod-fleet").is_some());
+    assert_eq!(bridge.rule_count(), 1);
+}
@@ -0,0 +1,157 @@
+//! Integration tests for tool constraint rules.
+//!
+//! Tests verify:
+//! - Tool constraint rules install over gRPC without anchors
+//! - They feed the same FORBIDDEN/CONTEXT_* passes as semantic rules
+//! - Evidence names the constraint that decided the call
+//! - They are restored as tool constraint rules after a restart
+//! - A rule set without anchor-based rules is enforced without encoding the intent
+
+mod common;
+
+use bridge::bridge::{Bridge, StorageConfig};
+use bridge::enforcement_engine::{EnforcementEngine, EnforcementResult};
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    InstallRulesRequest, RuleInstance as ProtoRuleInstance,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::types::Decision;
+use common::{decision, log_to, string_param};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::Request;
+
+fn tool_rule(
+    rule_id: &str,
+    policy_type: &str,
+    tool_name: &str,
+    constraints: Value,
+) -> ProtoRuleInstance {
+    let params = [
+        ("rule_type", "tool_constraint".to_string()),
+        ("tool_name", tool_name.to_string()),
+        ("constraints", constraints.to_string()),
+    ];
+    ProtoRuleInstance {
+        rule_id: rule_id.to_string(),
+        agent_id: "agent-1".to_string(),
+        layer: "L4".to_string(),
+        priority: 10,
+        enabled: true,
+        policy_type: policy_type.to_string(),
+        params: params
+            .iter()
+            .map(|(key, value)| (key.to_string(), string_param(value)))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+async fn install(bridge: &Arc<Bridge>) {
+    let service = DataPlaneService::new(Arc::clone(bridge), "http://localhost:1".to_string());
+    let response = service
+        .install_rules(Request::new(InstallRulesRequest {
+            agent_id: "agent-1".to_string(),
+            rules: vec![
+                tool_rule(
+                    "transfer-limit",
+                    "context_allow",
+                    "payments.transfer",
+                    json!([{"param": "amount", "type": "float", "required": true,
+                            "min": 0, "max_exclusive": 1000}]),
+                ),
+                tool_rule(
+                    "no-bulk-export",
+                    "forbidden",
+                    "db.*",
+                    json!([{"param": "limit", "type": "int", "max": 100}]),
+                ),
+                tool_rule("db-reads", "context_allow", "db.*", json!([])),
+            ],
+            atomic: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+}
+
+async fn enforce(bridge: &Arc<Bridge>, tool_name: &str, tool_params: Value) -> EnforcementResult {
+    let intent = json!({
+        "id": "evt-1",
+        "schemaVersion": "v1.3",
+        "tenantId": "",
+        "timestamp": 1699564800.0,
+        "actor": {"id": "agent-1", "type": "agent"},
+        "action": "execute",
+        "resource": {"type": "api", "name": tool_name, "location": "cloud"},
+        "data": {"sensitivity": ["internal"], "pii": false, "volume": "single"},
+        "risk": {"authn": "required"},
+        "layer": "L4",
+        "tool_name": tool_name,
+        "tool_params": tool_params
+    });
+    // No encoder listens there; only anchor-based rules would need one.
+    EnforcementEngine::new(Arc::clone(bridge), "http://localhost:1".to_string())
+        .enforce(&intent.to_string(), None, "", 0.0)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_constraints_decide_tool_calls() {
+    let dir = TempDir::new().unwrap();
+    log_to(&dir);
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    install(&bridge).await;
+
+    let allowed = enforce(&bridge, "payments.transfer", json!({"amount": 250})).await;
+    assert_eq!(decision(&allowed), Decision::Allow);
+
+    // Over the limit: no allow rule matches, so the call fails closed.
+    let over = enforce(&bridge, "payments.transfer", json!({"amount": 1000})).await;
+    assert_eq!(decision(&over), Decision::Deny);
+    let limit = over
+        .evidence
+        .iter()
+        .find(|evidence| evidence.rule_id == "transfer-limit")
+        .unwrap();
+    assert_eq!(limit.decision, 0);
+    assert!(
+        limit.anchor_matched.contains("amount"),
+        "{}",
+        limit.anchor_matched
+    );
+    assert_eq!(limit.scoring_mode, "deterministic");
+
+    // FORBIDDEN runs before any allow rule.
+    let small = enforce(&bridge, "db.query", json!({"limit": 10})).await;
+    assert_eq!(decision(&small), Decision::Allow);
+    let bulk = enforce(&bridge, "db.query", json!({"limit": 5000})).await;
+    assert_eq!(decision(&bulk), Decision::Deny);
+    assert_eq!(bulk.evidence.len(), 1);
+    assert_eq!(bulk.evidence[0].rule_id, "no-bulk-export");
+}
+
+#[tokio::test]
+async fn test_tool_constraint_rules_survive_restart() {
+    let dir = TempDir::new().unwrap();
+    log_to(&dir);
+    let config = StorageConfig {
+        warm_storage_path: dir.path().join("warm_storage.bin"),
+        cold_storage_path: dir.path().join("cold_storage.db"),
+    };
+    install(&Arc::new(Bridge::new(config.clone()).unwrap())).await;
+
+    let bridge = Arc::new(Bridge::new(config).unwrap());
+    assert!(bridge.quarantined_on_load().is_empty());
+    assert_eq!(
+        bridge.get_rule("transfer-limit").unwrap().family_id(),
+        "tool_constraint"
+    );
+    let over = enforce(&bridge, "payments.transfer", json!({"amount": 5000})).await;
+    assert_eq!(decision(&over), Decision::Deny);
+}
@@ -0,0 +1,435 @@
+//! Integration tests for signed rule installs.
+//!
+//! Tests verify:
+//! - With trusted keys configured, unsigned and mis-signed installs are rejected
+//!   before the bridge changes
+//! - A correctly signed batch installs and records the signer on each rule
+//! - Bundle imports and every other request that changes rules must be signed
+//!   commands bound to their method
+//! - Replayed or expired payloads are rejected, also after a restart
+//! - Install options are taken from the signed batch
+
+mod common;
+
+use bridge::bridge::Bridge;
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    ExportRuleBundleRequest, ImportRuleBundleRequest, InstallRulesRequest, ListRulesRequest,
+    PromoteStagedRulesRequest, RemoveAgentRulesRequest, ResetRateLimitsRequest,
+    RollbackRulesRequest, RuleInstance, RuleSignature, RuleStatusRequest, SetAgentLabelsRequest,
+    SignedCommand, SignedRuleBatch,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::signing::{sign, TrustedKeys};
+use bridge::types::now_ms;
+use common::{anchors_payload, log_to, open_bridge, string_param};
+use ed25519_dalek::SigningKey;
+use prost::Message;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::{Code, Request};
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32])
+}
+
+fn service(dir: &TempDir) -> (Arc<Bridge>, DataPlaneService) {
+    log_to(dir);
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    let service = trusting_service(Arc::clone(&bridge));
+    (bridge, service)
+}
+
+/// A service over `bridge` that requires requests signed by `signing_key`.
+fn trusting_service(bridge: Arc<Bridge>) -> DataPlaneService {
+    let mut keys = TrustedKeys::new();
+    keys.insert("ci", signing_key().verifying_key().as_bytes())
+        .unwrap();
+    DataPlaneService::new(bridge, "http://localhost:1".to_string()).with_trusted_keys(Some(keys))
+}
+
+fn rule(rule_id: &str) -> RuleInstance {
+    let params = HashMap::from([("rule_type".to_string(), string_param("design_boundary"))]);
+    RuleInstance {
+        rule_id: rule_id.to_string(),
+        agent_id: "agent-1".to_string(),
+        layer: "L4".to_string(),
+        priority: 10,
+        enabled: true,
+        params,
+        anchors: Some(anchors_payload()),
+        ..Default::default()
+    }
+}
+
+fn expires_at() -> i64 {
+    now_ms() as i64 + 60_000
+}
+
+fn signature(key: &SigningKey, payload: &[u8]) -> Option<RuleSignature> {
+    Some(RuleSignature {
+        key_id: "ci".to_string(),
+        signature: sign(key, payload),
+    })
+}
+
+fn signed_request(key: &SigningKey, agent_id: &str, sequence: u64) -> InstallRulesRequest {
+    let batch = SignedRuleBatch {
+        agent_id: agent_id.to_string(),
+        rules: vec![rule("rule-1")],
+        sequence,
+        expires_at_ms: expires_at(),
+        atomic: true,
+        ..Default::default()
+    }
+    .encode_to_vec();
+    InstallRulesRequest {
+        agent_id: "agent-1".to_string(),
+        signature: signature(key, &batch),
+        signed_batch: batch,
+        ..Default::default()
+    }
+}
+
+/// Encodes `request` as a SignedCommand for `method`.
+fn command(method: &str, request: impl Message, sequence: u64) -> Vec<u8> {
+    SignedCommand {
+        method: method.to_string(),
+        request: request.encode_to_vec(),
+        sequence,
+        expires_at_ms: expires_at(),
+    }
+    .encode_to_vec()
+}
+
+#[tokio::test]
+async fn test_unsigned_and_mis_signed_installs_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+
+    let unsigned = InstallRulesRequest {
+        agent_id: "agent-1".to_string(),
+        rules: vec![rule("rule-1")],
+        ..Default::default()
+    };
+    let err = service
+        .install_rules(Request::new(unsigned))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let forged = signed_request(&SigningKey::from_bytes(&[9u8; 32]), "agent-1", 1);
+    let err = service
+        .install_rules(Request::new(forged))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let mut tampered = signed_request(&signing_key(), "agent-1", 1);
+    tampered.signed_batch.push(0);
+    let err = service
+        .install_rules(Request::new(tampered))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let other_agent = signed_request(&signing_key(), "agent-2", 1);
+    assert!(service
+        .install_rules(Request::new(other_agent))
+        .await
+        .is_err());
+
+    assert_eq!(bridge.rule_count(), 0);
+    assert_eq!(bridge.version(), 0);
+}
+
+#[tokio::test]
+async fn test_signed_install_records_signer() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+
+    let response = service
+        .install_rules(Request::new(signed_request(&signing_key(), "agent-1", 1)))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+    assert_eq!(bridge.get_rule("rule-1").unwrap().signed_by(), Some("ci"));
+
+    let listed = service
+        .list_rules(Request::new(ListRulesRequest::default()))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(listed.rules[0].signed_by, "ci");
+}
+
+#[tokio::test]
+async fn test_bundle_imports_require_signature() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    service
+        .install_rules(Request::new(signed_request(&signing_key(), "agent-1", 1)))
+        .await
+        .unwrap();
+    let bundle = service
+        .export_rule_bundle(Request::new(ExportRuleBundleRequest::default()))
+        .await
+        .unwrap()
+        .into_inner()
+        .bundle;
+    bridge.clear_all().unwrap();
+
+    let import = ImportRuleBundleRequest {
+        bundle,
+        ..Default::default()
+    };
+    let err = service
+        .import_rule_bundle(Request::new(import.clone()))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    assert_eq!(bridge.rule_count(), 0);
+
+    let signed_command = command("ImportRuleBundle", import, 2);
+    let signed = ImportRuleBundleRequest {
+        signature: signature(&signing_key(), &signed_command),
+        signed_command,
+        ..Default::default()
+    };
+    let response = service
+        .import_rule_bundle(Request::new(signed))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.success, "{}", response.message);
+    assert_eq!(bridge.get_rule("rule-1").unwrap().signed_by(), Some("ci"));
+}
+
+#[tokio::test]
+async fn test_replayed_and_expired_batches_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    let request = signed_request(&signing_key(), "agent-1", 5);
+    service
+        .install_rules(Request::new(request.clone()))
+        .await
+        .unwrap();
+    bridge.remove_rule("rule-1").unwrap();
+
+    let err = service
+        .install_rules(Request::new(request))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    let err = service
+        .install_rules(Request::new(signed_request(&signing_key(), "agent-1", 4)))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let batch = SignedRuleBatch {
+        agent_id: "agent-1".to_string(),
+        rules: vec![rule("rule-1")],
+        sequence: 6,
+        expires_at_ms: now_ms() as i64 - 1,
+        ..Default::default()
+    }
+    .encode_to_vec();
+    let expired = InstallRulesRequest {
+        agent_id: "agent-1".to_string(),
+        signature: signature(&signing_key(), &batch),
+        signed_batch: batch,
+        ..Default::default()
+    };
+    let err = service
+        .install_rules(Request::new(expired))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    assert_eq!(bridge.rule_count(), 0);
+}
+
+#[tokio::test]
+async fn test_replay_is_rejected_after_restart() {
+    let dir = TempDir::new().unwrap();
+    log_to(&dir);
+    let request = signed_request(&signing_key(), "agent-1", 5);
+    {
+        let bridge = Arc::new(open_bridge(&dir));
+        trusting_service(Arc::clone(&bridge))
+            .install_rules(Request::new(request.clone()))
+            .await
+            .unwrap();
+        bridge.remove_rule("rule-1").unwrap();
+    }
+
+    let bridge = Arc::new(open_bridge(&dir));
+    let service = trusting_service(Arc::clone(&bridge));
+    let err = service
+        .install_rules(Request::new(request))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    assert_eq!(bridge.rule_count(), 0);
+
+    // Later sequences are still accepted.
+    service
+        .install_rules(Request::new(signed_request(&signing_key(), "agent-1", 6)))
+        .await
+        .unwrap();
+    assert_eq!(bridge.rule_count(), 1);
+}
+
+#[tokio::test]
+async fn test_install_options_come_from_signed_batch() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    let unanchored = RuleInstance {
+        anchors: None,
+        ..rule("rule-2")
+    };
+    let batch = SignedRuleBatch {
+        agent_id: "agent-1".to_string(),
+        rules: vec![rule("rule-1"), unanchored],
+        sequence: 1,
+        expires_at_ms: expires_at(),
+        atomic: true,
+        ..Default::default()
+    }
+    .encode_to_vec();
+    let request = InstallRulesRequest {
+        agent_id: "agent-1".to_string(),
+        atomic: false,
+        signature: signature(&signing_key(), &batch),
+        signed_batch: batch,
+        ..Default::default()
+    };
+
+    // Installed all-or-nothing as signed, not rule by rule as the outer request asks.
+    let response = service
+        .install_rules(Request::new(request))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.success);
+    assert_eq!(response.failures.len(), 1);
+    assert_eq!(bridge.rule_count(), 0);
+}
+
+#[tokio::test]
+async fn test_every_rule_change_requires_a_signed_command() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+    service
+        .install_rules(Request::new(signed_request(&signing_key(), "agent-1", 1)))
+        .await
+        .unwrap();
+
+    let disable = RuleStatusRequest {
+        rule_ids: vec!["rule-1".to_string()],
+        ..Default::default()
+    };
+    let remove = RemoveAgentRulesRequest {
+        agent_id: "agent-1".to_string(),
+        ..Default::default()
+    };
+    let codes = [
+        service
+            .disable_rules(Request::new(disable.clone()))
+            .await
+            .map(|_| ()),
+        service
+            .remove_agent_rules(Request::new(remove))
+            .await
+            .map(|_| ()),
+        service
+            .rollback_rules(Request::new(RollbackRulesRequest::default()))
+            .await
+            .map(|_| ()),
+        service
+            .promote_staged_rules(Request::new(PromoteStagedRulesRequest::default()))
+            .await
+            .map(|_| ()),
+        service
+            .set_agent_labels(Request::new(SetAgentLabelsRequest {
+                agent_id: "agent-1".to_string(),
+                labels: HashMap::from([("team".to_string(), "payments".to_string())]),
+                ..Default::default()
+            }))
+            .await
+            .map(|_| ()),
+        service
+            .reset_rate_limits(Request::new(ResetRateLimitsRequest::default()))
+            .await
+            .map(|_| ()),
+    ];
+    for result in codes {
+        assert_eq!(result.unwrap_err().code(), Code::Unauthenticated);
+    }
+    assert_eq!(bridge.rule_count(), 1);
+    assert!(bridge.agent_labels("", "agent-1").is_empty());
+
+    // A command is bound to the method it was signed for.
+    let signed_command = command("DisableRules", disable, 2);
+    let wrong_method = RuleStatusRequest {
+        signature: signature(&signing_key(), &signed_command),
+        signed_command: signed_command.clone(),
+        ..Default::default()
+    };
+    assert!(service
+        .enable_rules(Request::new(wrong_method.clone()))
+        .await
+        .is_err());
+    let response = service
+        .disable_rules(Request::new(wrong_method))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.rules_updated, 1);
+    assert_eq!(bridge.rule_count(), 0);
+
+    let signed_command = command(
+        "RollbackRules",
+        RollbackRulesRequest {
+            version: 1,
+            ..Default::default()
+        },
+        3,
+    );
+    let rollback = RollbackRulesRequest {
+        signature: signature(&signing_key(), &signed_command),
+        signed_command,
+        ..Default::default()
+    };
+    service
+        .rollback_rules(Request::new(rollback.clone()))
+        .await
+        .unwrap();
+    assert_eq!(bridge.rule_count(), 1);
+
+    // Replaying the rollback cannot restore the rule once it is disabled again.
+    let disable_again = command(
+        "DisableRules",
+        RuleStatusRequest {
+            rule_ids: vec!["rule-1".to_string()],
+            ..Default::default()
+        },
+        4,
+    );
+    service
+        .disable_rules(Request::new(RuleStatusRequest {
+            signature: signature(&signing_key(), &disable_again),
+            signed_command: disable_again,
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+    let err = service
+        .rollback_rules(Request::new(rollback))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    assert_eq!(bridge.rule_count(), 0);
+}
@@ -15,10 +15,11 @@ use std::time::Duration;
 
 /// Helper function to create a test RuleVector with distinguishable data.
 fn create_test_vector(id: usize) -> RuleVector {
-    let mut vector = RuleVector::default();
     // Set a unique value to distinguish vectors
-    vector.action_count = id;
-    vector
+    RuleVector {
+        action_count: id,
+        ..Default::default()
+    }
 }
 
 // ============================================================================
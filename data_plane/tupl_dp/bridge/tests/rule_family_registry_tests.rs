@@ -0,0 +1,230 @@
+//! Integration tests for the rule family registry.
+//!
+//! Tests verify:
+//! - Installs resolve the family from rule_type, or family_id without one
+//! - Unknown families and rules failing their family's validation are rejected
+//! - Stored rows of an unknown family, or failing their family's validation, are
+//!   quarantined on load
+//! - GetRuleStats reports rules per family and layer and the registered families
+
+mod common;
+
+use bridge::bridge::{Bridge, StorageConfig};
+use bridge::families::{family_ids, DesignBoundaryRule};
+use bridge::grpc_server::rule_installation::data_plane_server::DataPlane;
+use bridge::grpc_server::rule_installation::{
+    GetRuleStatsRequest, InstallRulesRequest, RuleInstance as ProtoRuleInstance,
+};
+use bridge::grpc_server::DataPlaneService;
+use bridge::rule_vector::RuleVector;
+use bridge::types::{RuleInstance, RuleScope};
+use common::{anchors_payload, log_to, string_param};
+use rusqlite::{params, Connection};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tonic::Request;
+
+fn service(dir: &TempDir) -> (Arc<Bridge>, DataPlaneService) {
+    log_to(dir);
+    let bridge = Arc::new(Bridge::in_memory().unwrap());
+    let service = DataPlaneService::new(Arc::clone(&bridge), "http://localhost:1".to_string());
+    (bridge, service)
+}
+
+fn proto_rule(rule_id: &str, params: &[(&str, &str)]) -> ProtoRuleInstance {
+    ProtoRuleInstance {
+        rule_id: rule_id.to_string(),
+        agent_id: "agent-1".to_string(),
+        layer: "L4".to_string(),
+        priority: 10,
+        enabled: true,
+        params: params
+            .iter()
+            .map(|(key, value)| (key.to_string(), string_param(value)))
+            .collect(),
+        anchors: Some(anchors_payload()),
+        ..Default::default()
+    }
+}
+
+async fn install(
+    service: &DataPlaneService,
+    rules: Vec<ProtoRuleInstance>,
+) -> bridge::grpc_server::rule_installation::InstallRulesResponse {
+    service
+        .install_rules(Request::new(InstallRulesRequest {
+            agent_id: "agent-1".to_string(),
+            rules,
+            atomic: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+#[tokio::test]
+async fn test_install_resolves_family() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+
+    let mut by_family_id = proto_rule("by-family-id", &[]);
+    by_family_id.family_id = "design_boundary".to_string();
+    let response = install(
+        &service,
+        vec![
+            proto_rule("by-rule-type", &[("rule_type", "design_boundary")]),
+            by_family_id,
+        ],
+    )
+    .await;
+    assert!(response.success, "{}", response.message);
+
+    let rule = bridge.get_rule("by-family-id").unwrap();
+    assert_eq!(rule.family_id(), "design_boundary");
+    assert_eq!(
+        rule.management_plane_payload()["rule_type"],
+        json!("design_boundary")
+    );
+}
+
+#[tokio::test]
+async fn test_install_rejects_unknown_and_invalid_rules() {
+    let dir = TempDir::new().unwrap();
+    let (bridge, service) = service(&dir);
+
+    let response = install(
+        &service,
+        vec![
+            proto_rule("unknown", &[("rule_type", "no_such_family")]),
+            proto_rule("untyped", &[]),
+            proto_rule(
+                "bad-decision",
+                &[("rule_type", "design_boundary"), ("rule_decision", "max")],
+            ),
+        ],
+    )
+    .await;
+    assert!(!response.success);
+
+    let stages: HashMap<String, String> = response
+        .failures
+        .into_iter()
+        .map(|failure| (failure.rule_id, failure.stage))
+        .collect();
+    assert_eq!(stages["unknown"], "rule_type");
+    assert_eq!(stages["untyped"], "rule_type");
+    assert_eq!(stages["bad-decision"], "conversion");
+    assert_eq!(bridge.rule_count(), 0);
+}
+
+#[test]
+fn test_unknown_or_invalid_family_rows_are_quarantined() {
+    let dir = TempDir::new().unwrap();
+    let config = StorageConfig {
+        warm_storage_path: dir.path().join("warm_storage.bin"),
+        cold_storage_path: dir.path().join("cold_storage.db"),
+    };
+    let bridge = Bridge::new(config.clone()).unwrap();
+    for rule_id in ["rule-1", "rule-2", "rule-3"] {
+        let rule: Arc<dyn RuleInstance> = Arc::new(DesignBoundaryRule::new(
+            rule_id.to_string(),
+            10,
+            RuleScope::global(),
+            Some("L4".to_string()),
+            1_700_000_000_000,
+            true,
+            None,
+            json!({"rule_type": "design_boundary"}),
+        ));
+        bridge
+            .add_rule_with_anchors(rule, RuleVector::default())
+            .unwrap();
+    }
+    drop(bridge);
+
+    // A row written by a binary that knows a family this one does not.
+    let conn = Connection::open(&config.cold_storage_path).unwrap();
+    conn.execute(
+        "UPDATE rules SET rule_json = json_set(rule_json, '$.params.rule_type', 'future_family')
+         WHERE id = ?1",
+        params!["rule-1"],
+    )
+    .unwrap();
+    // A row its family no longer accepts would otherwise load as a rule that never matches.
+    conn.execute(
+        "UPDATE rules SET rule_json = json_set(rule_json, '$.params.rule_decision', 'max')
+         WHERE id = ?1",
+        params!["rule-2"],
+    )
+    .unwrap();
+
+    let bridge = Bridge::new(config).unwrap();
+    assert_eq!(bridge.quarantined_on_load(), ["rule-1", "rule-2"]);
+    let quarantined = bridge.list_quarantined().unwrap();
+    assert!(
+        quarantined[0].reason.contains("future_family"),
+        "{}",
+        quarantined[0].reason
+    );
+    assert!(
+        quarantined[1].reason.contains("rule_decision"),
+        "{}",
+        quarantined[1].reason
+    );
+    assert!(bridge.get_rule("rule-3").is_some());
+}
+
+#[tokio::test]
+async fn test_rule_stats_report_families() {
+    let dir = TempDir::new().unwrap();
+    let (_bridge, service) = service(&dir);
+
+    let mut global = proto_rule("global", &[("rule_type", "design_boundary")]);
+    // Without an agent, only a tagged rule is global.
+    global.agent_id.clear();
+    global.tags.insert("env".to_string(), "prod".to_string());
+    let mut layerless = proto_rule("layerless", &[("rule_type", "design_boundary")]);
+    layerless.layer.clear();
+    let response = install(
+        &service,
+        vec![
+            proto_rule("scoped", &[("rule_type", "design_boundary")]),
+            global,
+            layerless,
+        ],
+    )
+    .await;
+    assert!(response.success, "{}", response.message);
+
+    let stats = service
+        .get_rule_stats(Request::new(GetRuleStatsRequest {}))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(stats.registered_families, family_ids());
+    assert_eq!(stats.total_tables, 2);
+
+    let tables: Vec<(&str, &str, i32, i32, i32)> = stats
+        .table_stats
+        .iter()
+        .map(|table| {
+            (
+                table.family_id.as_str(),
+                table.layer_id.as_str(),
+                table.rule_count,
+                table.global_count,
+                table.scoped_count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        tables,
+        vec![
+            ("design_boundary", "", 1, 0, 1),
+            ("design_boundary", "L4", 2, 1, 1),
+        ]
+    );
+}